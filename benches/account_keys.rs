@@ -0,0 +1,92 @@
+//! Account list construction on the per-transaction hot path
+//!
+//! `borrowed` is what process_transaction does: build the account list over
+//! the message's keys and resolve every instruction's accounts through it.
+//! `copied` is the same work over a freshly collected `Vec<Address>`, as the
+//! account list was built before it borrowed.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use solana_address::Address;
+use solana_hash::Hash;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::v0::{self, MessageAddressTableLookup};
+use solana_message::{MessageHeader, VersionedMessage};
+use solixdb_indexer::multi_parser::build_full_account_list;
+
+const STATIC_KEYS: u8 = 24;
+const LOADED_WRITABLE: u8 = 12;
+const LOADED_READONLY: u8 = 12;
+
+fn address(byte: u8) -> Address {
+    Address::from([byte; 32])
+}
+
+/// A V0 swap-sized transaction: 24 static keys, 24 loaded addresses and four
+/// instructions touching 16 accounts each
+fn v0_message() -> (VersionedMessage, Vec<Address>, Vec<Address>) {
+    let total = STATIC_KEYS + LOADED_WRITABLE + LOADED_READONLY;
+    let instructions = (0..4)
+        .map(|i| CompiledInstruction {
+            program_id_index: STATIC_KEYS - 1 - i,
+            accounts: (0..16).map(|a| (a * 3 + i) % total).collect(),
+            data: vec![0; 24],
+        })
+        .collect();
+    let message = v0::Message {
+        header: MessageHeader {
+            num_required_signatures: 1,
+            num_readonly_signed_accounts: 0,
+            num_readonly_unsigned_accounts: 4,
+        },
+        account_keys: (0..STATIC_KEYS).map(address).collect(),
+        recent_blockhash: Hash::default(),
+        instructions,
+        address_table_lookups: vec![MessageAddressTableLookup {
+            account_key: address(255),
+            writable_indexes: (0..LOADED_WRITABLE).collect(),
+            readonly_indexes: (0..LOADED_READONLY).collect(),
+        }],
+    };
+    let loaded_writable = (STATIC_KEYS..STATIC_KEYS + LOADED_WRITABLE).map(address).collect();
+    let loaded_readonly = (STATIC_KEYS + LOADED_WRITABLE..total).map(address).collect();
+    (VersionedMessage::V0(message), loaded_writable, loaded_readonly)
+}
+
+fn resolve_all(get: impl Fn(usize) -> Option<Address>, message: &VersionedMessage) -> usize {
+    message
+        .instructions()
+        .iter()
+        .flat_map(|ix| ix.accounts.iter().chain([&ix.program_id_index]))
+        .filter_map(|idx| get(*idx as usize))
+        .count()
+}
+
+fn account_list(c: &mut Criterion) {
+    let (message, loaded_writable, loaded_readonly) = v0_message();
+    let mut group = c.benchmark_group("account_list");
+
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            let keys = build_full_account_list(black_box(&message), &loaded_writable, &loaded_readonly);
+            resolve_all(|i| keys.get(i).copied(), &message)
+        })
+    });
+
+    group.bench_function("copied", |b| {
+        b.iter(|| {
+            let keys: Vec<Address> = message
+                .static_account_keys()
+                .iter()
+                .chain(&loaded_writable)
+                .chain(&loaded_readonly)
+                .copied()
+                .collect();
+            resolve_all(|i| keys.get(i).copied(), black_box(&message))
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, account_list);
+criterion_main!(benches);
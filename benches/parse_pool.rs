@@ -0,0 +1,93 @@
+//! Instruction decoding inline on the runtime vs on the parse pool
+//!
+//! Each iteration decodes `INSTRUCTIONS` pump_fun sells from `TASKS`
+//! concurrent tasks on a multi-thread runtime, as process_transaction does
+//! per firehose thread. `inline` decodes on the tokio workers; `pool` hands
+//! each decode to a `ParsePool` with one thread per core. Alongside, a ticker
+//! task measures how late a 1 ms timer fires, standing in for ClickHouse I/O
+//! waiting behind parsing; its worst lateness is printed per variant.
+//! Compare on the machine the indexer runs on (core count matters).
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use solixdb_indexer::multi_parser::try_parse_candidates;
+use solixdb_indexer::parse_pool::ParsePool;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use yellowstone_vixen_core::instruction::InstructionUpdate;
+
+const PUMP_FUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const SELL_DISCRIMINATOR: [u8; 8] = [0x33, 0xe6, 0x85, 0xa4, 0x01, 0x7f, 0x83, 0xad];
+const TASKS: usize = 64;
+const INSTRUCTIONS: usize = 20_000;
+
+fn sell_update() -> InstructionUpdate {
+    let program = solixdb_indexer::alt::parse_address(PUMP_FUN_PROGRAM_ID).expect("valid program id");
+    let mut data = SELL_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&1_000_000u64.to_le_bytes());
+    data.extend_from_slice(&5_000u64.to_le_bytes());
+    InstructionUpdate {
+        program: program.to_bytes().into(),
+        data,
+        accounts: (0..14u8).map(|i| [i; 32].into()).collect(),
+        shared: Default::default(),
+        inner: vec![],
+    }
+}
+
+/// Decode INSTRUCTIONS sells from TASKS tasks, returning the worst timer lateness seen meanwhile
+async fn decode_all(pool: Option<Arc<ParsePool>>) -> Duration {
+    let worst_lateness = Arc::new(AtomicU64::new(0));
+    let ticker = {
+        let worst_lateness = Arc::clone(&worst_lateness);
+        tokio::spawn(async move {
+            loop {
+                let start = Instant::now();
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                let late = start.elapsed().saturating_sub(Duration::from_millis(1));
+                worst_lateness.fetch_max(late.as_micros() as u64, Ordering::Relaxed);
+            }
+        })
+    };
+    let tasks: Vec<_> = (0..TASKS)
+        .map(|_| {
+            let pool = pool.clone();
+            tokio::spawn(async move {
+                for _ in 0..INSTRUCTIONS / TASKS {
+                    let update = sell_update();
+                    let result = match &pool {
+                        Some(pool) => pool.parse(update, vec!["pump_fun"]).await.1,
+                        None => try_parse_candidates(&update, &["pump_fun"]).await,
+                    };
+                    assert!(result.is_ok());
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+    ticker.abort();
+    Duration::from_micros(worst_lateness.load(Ordering::Relaxed))
+}
+
+fn parse_pool(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let pool = Arc::new(runtime.block_on(async { ParsePool::new(0, 1024).unwrap() }));
+
+    let mut group = c.benchmark_group("parse_pool");
+    group.sample_size(10);
+
+    for (name, pool) in [("inline", None), ("pool", Some(pool))] {
+        let mut worst = Duration::ZERO;
+        group.bench_function(name, |b| {
+            b.iter(|| worst = worst.max(runtime.block_on(decode_all(pool.clone()))))
+        });
+        println!("{}: worst 1 ms timer lateness {:?}", name, worst);
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, parse_pool);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+//! Address Lookup Table (ALT) program decoder
+//!
+//! The ALT program is a native program with bincode-encoded instructions, so it
+//! can't be generated from a Codama IDL like the other parsers.
+
+use crate::Pubkey;
+
+pub const ALT_PROGRAM_ID: &str = "AddressLookupTab1e1111111111111111111111111";
+
+/// Decoded Address Lookup Table program instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AltInstruction {
+    CreateLookupTable { recent_slot: u64, bump_seed: u8 },
+    FreezeLookupTable,
+    ExtendLookupTable { new_addresses: Vec<Pubkey> },
+    DeactivateLookupTable,
+    CloseLookupTable,
+}
+
+impl AltInstruction {
+    /// Decode bincode instruction data (u32 LE variant tag followed by fields)
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        let tag = read_u32(data, 0).ok_or("ALT instruction data too short")?;
+        match tag {
+            0 => {
+                let recent_slot = read_u64(data, 4).ok_or("CreateLookupTable: missing recent_slot")?;
+                let bump_seed = *data.get(12).ok_or("CreateLookupTable: missing bump_seed")?;
+                Ok(Self::CreateLookupTable { recent_slot, bump_seed })
+            }
+            1 => Ok(Self::FreezeLookupTable),
+            2 => {
+                let len = read_u64(data, 4).ok_or("ExtendLookupTable: missing length")? as usize;
+                let body = data.get(12..).unwrap_or_default();
+                if len > 256 || body.len() < len * 32 {
+                    return Err(format!("ExtendLookupTable: invalid address count {}", len));
+                }
+                let new_addresses = body
+                    .chunks_exact(32)
+                    .take(len)
+                    .map(|chunk| {
+                        let bytes: [u8; 32] = chunk.try_into().unwrap_or([0u8; 32]);
+                        Pubkey::from(bytes)
+                    })
+                    .collect();
+                Ok(Self::ExtendLookupTable { new_addresses })
+            }
+            3 => Ok(Self::DeactivateLookupTable),
+            4 => Ok(Self::CloseLookupTable),
+            _ => Err(format!("Unknown ALT instruction tag {}", tag)),
+        }
+    }
+
+    /// Short action name stored in alt_updates.action
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::CreateLookupTable { .. } => "create",
+            Self::FreezeLookupTable => "freeze",
+            Self::ExtendLookupTable { .. } => "extend",
+            Self::DeactivateLookupTable => "deactivate",
+            Self::CloseLookupTable => "close",
+        }
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap_or_default()))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap_or_default()))
+}
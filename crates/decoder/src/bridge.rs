@@ -0,0 +1,192 @@
+//! Cross-chain bridge events for Wormhole and deBridge DLN
+//!
+//! Normalizes outbound transfers, inbound redemptions and raw Wormhole
+//! messages into one shape. Chains are named from each protocol's own chain
+//! ids (Wormhole's u16 ids, deBridge's EVM-style ids); unknown ids are kept as
+//! `wormhole:<id>` / `debridge:<id>`. Foreign addresses are `0x` hex (with the
+//! 12-byte left padding of 20-byte EVM addresses dropped), Solana addresses
+//! base58.
+//!
+//! Redemptions (Wormhole `completeNative`/`completeWrapped`) carry the amount
+//! and source chain in the posted VAA, not the instruction, so they are left at
+//! 0 and empty. deBridge orders are only seen on the Solana source side.
+
+use crate::{ParsedInstruction, Pubkey};
+
+pub const SOLANA: &str = "solana";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BridgeAction {
+    /// Tokens sent from Solana
+    TransferOut,
+    /// Tokens redeemed on Solana
+    TransferIn,
+    /// Raw Wormhole message (no token movement)
+    Message,
+}
+
+impl BridgeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TransferOut => "transfer_out",
+            Self::TransferIn => "transfer_in",
+            Self::Message => "message",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeEvent {
+    pub action: BridgeAction,
+    /// Solana mint sent or received (None for messages)
+    pub token: Option<Pubkey>,
+    /// Raw token amount (0 where the instruction doesn't carry it)
+    pub amount: u64,
+    /// Empty where unknown
+    pub source_chain: String,
+    /// Empty where unknown (messages are broadcast)
+    pub target_chain: String,
+    /// Solana signer (or emitter for messages)
+    pub sender: Option<Pubkey>,
+    /// Destination address, in the target chain's format
+    pub recipient: String,
+}
+
+/// Chain name for a Wormhole chain id
+pub fn wormhole_chain(id: u16) -> String {
+    let name = match id {
+        1 => SOLANA,
+        2 => "ethereum",
+        4 => "bsc",
+        5 => "polygon",
+        6 => "avalanche",
+        10 => "fantom",
+        21 => "sui",
+        22 => "aptos",
+        23 => "arbitrum",
+        24 => "optimism",
+        30 => "base",
+        _ => return format!("wormhole:{}", id),
+    };
+    name.to_string()
+}
+
+/// Chain name for a deBridge chain id
+pub fn debridge_chain(id: u64) -> String {
+    let name = match id {
+        1 => "ethereum",
+        10 => "optimism",
+        56 => "bsc",
+        137 => "polygon",
+        8453 => "base",
+        42161 => "arbitrum",
+        43114 => "avalanche",
+        59144 => "linea",
+        7565164 => SOLANA,
+        _ => return format!("debridge:{}", id),
+    };
+    name.to_string()
+}
+
+/// Format a foreign address: base58 for Solana, otherwise 0x hex
+pub(crate) fn format_address(bytes: &[u8], chain: &str) -> String {
+    if chain == SOLANA && bytes.len() == 32 {
+        return bs58::encode(bytes).into_string();
+    }
+    // 20-byte EVM addresses are left-padded to 32 bytes in Wormhole payloads
+    let bytes = match bytes.len() {
+        32 if bytes[..12].iter().all(|b| *b == 0) => &bytes[12..],
+        _ => bytes,
+    };
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", hex)
+}
+
+/// A big-endian 256-bit chain id, if it fits in a u64
+pub(crate) fn chain_id_u64(bytes: &[u8; 32]) -> Option<u64> {
+    if bytes[..24].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Some(u64::from_be_bytes(bytes[24..].try_into().ok()?))
+}
+
+/// Extract a bridge event, if the instruction sends, redeems or publishes a cross-chain message
+pub fn bridge_event(parsed: &ParsedInstruction) -> Option<BridgeEvent> {
+    match parsed {
+        #[cfg(feature = "wormhole")]
+        ParsedInstruction::WormholeCore(ix) => {
+            use crate::wormhole_core::WormholeInstruction as Ix;
+            let emitter = match ix {
+                Ix::PostMessage { accounts: a, .. } => a.emitter,
+                Ix::PostMessageUnreliable { accounts: a, .. } => a.emitter,
+            };
+            Some(BridgeEvent {
+                action: BridgeAction::Message,
+                token: None,
+                amount: 0,
+                source_chain: SOLANA.to_string(),
+                target_chain: String::new(),
+                sender: Some(emitter),
+                recipient: String::new(),
+            })
+        }
+        #[cfg(feature = "wormhole")]
+        ParsedInstruction::WormholeTokenBridge(ix) => {
+            use crate::wormhole_token_bridge::TokenBridgeInstruction as Ix;
+            let transfer_out = |mint: Pubkey, sender: Pubkey, amount: u64, chain: u16, address: &[u8; 32]| {
+                let target_chain = wormhole_chain(chain);
+                BridgeEvent {
+                    action: BridgeAction::TransferOut,
+                    token: Some(mint),
+                    amount,
+                    source_chain: SOLANA.to_string(),
+                    recipient: format_address(address, &target_chain),
+                    target_chain,
+                    sender: Some(sender),
+                }
+            };
+            let transfer_in = |mint: Pubkey, to: Pubkey| BridgeEvent {
+                action: BridgeAction::TransferIn,
+                token: Some(mint),
+                amount: 0,
+                source_chain: String::new(),
+                target_chain: SOLANA.to_string(),
+                sender: None,
+                recipient: to.to_string(),
+            };
+            let event = match ix {
+                Ix::TransferNative { accounts: a, args } => {
+                    transfer_out(a.mint, a.payer, args.amount, args.target_chain, &args.target_address)
+                }
+                Ix::TransferWrapped { accounts: a, args } => {
+                    transfer_out(a.mint, a.from_owner, args.amount, args.target_chain, &args.target_address)
+                }
+                Ix::CompleteNative { accounts: a, .. } => transfer_in(a.mint, a.to),
+                Ix::CompleteWrapped { accounts: a, .. } => transfer_in(a.mint, a.to),
+            };
+            Some(event)
+        }
+        #[cfg(feature = "debridge")]
+        ParsedInstruction::DebridgeDln(ix) => {
+            use crate::debridge_dln::DlnSourceInstruction as Ix;
+            match ix {
+                Ix::CreateOrderWithNonce { accounts: a, args } => {
+                    let target_chain = chain_id_u64(&args.take.chain_id)
+                        .map(debridge_chain)
+                        .unwrap_or_default();
+                    Some(BridgeEvent {
+                        action: BridgeAction::TransferOut,
+                        token: Some(a.token_mint),
+                        amount: args.give_original_amount,
+                        source_chain: SOLANA.to_string(),
+                        recipient: format_address(&args.receiver_dst, &target_chain),
+                        target_chain,
+                        sender: Some(a.maker),
+                    })
+                }
+            }
+        }
+        _ => None,
+    }
+}
+
@@ -0,0 +1,132 @@
+//! Pool state around CLMM swaps, from the events the programs log
+//!
+//! Swap instructions only carry a price limit, so the fee tier, ticks and
+//! liquidity a swap actually ran at come from the Anchor events logged with
+//! it (`Program data:` payloads, an 8-byte discriminator then the fields):
+//!
+//! - Raydium CLMM `SwapEvent`: sqrt price, active liquidity and tick after
+//!   the swap. No fee or state before the swap.
+//! - Orca Whirlpool `Traded`: sqrt price before and after the swap and the
+//!   fees charged, from which the fee rate is derived. No liquidity.
+//!
+//! Ticks derived from a sqrt price use `tick = floor(log_1.0001(price))` in
+//! floating point, which can be one off right at a tick boundary.
+
+use crate::Pubkey;
+
+/// `sha256("event:SwapEvent")[..8]`
+const RAYDIUM_SWAP_EVENT: [u8; 8] = [64, 198, 205, 232, 38, 8, 113, 226];
+/// `sha256("event:Traded")[..8]`
+const WHIRLPOOL_TRADED: [u8; 8] = [225, 202, 73, 175, 147, 43, 160, 150];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClmmSwapState {
+    pub pool: Pubkey,
+    /// Total fee in hundredths of a basis point (3000 = 0.3%)
+    pub fee_rate: Option<u32>,
+    pub tick_before: Option<i32>,
+    pub tick_after: Option<i32>,
+    /// Active liquidity after the swap
+    pub liquidity: Option<u128>,
+}
+
+/// Decode a `Program data:` payload (base64-decoded) if it is a CLMM swap event
+pub fn clmm_swap_state(data: &[u8]) -> Option<ClmmSwapState> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (discriminator, body) = data.split_at(8);
+    if discriminator == RAYDIUM_SWAP_EVENT {
+        // pool_state, sender, token_account_0, token_account_1, amount_0,
+        // transfer_fee_0, amount_1, transfer_fee_1, zero_for_one, sqrt_price_x64, liquidity, tick
+        let offset = 4 * 32 + 4 * 8 + 1;
+        return Some(ClmmSwapState {
+            pool: read_pubkey(body, 0)?,
+            fee_rate: None,
+            tick_before: None,
+            tick_after: Some(read_i32(body, offset + 32)?),
+            liquidity: Some(read_u128(body, offset + 16)?),
+        });
+    }
+    if discriminator == WHIRLPOOL_TRADED {
+        // whirlpool, a_to_b, pre_sqrt_price, post_sqrt_price, input_amount, output_amount,
+        // input_transfer_fee, output_transfer_fee, lp_fee, protocol_fee
+        let pre_sqrt_price = read_u128(body, 33)?;
+        let post_sqrt_price = read_u128(body, 49)?;
+        let input_amount = read_u64(body, 65)?;
+        let input_transfer_fee = read_u64(body, 81)?;
+        let fee = read_u64(body, 97)?.saturating_add(read_u64(body, 105)?);
+        let swapped = input_amount.saturating_sub(input_transfer_fee);
+        return Some(ClmmSwapState {
+            pool: read_pubkey(body, 0)?,
+            fee_rate: (swapped > 0).then(|| ((fee as u128 * 1_000_000 + swapped as u128 / 2) / swapped as u128) as u32),
+            tick_before: tick_at(pre_sqrt_price),
+            tick_after: tick_at(post_sqrt_price),
+            liquidity: None,
+        });
+    }
+    None
+}
+
+/// Tick of a Q64.64 sqrt price
+fn tick_at(sqrt_price_x64: u128) -> Option<i32> {
+    if sqrt_price_x64 == 0 {
+        return None;
+    }
+    let sqrt_price = sqrt_price_x64 as f64 / 2f64.powi(64);
+    Some((2.0 * sqrt_price.ln() / 1.0001f64.ln()).floor() as i32)
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    Some(Pubkey::from(bytes))
+}
+
+fn read_i32(data: &[u8], offset: usize) -> Option<i32> {
+    Some(i32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Option<u128> {
+    Some(u128::from_le_bytes(data.get(offset..offset + 16)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clmm_swap_state_from_events() {
+        // Whirlpool Traded: price 1.0 -> 0.998 (sqrt 0.999), 0.3% fee split 90/10
+        let mut traded = vec![225, 202, 73, 175, 147, 43, 160, 150];
+        traded.extend_from_slice(&[7; 32]);
+        traded.push(1);
+        traded.extend_from_slice(&(1u128 << 64).to_le_bytes());
+        traded.extend_from_slice(&(((1u128 << 64) as f64 * 0.999) as u128).to_le_bytes());
+        for amount in [1_000_000u64, 996_000, 0, 0, 2_700, 300] {
+            traded.extend_from_slice(&amount.to_le_bytes());
+        }
+        let state = clmm_swap_state(&traded).unwrap();
+        assert_eq!(state.pool, Pubkey::from([7; 32]));
+        assert_eq!(state.fee_rate, Some(3_000));
+        assert_eq!((state.tick_before, state.tick_after), (Some(0), Some(-21)));
+        assert_eq!(state.liquidity, None);
+
+        // Raydium CLMM SwapEvent: state after the swap only
+        let mut swap_event = vec![64, 198, 205, 232, 38, 8, 113, 226];
+        swap_event.extend_from_slice(&[7; 4 * 32 + 4 * 8]);
+        swap_event.push(0);
+        swap_event.extend_from_slice(&(1u128 << 64).to_le_bytes());
+        swap_event.extend_from_slice(&5_000_000_000u128.to_le_bytes());
+        swap_event.extend_from_slice(&(-1234i32).to_le_bytes());
+        let state = clmm_swap_state(&swap_event).unwrap();
+        assert_eq!((state.tick_before, state.tick_after), (None, Some(-1234)));
+        assert_eq!(state.liquidity, Some(5_000_000_000));
+        assert_eq!(state.fee_rate, None);
+
+        assert!(clmm_swap_state(&traded[..40]).is_none());
+    }
+}
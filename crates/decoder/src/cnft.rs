@@ -0,0 +1,142 @@
+//! Compressed NFT (Bubblegum) asset ids
+//!
+//! A compressed NFT's asset id is the PDA `["asset", merkle_tree, nonce_le]`
+//! under the Bubblegum program, which is what DAS APIs key assets by.
+//! Transfer/burn/delegate/redeem carry the leaf nonce in their args. Mints
+//! don't: the nonce (and the asset id itself) is only in the `LeafSchemaEvent`
+//! Bubblegum logs through an spl-noop CPI, so mints need the inner
+//! instructions.
+
+#[cfg(feature = "bubblegum")]
+use crate::ParsedInstruction;
+use crate::Pubkey;
+use solana_address::Address;
+
+pub const BUBBLEGUM_PROGRAM_ID: &str = "BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY";
+pub const NOOP_PROGRAM_ID: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
+
+/// Derive a compressed NFT's asset id from its tree and leaf nonce
+pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    let program = Address::from(program_id_bytes());
+    let (address, _bump) = Address::find_program_address(
+        &[b"asset", merkle_tree.0.as_slice(), &nonce.to_le_bytes()],
+        &program,
+    );
+    Pubkey::from(address.to_bytes())
+}
+
+fn program_id_bytes() -> [u8; 32] {
+    bs58::decode(BUBBLEGUM_PROGRAM_ID)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .expect("valid Bubblegum program id")
+}
+
+/// Leaf fields from a Bubblegum `LeafSchemaEvent` (V1 schema)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeafSchema {
+    pub asset_id: Pubkey,
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub nonce: u64,
+}
+
+/// Decode the data of an spl-noop CPI made by Bubblegum
+///
+/// Layout: `AccountCompressionEvent::ApplicationData` (1) / `ApplicationDataEvent::V1` (0),
+/// u32 length, then `LeafSchemaEvent { event_type: LeafSchemaEvent (1), version: V1 (0),
+/// schema: LeafSchema::V1 (0) { id, owner, delegate, nonce, data_hash, creator_hash }, leaf_hash }`.
+/// Returns None for other noop payloads (e.g. account compression change logs).
+pub fn decode_leaf_schema_event(data: &[u8]) -> Option<LeafSchema> {
+    if data.get(..2)? != [1, 0] {
+        return None;
+    }
+    let len = u32::from_le_bytes(data.get(2..6)?.try_into().ok()?) as usize;
+    let event = data.get(6..6 + len)?;
+    if event.get(..3)? != [1, 0, 0] {
+        return None;
+    }
+    let key = |offset: usize| -> Option<Pubkey> {
+        let bytes: [u8; 32] = event.get(offset..offset + 32)?.try_into().ok()?;
+        Some(Pubkey::from(bytes))
+    };
+    Some(LeafSchema {
+        asset_id: key(3)?,
+        owner: key(35)?,
+        delegate: key(67)?,
+        nonce: u64::from_le_bytes(event.get(99..107)?.try_into().ok()?),
+    })
+}
+
+/// A compressed NFT lifecycle event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedAssetEvent {
+    /// mint / transfer / burn / delegate / redeem
+    pub action: &'static str,
+    pub merkle_tree: Pubkey,
+    /// Leaf owner before the instruction
+    pub owner: Pubkey,
+    /// New owner (transfer) or delegate (delegate); empty otherwise
+    pub counterparty: Option<Pubkey>,
+    /// Leaf nonce, if known from the instruction args
+    pub nonce: Option<u64>,
+}
+
+/// Extract a compressed NFT event from a parsed Bubblegum instruction
+///
+/// For mints `nonce` is None; pair with [`decode_leaf_schema_event`] on the
+/// instruction's noop CPI to get the nonce and asset id.
+#[cfg(feature = "bubblegum")]
+pub fn compressed_asset_event(parsed: &ParsedInstruction) -> Option<CompressedAssetEvent> {
+    use crate::bubblegum::BubblegumInstruction as Ix;
+
+    let ParsedInstruction::Bubblegum(ix) = parsed else {
+        return None;
+    };
+    let event = match ix {
+        Ix::MintV1 { accounts: a, .. } => CompressedAssetEvent {
+            action: "mint",
+            merkle_tree: a.merkle_tree,
+            owner: a.leaf_owner,
+            counterparty: None,
+            nonce: None,
+        },
+        Ix::MintToCollectionV1 { accounts: a, .. } => CompressedAssetEvent {
+            action: "mint",
+            merkle_tree: a.merkle_tree,
+            owner: a.leaf_owner,
+            counterparty: None,
+            nonce: None,
+        },
+        Ix::Transfer { accounts: a, args } => CompressedAssetEvent {
+            action: "transfer",
+            merkle_tree: a.merkle_tree,
+            owner: a.leaf_owner,
+            counterparty: Some(a.new_leaf_owner),
+            nonce: Some(args.nonce),
+        },
+        Ix::Burn { accounts: a, args } => CompressedAssetEvent {
+            action: "burn",
+            merkle_tree: a.merkle_tree,
+            owner: a.leaf_owner,
+            counterparty: None,
+            nonce: Some(args.nonce),
+        },
+        Ix::Delegate { accounts: a, args } => CompressedAssetEvent {
+            action: "delegate",
+            merkle_tree: a.merkle_tree,
+            owner: a.leaf_owner,
+            counterparty: Some(a.new_leaf_delegate),
+            nonce: Some(args.nonce),
+        },
+        Ix::Redeem { accounts: a, args } => CompressedAssetEvent {
+            action: "redeem",
+            merkle_tree: a.merkle_tree,
+            owner: a.leaf_owner,
+            counterparty: None,
+            nonce: Some(args.nonce),
+        },
+    };
+    Some(event)
+}
@@ -0,0 +1,120 @@
+//! Token creator attribution for launchpads (pump.fun, Meteora DBC)
+//!
+//! Pulls the creator, the deploying wallet and buys out of pump.fun
+//! `create`/`buy`/`buy_exact_sol_in` instructions and Meteora DBC
+//! `initialize_virtual_pool_*`/`swap` instructions. Which buys were "first"
+//! is cross-transaction state, so that's left to the caller.
+
+use crate::{ParsedInstruction, Pubkey};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenCreation {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    /// Creator named in the instruction (receives creator fees)
+    pub creator: Pubkey,
+    /// Wallet that signed and paid for the create
+    pub deployer: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Partner config the curve was created under (Meteora DBC)
+    pub config: Option<Pubkey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBuy {
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    /// Tokens requested (`buy`) or the minimum accepted (`buy_exact_sol_in`)
+    pub token_amount: u64,
+    /// Max SOL cost (`buy`) or exact SOL spent (`buy_exact_sol_in`), in lamports
+    pub sol_amount: u64,
+    /// Partner config of the curve (Meteora DBC)
+    pub config: Option<Pubkey>,
+}
+
+/// Extract a token creation, if the instruction launches a token
+pub fn token_creation(parsed: &ParsedInstruction) -> Option<TokenCreation> {
+    #[cfg(feature = "pumpfun")]
+    use crate::pump_fun::Pump_funInstruction as Ix;
+
+    match parsed {
+        #[cfg(feature = "pumpfun")]
+        ParsedInstruction::PumpFun(Ix::Create { accounts: a, args }) => Some(TokenCreation {
+            mint: a.mint,
+            bonding_curve: a.bonding_curve,
+            creator: args.creator,
+            deployer: a.user,
+            name: args.name.clone(),
+            symbol: args.symbol.clone(),
+            uri: args.uri.clone(),
+            config: None,
+        }),
+        #[cfg(feature = "meteora")]
+        ParsedInstruction::MeteoraDbc(ix) => {
+            use crate::meteora_dbc::DynamicBondingCurveInstruction as Dbc;
+            match ix {
+                Dbc::InitializeVirtualPoolWithSplToken { accounts: a, args } => Some(TokenCreation {
+                    mint: a.base_mint,
+                    bonding_curve: a.pool,
+                    creator: a.creator,
+                    deployer: a.payer,
+                    name: args.name.clone(),
+                    symbol: args.symbol.clone(),
+                    uri: args.uri.clone(),
+                    config: Some(a.config),
+                }),
+                Dbc::InitializeVirtualPoolWithToken2022 { accounts: a, args } => Some(TokenCreation {
+                    mint: a.base_mint,
+                    bonding_curve: a.pool,
+                    creator: a.creator,
+                    deployer: a.payer,
+                    name: args.name.clone(),
+                    symbol: args.symbol.clone(),
+                    uri: args.uri.clone(),
+                    config: Some(a.config),
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Extract a bonding curve buy
+pub fn token_buy(parsed: &ParsedInstruction) -> Option<TokenBuy> {
+    #[cfg(feature = "pumpfun")]
+    use crate::pump_fun::Pump_funInstruction as Ix;
+
+    match parsed {
+        #[cfg(feature = "pumpfun")]
+        ParsedInstruction::PumpFun(Ix::Buy { accounts: a, args }) => Some(TokenBuy {
+            mint: a.mint,
+            buyer: a.user,
+            token_amount: args.amount,
+            sol_amount: args.max_sol_cost,
+            config: None,
+        }),
+        #[cfg(feature = "pumpfun")]
+        ParsedInstruction::PumpFun(Ix::BuyExactSolIn { accounts: a, args }) => Some(TokenBuy {
+            mint: a.mint,
+            buyer: a.user,
+            token_amount: args.min_tokens_out,
+            sol_amount: args.spendable_sol_in,
+            config: None,
+        }),
+        // Swaps don't say which way they go; trade_event works it out
+        #[cfg(feature = "meteora")]
+        ParsedInstruction::MeteoraDbc(_) => crate::events::trade_event(parsed)
+            .filter(|trade| trade.side == crate::events::TradeSide::Buy)
+            .map(|trade| TokenBuy {
+                mint: trade.mint,
+                buyer: trade.user,
+                token_amount: trade.token_amount,
+                sol_amount: trade.sol_amount,
+                config: trade.config,
+            }),
+        _ => None,
+    }
+}
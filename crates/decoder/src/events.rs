@@ -0,0 +1,609 @@
+//! Typed swap, bonding curve trade and pool liquidity events
+//!
+//! Normalizes the trading instructions of each protocol into one shape per
+//! event kind. Amounts are the instruction's values, not the amounts actually
+//! transferred: one side is the exact amount the user specified and the other
+//! is its slippage bound (`exact_in` says which). Mints are `None` where the
+//! instruction doesn't reference them (Whirlpool and Raydium CLMM v1 swaps,
+//! Jupiter `route`, Raydium Stable and Saber stable swaps).
+//!
+//! PumpSwap (Pump AMM) swaps of SOL-paired pools are also trade events, on the
+//! `Amm` venue, so a pump.fun token's bonding curve trading and its trading
+//! after migration read as one series per mint.
+//!
+//! CLMM/DLMM position changes are covered by [`crate::positions`]; liquidity
+//! events here are full-range pool deposits and withdrawals.
+
+use crate::{ParsedInstruction, Pubkey};
+use solana_address::Address;
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// Wrapped SOL mint (So11111111111111111111111111111111111111112)
+#[cfg(any(feature = "pumpfun", feature = "meteora"))]
+const WSOL_MINT: [u8; 32] = [
+    6, 155, 136, 87, 254, 171, 129, 132, 251, 104, 127, 99, 70, 24, 192, 53, 218, 196, 57, 220, 26, 235, 59, 85, 152,
+    160, 240, 0, 0, 0, 0, 1,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapEvent {
+    /// Pool swapped against (`None` for aggregator routes)
+    pub pool: Option<Pubkey>,
+    /// Signer whose tokens are swapped
+    pub user: Pubkey,
+    pub mint_in: Option<Pubkey>,
+    pub mint_out: Option<Pubkey>,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// amount_in is exact and amount_out the minimum (otherwise amount_out is
+    /// exact and amount_in the maximum)
+    pub exact_in: bool,
+}
+
+impl SwapEvent {
+    #[cfg(any(feature = "pumpfun", feature = "jupiter", feature = "orca", feature = "saber", feature = "raydium"))]
+    fn new(pool: Option<Pubkey>, user: Pubkey) -> Self {
+        Self {
+            pool,
+            user,
+            mint_in: None,
+            mint_out: None,
+            amount_in: 0,
+            amount_out: 0,
+            exact_in: true,
+        }
+    }
+
+    /// Set amounts from an (amount, threshold) pair, as taken by CLMM swaps
+    #[cfg(any(feature = "orca", feature = "raydium"))]
+    fn with_amounts(mut self, amount: u64, threshold: u64, exact_in: bool) -> Self {
+        (self.amount_in, self.amount_out) = if exact_in { (amount, threshold) } else { (threshold, amount) };
+        self.exact_in = exact_in;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+impl TradeSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Buy => "buy",
+            Self::Sell => "sell",
+        }
+    }
+}
+
+/// Where a trade executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeVenue {
+    BondingCurve,
+    Amm,
+}
+
+impl TradeVenue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BondingCurve => "bonding_curve",
+            Self::Amm => "amm",
+        }
+    }
+}
+
+/// Token trade against SOL: on the bonding curve of pump.fun, Boop.fun,
+/// Moonshot or a SOL-quoted Meteora Dynamic Bonding Curve, or in a SOL-paired
+/// PumpSwap pool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradeEvent {
+    pub side: TradeSide,
+    pub mint: Pubkey,
+    /// Curve traded against (the pool on the `Amm` venue)
+    pub bonding_curve: Pubkey,
+    pub user: Pubkey,
+    pub token_amount: u64,
+    /// Lamports
+    pub sol_amount: u64,
+    /// The token amount is exact and the SOL amount its bound (otherwise the
+    /// SOL amount is exact)
+    pub exact_tokens: bool,
+    /// Launch configuration the curve was created under, on launchpads shared
+    /// by several frontends (the partner config of a Meteora DBC pool)
+    pub config: Option<Pubkey>,
+    pub venue: TradeVenue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityAction {
+    Deposit,
+    Withdraw,
+}
+
+impl LiquidityAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Deposit => "deposit",
+            Self::Withdraw => "withdraw",
+        }
+    }
+}
+
+/// Full-range pool deposit or withdrawal, in exchange for LP tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidityEvent {
+    pub action: LiquidityAction,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// LP tokens minted (deposit) or burned (withdraw)
+    pub lp_amount: u64,
+    /// Maximum in (deposit) or minimum out (withdraw)
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+/// Extract a swap event, if the instruction swaps through a pool or route
+pub fn swap_event(parsed: &ParsedInstruction) -> Option<SwapEvent> {
+    match parsed {
+        #[cfg(feature = "pumpfun")]
+        ParsedInstruction::PumpAmm(ix) => {
+            use crate::pump_amm::PumpAmmInstruction as Ix;
+            match ix {
+                Ix::Buy { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.quote_mint),
+                    mint_out: Some(a.base_mint),
+                    amount_in: args.max_quote_amount_in,
+                    amount_out: args.base_amount_out,
+                    exact_in: false,
+                    ..SwapEvent::new(Some(a.pool), a.user)
+                }),
+                Ix::BuyExactQuoteIn { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.quote_mint),
+                    mint_out: Some(a.base_mint),
+                    amount_in: args.spendable_quote_in,
+                    amount_out: args.min_base_amount_out,
+                    ..SwapEvent::new(Some(a.pool), a.user)
+                }),
+                Ix::Sell { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.base_mint),
+                    mint_out: Some(a.quote_mint),
+                    amount_in: args.base_amount_in,
+                    amount_out: args.min_quote_amount_out,
+                    ..SwapEvent::new(Some(a.pool), a.user)
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "raydium")]
+        ParsedInstruction::RaydiumCpSwap(ix) => {
+            use crate::raydium_cp_swap::RaydiumCpSwapInstruction as Ix;
+            match ix {
+                Ix::SwapBaseInput { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.input_token_mint),
+                    mint_out: Some(a.output_token_mint),
+                    amount_in: args.amount_in,
+                    amount_out: args.minimum_amount_out,
+                    ..SwapEvent::new(Some(a.pool_state), a.payer)
+                }),
+                Ix::SwapBaseOutput { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.input_token_mint),
+                    mint_out: Some(a.output_token_mint),
+                    amount_in: args.max_amount_in,
+                    amount_out: args.amount_out,
+                    exact_in: false,
+                    ..SwapEvent::new(Some(a.pool_state), a.payer)
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "raydium")]
+        ParsedInstruction::RaydiumAmmV3(ix) => {
+            use crate::raydium_amm_v3::AmmV3Instruction as Ix;
+            match ix {
+                Ix::Swap { accounts: a, args } => Some(
+                    SwapEvent::new(Some(a.pool_state), a.payer).with_amounts(
+                        args.amount,
+                        args.other_amount_threshold,
+                        args.is_base_input,
+                    ),
+                ),
+                Ix::SwapV2 { accounts: a, args } => Some(
+                    SwapEvent {
+                        mint_in: Some(a.input_vault_mint),
+                        mint_out: Some(a.output_vault_mint),
+                        ..SwapEvent::new(Some(a.pool_state), a.payer)
+                    }
+                    .with_amounts(args.amount, args.other_amount_threshold, args.is_base_input),
+                ),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "raydium")]
+        ParsedInstruction::RaydiumStable(ix) => {
+            use crate::raydium_stable::RaydiumStableInstruction as Ix;
+            match ix {
+                Ix::SwapBaseIn { accounts: a, args } => Some(SwapEvent {
+                    amount_in: args.amount_in,
+                    amount_out: args.minimum_amount_out,
+                    ..SwapEvent::new(Some(a.amm), a.user_source_owner)
+                }),
+                Ix::SwapBaseOut { accounts: a, args } => Some(SwapEvent {
+                    amount_in: args.max_amount_in,
+                    amount_out: args.amount_out,
+                    exact_in: false,
+                    ..SwapEvent::new(Some(a.amm), a.user_source_owner)
+                }),
+            }
+        }
+        #[cfg(feature = "saber")]
+        ParsedInstruction::Saber(ix) => {
+            use crate::saber::StableSwapInstruction as Ix;
+            match ix {
+                Ix::Swap { accounts: a, args } => Some(SwapEvent {
+                    amount_in: args.amount_in,
+                    amount_out: args.minimum_amount_out,
+                    ..SwapEvent::new(Some(a.swap), a.user_authority)
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "orca")]
+        ParsedInstruction::Whirlpool(ix) => {
+            use crate::whirlpool::WhirlpoolInstruction as Ix;
+            match ix {
+                Ix::Swap { accounts: a, args } => Some(
+                    SwapEvent::new(Some(a.whirlpool), a.token_authority).with_amounts(
+                        args.amount,
+                        args.other_amount_threshold,
+                        args.amount_specified_is_input,
+                    ),
+                ),
+                Ix::SwapV2 { accounts: a, args } => {
+                    let (mint_in, mint_out) = if args.a_to_b {
+                        (a.token_mint_a, a.token_mint_b)
+                    } else {
+                        (a.token_mint_b, a.token_mint_a)
+                    };
+                    Some(
+                        SwapEvent {
+                            mint_in: Some(mint_in),
+                            mint_out: Some(mint_out),
+                            ..SwapEvent::new(Some(a.whirlpool), a.token_authority)
+                        }
+                        .with_amounts(args.amount, args.other_amount_threshold, args.amount_specified_is_input),
+                    )
+                }
+                _ => None,
+            }
+        }
+        #[cfg(feature = "jupiter")]
+        ParsedInstruction::JupiterV6(ix) => {
+            use crate::jupiter_v6::Jupiter_v6Instruction as Ix;
+            match ix {
+                Ix::Route { accounts: a, args } => Some(SwapEvent {
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.in_amount,
+                    amount_out: args.quoted_out_amount,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                Ix::RouteV2 { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.source_mint),
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.in_amount,
+                    amount_out: args.quoted_out_amount,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                Ix::SharedAccountsRoute { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.source_mint),
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.in_amount,
+                    amount_out: args.quoted_out_amount,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                Ix::SharedAccountsRouteV2 { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.source_mint),
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.in_amount,
+                    amount_out: args.quoted_out_amount,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                Ix::ExactOutRoute { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.source_mint),
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.quoted_in_amount,
+                    amount_out: args.out_amount,
+                    exact_in: false,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                Ix::ExactOutRouteV2 { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.source_mint),
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.quoted_in_amount,
+                    amount_out: args.out_amount,
+                    exact_in: false,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                Ix::SharedAccountsExactOutRoute { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.source_mint),
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.quoted_in_amount,
+                    amount_out: args.out_amount,
+                    exact_in: false,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                Ix::SharedAccountsExactOutRouteV2 { accounts: a, args } => Some(SwapEvent {
+                    mint_in: Some(a.source_mint),
+                    mint_out: Some(a.destination_mint),
+                    amount_in: args.quoted_in_amount,
+                    amount_out: args.out_amount,
+                    exact_in: false,
+                    ..SwapEvent::new(None, a.user_transfer_authority)
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Extract a trade event, if the instruction buys or sells on a bonding curve
+/// or in a SOL-paired PumpSwap pool
+///
+/// Moonshot trades carry the expected amount on the non-fixed side plus a
+/// slippage tolerance (`slippage_bps`) rather than a bound, so for them the
+/// inexact amount is the quote the user traded against.
+///
+/// Meteora DBC `swap` doesn't say which way it trades, only which token
+/// accounts it pays from and into. The side is read off the payer's
+/// associated token accounts: paying from the base token's (or into the
+/// quote token's) is a sell, the reverse a buy. Swaps through other accounts
+/// on both sides (e.g. temporary wrapped SOL accounts for both) yield no event,
+/// as do swaps on curves quoted in a token other than SOL.
+pub fn trade_event(parsed: &ParsedInstruction) -> Option<TradeEvent> {
+    match parsed {
+        // Migrated pump.fun tokens trade here, usually as the base against SOL
+        #[cfg(feature = "pumpfun")]
+        ParsedInstruction::PumpAmm(_) => swap_event(parsed).and_then(sol_pair_trade),
+        #[cfg(feature = "pumpfun")]
+        ParsedInstruction::PumpFun(ix) => {
+            use crate::pump_fun::Pump_funInstruction as Ix;
+            match ix {
+                Ix::Buy { accounts: a, args } => Some(TradeEvent {
+                    side: TradeSide::Buy,
+                    mint: a.mint,
+                    bonding_curve: a.bonding_curve,
+                    user: a.user,
+                    token_amount: args.amount,
+                    sol_amount: args.max_sol_cost,
+                    exact_tokens: true,
+                    config: None,
+                    venue: TradeVenue::BondingCurve,
+                }),
+                Ix::BuyExactSolIn { accounts: a, args } => Some(TradeEvent {
+                    side: TradeSide::Buy,
+                    mint: a.mint,
+                    bonding_curve: a.bonding_curve,
+                    user: a.user,
+                    token_amount: args.min_tokens_out,
+                    sol_amount: args.spendable_sol_in,
+                    exact_tokens: false,
+                    config: None,
+                    venue: TradeVenue::BondingCurve,
+                }),
+                Ix::Sell { accounts: a, args } => Some(TradeEvent {
+                    side: TradeSide::Sell,
+                    mint: a.mint,
+                    bonding_curve: a.bonding_curve,
+                    user: a.user,
+                    token_amount: args.amount,
+                    sol_amount: args.min_sol_output,
+                    exact_tokens: true,
+                    config: None,
+                    venue: TradeVenue::BondingCurve,
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "boop")]
+        ParsedInstruction::Boop(ix) => {
+            use crate::boop::BoopInstruction as Ix;
+            match ix {
+                Ix::BuyToken { accounts: a, args } => Some(TradeEvent {
+                    side: TradeSide::Buy,
+                    mint: a.mint,
+                    bonding_curve: a.bonding_curve,
+                    user: a.buyer,
+                    token_amount: args.amount_out_min,
+                    sol_amount: args.buy_amount,
+                    exact_tokens: false,
+                    config: None,
+                    venue: TradeVenue::BondingCurve,
+                }),
+                Ix::SellToken { accounts: a, args } => Some(TradeEvent {
+                    side: TradeSide::Sell,
+                    mint: a.mint,
+                    bonding_curve: a.bonding_curve,
+                    user: a.seller,
+                    token_amount: args.sell_amount,
+                    sol_amount: args.amount_out_min,
+                    exact_tokens: true,
+                    config: None,
+                    venue: TradeVenue::BondingCurve,
+                }),
+            }
+        }
+        #[cfg(feature = "moonshot")]
+        ParsedInstruction::Moonshot(ix) => {
+            use crate::moonshot::MoonshotInstruction as Ix;
+            // fixed_side 0 fixes what goes in (SOL on buys), 1 what comes out
+            match ix {
+                Ix::Buy { accounts: a, args } => Some(TradeEvent {
+                    side: TradeSide::Buy,
+                    mint: a.mint,
+                    bonding_curve: a.curve_account,
+                    user: a.sender,
+                    token_amount: args.token_amount,
+                    sol_amount: args.collateral_amount,
+                    exact_tokens: args.fixed_side == 1,
+                    config: None,
+                    venue: TradeVenue::BondingCurve,
+                }),
+                Ix::Sell { accounts: a, args } => Some(TradeEvent {
+                    side: TradeSide::Sell,
+                    mint: a.mint,
+                    bonding_curve: a.curve_account,
+                    user: a.sender,
+                    token_amount: args.token_amount,
+                    sol_amount: args.collateral_amount,
+                    exact_tokens: args.fixed_side == 0,
+                    config: None,
+                    venue: TradeVenue::BondingCurve,
+                }),
+            }
+        }
+        #[cfg(feature = "meteora")]
+        ParsedInstruction::MeteoraDbc(crate::meteora_dbc::DynamicBondingCurveInstruction::Swap {
+            accounts: a,
+            args,
+        }) => {
+            if a.quote_mint.0 != WSOL_MINT {
+                return None;
+            }
+            let base = associated_token_address(&a.payer, &a.base_mint, &a.token_base_program);
+            let quote = associated_token_address(&a.payer, &a.quote_mint, &a.token_quote_program);
+            let side = if a.input_token_account == base || a.output_token_account == quote {
+                TradeSide::Sell
+            } else if a.output_token_account == base || a.input_token_account == quote {
+                TradeSide::Buy
+            } else {
+                return None;
+            };
+            // amount_in is exact, in the input token
+            let (token_amount, sol_amount) = match side {
+                TradeSide::Buy => (args.minimum_amount_out, args.amount_in),
+                TradeSide::Sell => (args.amount_in, args.minimum_amount_out),
+            };
+            Some(TradeEvent {
+                side,
+                mint: a.base_mint,
+                bonding_curve: a.pool,
+                user: a.payer,
+                token_amount,
+                sol_amount,
+                exact_tokens: side == TradeSide::Sell,
+                config: Some(a.config),
+                venue: TradeVenue::BondingCurve,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// A pool swap between SOL and another token as a trade of that token
+#[cfg(feature = "pumpfun")]
+fn sol_pair_trade(swap: SwapEvent) -> Option<TradeEvent> {
+    let is_sol = |mint: Option<Pubkey>| mint.is_some_and(|mint| mint.0 == WSOL_MINT);
+    let (side, mint, token_amount, sol_amount, exact_tokens) = if is_sol(swap.mint_in) {
+        (TradeSide::Buy, swap.mint_out?, swap.amount_out, swap.amount_in, !swap.exact_in)
+    } else if is_sol(swap.mint_out) {
+        (TradeSide::Sell, swap.mint_in?, swap.amount_in, swap.amount_out, swap.exact_in)
+    } else {
+        return None;
+    };
+    Some(TradeEvent {
+        side,
+        mint,
+        bonding_curve: swap.pool?,
+        user: swap.user,
+        token_amount,
+        sol_amount,
+        exact_tokens,
+        config: None,
+        venue: TradeVenue::Amm,
+    })
+}
+
+/// Associated token account of `owner` for `mint` under `token_program`
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let program = Address::from(
+        bs58::decode(ASSOCIATED_TOKEN_PROGRAM_ID)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .expect("valid associated token program id"),
+    );
+    let (address, _bump) = Address::find_program_address(
+        &[owner.0.as_slice(), token_program.0.as_slice(), mint.0.as_slice()],
+        &program,
+    );
+    Pubkey::from(address.to_bytes())
+}
+
+/// Extract a liquidity event, if the instruction deposits into or withdraws from a pool
+pub fn liquidity_event(parsed: &ParsedInstruction) -> Option<LiquidityEvent> {
+    #[cfg(any(feature = "pumpfun", feature = "raydium"))]
+    use LiquidityAction::*;
+
+    match parsed {
+        #[cfg(feature = "pumpfun")]
+        ParsedInstruction::PumpAmm(ix) => {
+            use crate::pump_amm::PumpAmmInstruction as Ix;
+            match ix {
+                Ix::Deposit { accounts: a, args } => Some(LiquidityEvent {
+                    action: Deposit,
+                    pool: a.pool,
+                    user: a.user,
+                    mint_a: a.base_mint,
+                    mint_b: a.quote_mint,
+                    lp_amount: args.lp_token_amount_out,
+                    amount_a: args.max_base_amount_in,
+                    amount_b: args.max_quote_amount_in,
+                }),
+                Ix::Withdraw { accounts: a, args } => Some(LiquidityEvent {
+                    action: Withdraw,
+                    pool: a.pool,
+                    user: a.user,
+                    mint_a: a.base_mint,
+                    mint_b: a.quote_mint,
+                    lp_amount: args.lp_token_amount_in,
+                    amount_a: args.min_base_amount_out,
+                    amount_b: args.min_quote_amount_out,
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "raydium")]
+        ParsedInstruction::RaydiumCpSwap(ix) => {
+            use crate::raydium_cp_swap::RaydiumCpSwapInstruction as Ix;
+            match ix {
+                Ix::Deposit { accounts: a, args } => Some(LiquidityEvent {
+                    action: Deposit,
+                    pool: a.pool_state,
+                    user: a.owner,
+                    mint_a: a.vault0_mint,
+                    mint_b: a.vault1_mint,
+                    lp_amount: args.lp_token_amount,
+                    amount_a: args.maximum_token0_amount,
+                    amount_b: args.maximum_token1_amount,
+                }),
+                Ix::Withdraw { accounts: a, args } => Some(LiquidityEvent {
+                    action: Withdraw,
+                    pool: a.pool_state,
+                    user: a.owner,
+                    mint_a: a.vault0_mint,
+                    mint_b: a.vault1_mint,
+                    lp_amount: args.lp_token_amount,
+                    amount_a: args.minimum_token0_amount,
+                    amount_b: args.minimum_token1_amount,
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
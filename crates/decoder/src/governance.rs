@@ -0,0 +1,137 @@
+//! Proposal lifecycle events for Squads v4 multisigs and SPL Governance
+//!
+//! Normalizes proposal creation, votes and executions into one shape, keyed
+//! by the governing account: the multisig for Squads, the governance account
+//! (one per governed treasury or program) for SPL Governance. Squads
+//! `proposalCancel` is a member vote to cancel an approved proposal, so it is
+//! a vote with [`VoteKind::Cancel`].
+
+use crate::{ParsedInstruction, Pubkey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GovernanceAction {
+    Create,
+    Vote,
+    Execute,
+}
+
+impl GovernanceAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Vote => "vote",
+            Self::Execute => "execute",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoteKind {
+    Approve,
+    Reject,
+    Abstain,
+    Veto,
+    Cancel,
+}
+
+impl VoteKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approve => "approve",
+            Self::Reject => "reject",
+            Self::Abstain => "abstain",
+            Self::Veto => "veto",
+            Self::Cancel => "cancel",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GovernanceEvent {
+    pub action: GovernanceAction,
+    /// Multisig (Squads) or governance account (SPL Governance)
+    pub governance: Pubkey,
+    pub proposal: Pubkey,
+    /// Signer creating, voting or executing (None for permissionless SPL Governance executions)
+    pub actor: Option<Pubkey>,
+    /// Set on votes
+    pub vote: Option<VoteKind>,
+}
+
+impl GovernanceEvent {
+    #[cfg(any(feature = "squads", feature = "governance"))]
+    fn new(action: GovernanceAction, governance: Pubkey, proposal: Pubkey, actor: Option<Pubkey>) -> Self {
+        Self {
+            action,
+            governance,
+            proposal,
+            actor,
+            vote: None,
+        }
+    }
+
+    #[cfg(any(feature = "squads", feature = "governance"))]
+    fn vote(governance: Pubkey, proposal: Pubkey, actor: Pubkey, vote: VoteKind) -> Self {
+        Self {
+            vote: Some(vote),
+            ..Self::new(GovernanceAction::Vote, governance, proposal, Some(actor))
+        }
+    }
+}
+
+/// Extract a governance event, if the instruction creates, votes on or executes a proposal
+pub fn governance_event(parsed: &ParsedInstruction) -> Option<GovernanceEvent> {
+    #[cfg(any(feature = "squads", feature = "governance"))]
+    use GovernanceAction::*;
+
+    match parsed {
+        #[cfg(feature = "squads")]
+        ParsedInstruction::SquadsV4(ix) => {
+            use crate::squads_v4::SquadsMultisigProgramInstruction as Ix;
+            let event = match ix {
+                Ix::ProposalCreate { accounts: a, .. } => {
+                    GovernanceEvent::new(Create, a.multisig, a.proposal, Some(a.creator))
+                }
+                Ix::ProposalApprove { accounts: a, .. } => {
+                    GovernanceEvent::vote(a.multisig, a.proposal, a.member, VoteKind::Approve)
+                }
+                Ix::ProposalReject { accounts: a, .. } => {
+                    GovernanceEvent::vote(a.multisig, a.proposal, a.member, VoteKind::Reject)
+                }
+                Ix::ProposalCancel { accounts: a, .. } => {
+                    GovernanceEvent::vote(a.multisig, a.proposal, a.member, VoteKind::Cancel)
+                }
+                Ix::VaultTransactionExecute { accounts: a, .. } => {
+                    GovernanceEvent::new(Execute, a.multisig, a.proposal, Some(a.member))
+                }
+                Ix::ConfigTransactionExecute { accounts: a, .. } => {
+                    GovernanceEvent::new(Execute, a.multisig, a.proposal, Some(a.member))
+                }
+            };
+            Some(event)
+        }
+        #[cfg(feature = "governance")]
+        ParsedInstruction::SplGovernance(ix) => {
+            use crate::spl_governance::{SplGovernanceInstruction as Ix, Vote};
+            let event = match ix {
+                Ix::CreateProposal { accounts: a, .. } => {
+                    GovernanceEvent::new(Create, a.governance, a.proposal, Some(a.governance_authority))
+                }
+                Ix::CastVote { accounts: a, args } => {
+                    let vote = match args.vote {
+                        Vote::Approve(_) => VoteKind::Approve,
+                        Vote::Deny => VoteKind::Reject,
+                        Vote::Abstain => VoteKind::Abstain,
+                        Vote::Veto => VoteKind::Veto,
+                    };
+                    GovernanceEvent::vote(a.governance, a.proposal, a.governance_authority, vote)
+                }
+                Ix::ExecuteTransaction { accounts: a, .. } => {
+                    GovernanceEvent::new(Execute, a.governance, a.proposal, None)
+                }
+            };
+            Some(event)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,816 @@
+//! Instruction decoders for SolixDB.
+//!
+//! This crate holds the parser map, [`try_parse`] and the typed instruction
+//! outputs, with no storage or runtime dependencies, so the same decoders used
+//! by the indexer can be compiled to WASM and reused elsewhere (e.g. a browser
+//! explorer).
+
+use std::collections::HashMap;
+
+pub use yellowstone_vixen_core::instruction::InstructionUpdate;
+pub use yellowstone_vixen_core::Pubkey;
+use yellowstone_vixen_core::Parser;
+
+pub mod alt;
+pub mod bridge;
+pub mod clmm;
+pub mod cnft;
+pub mod creators;
+pub mod events;
+pub mod governance;
+pub mod lifecycle;
+pub mod nft;
+pub mod orders;
+pub mod positions;
+pub mod registry;
+pub mod transfers;
+
+/// Generate a public module for a Codama IDL, re-exporting the generated items
+/// (the proc macro emits a private module named after the IDL program).
+macro_rules! idl_module {
+    ($name:ident, $generated:ident, $path:literal) => {
+        pub mod $name {
+            yellowstone_vixen_proc_macro::include_vixen_parser!($path);
+            pub use self::$generated::*;
+        }
+    };
+}
+
+#[cfg(feature = "jupiter")]
+idl_module!(jupiter_v6, jupiter_v6, "../../idls/jupiter_v6.json");
+#[cfg(feature = "jupiter")]
+idl_module!(jupiter_v4, jupiter_v4, "../../idls/jupiter_v4.json");
+#[cfg(feature = "pumpfun")]
+idl_module!(pump_amm, pump_amm, "../../idls/pumpfun_swaps.json");
+#[cfg(feature = "pumpfun")]
+idl_module!(pump_fun, pump_fun, "../../idls/pump_fun.json");
+#[cfg(feature = "raydium")]
+idl_module!(raydium_amm_v3, amm_v3, "../../idls/raydium_amm_v3.json");
+#[cfg(feature = "raydium")]
+idl_module!(raydium_cp_swap, raydium_cp_swap, "../../idls/raydium_cpmm.json");
+#[cfg(feature = "orca")]
+idl_module!(whirlpool, whirlpool, "../../idls/orca_whirlpool.json");
+#[cfg(feature = "meteora")]
+idl_module!(meteora_dlmm, lb_clmm, "../../idls/meteora_dlmm.json");
+#[cfg(feature = "meteora")]
+idl_module!(meteora_dbc, dynamic_bonding_curve, "../../idls/meteora_dbc.json");
+#[cfg(feature = "bubblegum")]
+idl_module!(bubblegum, bubblegum, "../../idls/bubblegum.json");
+#[cfg(feature = "jupiter")]
+idl_module!(jupiter_limit_order_v2, limit_order2, "../../idls/jupiter_limit_order_v2.json");
+#[cfg(feature = "jupiter")]
+idl_module!(jupiter_dca, dca, "../../idls/jupiter_dca.json");
+#[cfg(feature = "raydium")]
+idl_module!(raydium_stable, raydium_stable, "../../idls/raydium_stable.json");
+#[cfg(feature = "saber")]
+idl_module!(saber, stable_swap, "../../idls/saber_stable_swap.json");
+#[cfg(feature = "tensor")]
+idl_module!(tensor_swap, tensor_swap, "../../idls/tensor_swap.json");
+#[cfg(feature = "tensor")]
+idl_module!(tcomp, tcomp, "../../idls/tensor_tcomp.json");
+#[cfg(feature = "magic_eden")]
+idl_module!(magic_eden_v2, m2, "../../idls/magic_eden_v2.json");
+#[cfg(feature = "squads")]
+idl_module!(squads_v4, squads_multisig_program, "../../idls/squads_v4.json");
+#[cfg(feature = "governance")]
+idl_module!(spl_governance, spl_governance, "../../idls/spl_governance.json");
+#[cfg(feature = "wormhole")]
+idl_module!(wormhole_core, wormhole, "../../idls/wormhole_core.json");
+#[cfg(feature = "wormhole")]
+idl_module!(wormhole_token_bridge, token_bridge, "../../idls/wormhole_token_bridge.json");
+#[cfg(feature = "debridge")]
+idl_module!(debridge_dln, dln_source, "../../idls/debridge_dln_source.json");
+#[cfg(feature = "boop")]
+idl_module!(boop, boop, "../../idls/boop.json");
+#[cfg(feature = "moonshot")]
+idl_module!(moonshot, moonshot, "../../idls/moonshot.json");
+
+/// A successfully decoded instruction, tagged by the parser that produced it
+#[derive(Debug)]
+pub enum ParsedInstruction {
+    #[cfg(feature = "jupiter")]
+    JupiterV6(jupiter_v6::Jupiter_v6Instruction),
+    #[cfg(feature = "jupiter")]
+    JupiterV4(jupiter_v4::Jupiter_v4Instruction),
+    #[cfg(feature = "pumpfun")]
+    PumpAmm(pump_amm::PumpAmmInstruction),
+    #[cfg(feature = "pumpfun")]
+    PumpFun(pump_fun::Pump_funInstruction),
+    #[cfg(feature = "raydium")]
+    RaydiumAmmV3(raydium_amm_v3::AmmV3Instruction),
+    #[cfg(feature = "raydium")]
+    RaydiumCpSwap(raydium_cp_swap::RaydiumCpSwapInstruction),
+    #[cfg(feature = "orca")]
+    Whirlpool(whirlpool::WhirlpoolInstruction),
+    #[cfg(feature = "meteora")]
+    MeteoraDlmm(meteora_dlmm::LbClmmInstruction),
+    #[cfg(feature = "meteora")]
+    MeteoraDbc(meteora_dbc::DynamicBondingCurveInstruction),
+    #[cfg(feature = "bubblegum")]
+    Bubblegum(bubblegum::BubblegumInstruction),
+    #[cfg(feature = "jupiter")]
+    JupiterLimitOrderV2(jupiter_limit_order_v2::LimitOrder2Instruction),
+    #[cfg(feature = "jupiter")]
+    JupiterDca(jupiter_dca::DcaInstruction),
+    #[cfg(feature = "raydium")]
+    RaydiumStable(raydium_stable::RaydiumStableInstruction),
+    #[cfg(feature = "saber")]
+    Saber(saber::StableSwapInstruction),
+    #[cfg(feature = "tensor")]
+    TensorSwap(tensor_swap::TensorSwapInstruction),
+    #[cfg(feature = "tensor")]
+    Tcomp(tcomp::TcompInstruction),
+    #[cfg(feature = "magic_eden")]
+    MagicEdenV2(magic_eden_v2::M2Instruction),
+    #[cfg(feature = "squads")]
+    SquadsV4(squads_v4::SquadsMultisigProgramInstruction),
+    #[cfg(feature = "governance")]
+    SplGovernance(spl_governance::SplGovernanceInstruction),
+    #[cfg(feature = "wormhole")]
+    WormholeCore(wormhole_core::WormholeInstruction),
+    #[cfg(feature = "wormhole")]
+    WormholeTokenBridge(wormhole_token_bridge::TokenBridgeInstruction),
+    #[cfg(feature = "debridge")]
+    DebridgeDln(debridge_dln::DlnSourceInstruction),
+    #[cfg(feature = "boop")]
+    Boop(boop::BoopInstruction),
+    #[cfg(feature = "moonshot")]
+    Moonshot(moonshot::MoonshotInstruction),
+    AddressLookupTable(alt::AltInstruction),
+}
+
+impl ParsedInstruction {
+    /// Debug representation of the inner instruction, e.g. `Route { accounts: .., args: .. }`
+    pub fn to_debug_string(&self) -> String {
+        match self {
+            #[cfg(feature = "jupiter")]
+            Self::JupiterV6(ix) => format!("{:?}", ix),
+            #[cfg(feature = "jupiter")]
+            Self::JupiterV4(ix) => format!("{:?}", ix),
+            #[cfg(feature = "pumpfun")]
+            Self::PumpAmm(ix) => format!("{:?}", ix),
+            #[cfg(feature = "pumpfun")]
+            Self::PumpFun(ix) => format!("{:?}", ix),
+            #[cfg(feature = "raydium")]
+            Self::RaydiumAmmV3(ix) => format!("{:?}", ix),
+            #[cfg(feature = "raydium")]
+            Self::RaydiumCpSwap(ix) => format!("{:?}", ix),
+            #[cfg(feature = "orca")]
+            Self::Whirlpool(ix) => format!("{:?}", ix),
+            #[cfg(feature = "meteora")]
+            Self::MeteoraDlmm(ix) => format!("{:?}", ix),
+            #[cfg(feature = "meteora")]
+            Self::MeteoraDbc(ix) => format!("{:?}", ix),
+            #[cfg(feature = "bubblegum")]
+            Self::Bubblegum(ix) => format!("{:?}", ix),
+            #[cfg(feature = "jupiter")]
+            Self::JupiterLimitOrderV2(ix) => format!("{:?}", ix),
+            #[cfg(feature = "jupiter")]
+            Self::JupiterDca(ix) => format!("{:?}", ix),
+            #[cfg(feature = "raydium")]
+            Self::RaydiumStable(ix) => format!("{:?}", ix),
+            #[cfg(feature = "saber")]
+            Self::Saber(ix) => format!("{:?}", ix),
+            #[cfg(feature = "tensor")]
+            Self::TensorSwap(ix) => format!("{:?}", ix),
+            #[cfg(feature = "tensor")]
+            Self::Tcomp(ix) => format!("{:?}", ix),
+            #[cfg(feature = "magic_eden")]
+            Self::MagicEdenV2(ix) => format!("{:?}", ix),
+            #[cfg(feature = "squads")]
+            Self::SquadsV4(ix) => format!("{:?}", ix),
+            #[cfg(feature = "governance")]
+            Self::SplGovernance(ix) => format!("{:?}", ix),
+            #[cfg(feature = "wormhole")]
+            Self::WormholeCore(ix) => format!("{:?}", ix),
+            #[cfg(feature = "wormhole")]
+            Self::WormholeTokenBridge(ix) => format!("{:?}", ix),
+            #[cfg(feature = "debridge")]
+            Self::DebridgeDln(ix) => format!("{:?}", ix),
+            #[cfg(feature = "boop")]
+            Self::Boop(ix) => format!("{:?}", ix),
+            #[cfg(feature = "moonshot")]
+            Self::Moonshot(ix) => format!("{:?}", ix),
+            Self::AddressLookupTable(ix) => format!("{:?}", ix),
+        }
+    }
+
+    /// Instruction type name, e.g. `SharedAccountsRoute`
+    pub fn instruction_type(&self) -> String {
+        extract_instruction_type(&self.to_debug_string())
+    }
+
+    /// Accounts by their IDL name (e.g. `pool_state`), in instruction order
+    ///
+    /// Remaining accounts past the IDL's list have no name and are left out.
+    /// Address lookup table instructions have no IDL and return nothing.
+    pub fn named_accounts(&self) -> Vec<(&'static str, Pubkey)> {
+        match self {
+            #[cfg(feature = "jupiter")]
+            Self::JupiterV6(ix) => ix.named_accounts(),
+            #[cfg(feature = "jupiter")]
+            Self::JupiterV4(ix) => ix.named_accounts(),
+            #[cfg(feature = "pumpfun")]
+            Self::PumpAmm(ix) => ix.named_accounts(),
+            #[cfg(feature = "pumpfun")]
+            Self::PumpFun(ix) => ix.named_accounts(),
+            #[cfg(feature = "raydium")]
+            Self::RaydiumAmmV3(ix) => ix.named_accounts(),
+            #[cfg(feature = "raydium")]
+            Self::RaydiumCpSwap(ix) => ix.named_accounts(),
+            #[cfg(feature = "orca")]
+            Self::Whirlpool(ix) => ix.named_accounts(),
+            #[cfg(feature = "meteora")]
+            Self::MeteoraDlmm(ix) => ix.named_accounts(),
+            #[cfg(feature = "meteora")]
+            Self::MeteoraDbc(ix) => ix.named_accounts(),
+            #[cfg(feature = "bubblegum")]
+            Self::Bubblegum(ix) => ix.named_accounts(),
+            #[cfg(feature = "jupiter")]
+            Self::JupiterLimitOrderV2(ix) => ix.named_accounts(),
+            #[cfg(feature = "jupiter")]
+            Self::JupiterDca(ix) => ix.named_accounts(),
+            #[cfg(feature = "raydium")]
+            Self::RaydiumStable(ix) => ix.named_accounts(),
+            #[cfg(feature = "saber")]
+            Self::Saber(ix) => ix.named_accounts(),
+            #[cfg(feature = "tensor")]
+            Self::TensorSwap(ix) => ix.named_accounts(),
+            #[cfg(feature = "tensor")]
+            Self::Tcomp(ix) => ix.named_accounts(),
+            #[cfg(feature = "magic_eden")]
+            Self::MagicEdenV2(ix) => ix.named_accounts(),
+            #[cfg(feature = "squads")]
+            Self::SquadsV4(ix) => ix.named_accounts(),
+            #[cfg(feature = "governance")]
+            Self::SplGovernance(ix) => ix.named_accounts(),
+            #[cfg(feature = "wormhole")]
+            Self::WormholeCore(ix) => ix.named_accounts(),
+            #[cfg(feature = "wormhole")]
+            Self::WormholeTokenBridge(ix) => ix.named_accounts(),
+            #[cfg(feature = "debridge")]
+            Self::DebridgeDln(ix) => ix.named_accounts(),
+            #[cfg(feature = "boop")]
+            Self::Boop(ix) => ix.named_accounts(),
+            #[cfg(feature = "moonshot")]
+            Self::Moonshot(ix) => ix.named_accounts(),
+            Self::AddressLookupTable(_) => Vec::new(),
+        }
+    }
+}
+
+/// Parse an instruction with the named parser (see [`parser_map`] for names)
+pub async fn try_parse(
+    update: &InstructionUpdate,
+    parser_name: &str,
+) -> Result<ParsedInstruction, Box<dyn std::error::Error + Send + Sync>> {
+    match parser_name {
+        #[cfg(feature = "jupiter")]
+        "jupiter_v6" => {
+            jupiter_v6::InstructionParser.parse(update).await
+                .map(ParsedInstruction::JupiterV6)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "jupiter")]
+        "jupiter_v4" => {
+            jupiter_v4::InstructionParser.parse(update).await
+                .map(ParsedInstruction::JupiterV4)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "pumpfun")]
+        "pump_amm" => {
+            pump_amm::InstructionParser.parse(update).await
+                .map(ParsedInstruction::PumpAmm)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "pumpfun")]
+        "pump_fun" => {
+            pump_fun::InstructionParser.parse(update).await
+                .map(ParsedInstruction::PumpFun)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "raydium")]
+        "raydium_amm_v3" => {
+            raydium_amm_v3::InstructionParser.parse(update).await
+                .map(ParsedInstruction::RaydiumAmmV3)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "raydium")]
+        "raydium_cp_swap" => {
+            raydium_cp_swap::InstructionParser.parse(update).await
+                .map(ParsedInstruction::RaydiumCpSwap)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "orca")]
+        "whirlpool" => {
+            whirlpool::InstructionParser.parse(update).await
+                .map(ParsedInstruction::Whirlpool)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "meteora")]
+        "meteora_dlmm" => {
+            meteora_dlmm::InstructionParser.parse(update).await
+                .map(ParsedInstruction::MeteoraDlmm)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "meteora")]
+        "meteora_dbc" => {
+            meteora_dbc::InstructionParser.parse(update).await
+                .map(ParsedInstruction::MeteoraDbc)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "bubblegum")]
+        "bubblegum" => {
+            bubblegum::InstructionParser.parse(update).await
+                .map(ParsedInstruction::Bubblegum)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "jupiter")]
+        "jupiter_limit_order_v2" => {
+            jupiter_limit_order_v2::InstructionParser.parse(update).await
+                .map(ParsedInstruction::JupiterLimitOrderV2)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "jupiter")]
+        "jupiter_dca" => {
+            jupiter_dca::InstructionParser.parse(update).await
+                .map(ParsedInstruction::JupiterDca)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "raydium")]
+        "raydium_stable" => {
+            raydium_stable::InstructionParser.parse(update).await
+                .map(ParsedInstruction::RaydiumStable)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "saber")]
+        "saber" => {
+            saber::InstructionParser.parse(update).await
+                .map(ParsedInstruction::Saber)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "tensor")]
+        "tensor_swap" => {
+            tensor_swap::InstructionParser.parse(update).await
+                .map(ParsedInstruction::TensorSwap)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "tensor")]
+        "tcomp" => {
+            tcomp::InstructionParser.parse(update).await
+                .map(ParsedInstruction::Tcomp)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "magic_eden")]
+        "magic_eden_v2" => {
+            magic_eden_v2::InstructionParser.parse(update).await
+                .map(ParsedInstruction::MagicEdenV2)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "squads")]
+        "squads_v4" => {
+            squads_v4::InstructionParser.parse(update).await
+                .map(ParsedInstruction::SquadsV4)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "governance")]
+        "spl_governance" => {
+            spl_governance::InstructionParser.parse(update).await
+                .map(ParsedInstruction::SplGovernance)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "wormhole")]
+        "wormhole_core" => {
+            wormhole_core::InstructionParser.parse(update).await
+                .map(ParsedInstruction::WormholeCore)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "wormhole")]
+        "wormhole_token_bridge" => {
+            wormhole_token_bridge::InstructionParser.parse(update).await
+                .map(ParsedInstruction::WormholeTokenBridge)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "debridge")]
+        "debridge_dln" => {
+            debridge_dln::InstructionParser.parse(update).await
+                .map(ParsedInstruction::DebridgeDln)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "boop")]
+        "boop" => {
+            boop::InstructionParser.parse(update).await
+                .map(ParsedInstruction::Boop)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        #[cfg(feature = "moonshot")]
+        "moonshot" => {
+            moonshot::InstructionParser.parse(update).await
+                .map(ParsedInstruction::Moonshot)
+                .map_err(|e| format!("{:?}", e).into())
+        }
+        "address_lookup_table" => {
+            alt::AltInstruction::decode(&update.data)
+                .map(ParsedInstruction::AddressLookupTable)
+                .map_err(|e| e.into())
+        }
+        _ => Err(format!("Unknown parser: {}", parser_name).into()),
+    }
+}
+
+/// Try candidate parsers in priority order, returning the first that succeeds
+/// along with its name
+///
+/// A program id can have several candidates when its layout changed across
+/// upgrades (one IDL per version). If every candidate fails, the primary
+/// (first) candidate's error is returned.
+pub async fn try_parse_candidates(
+    update: &InstructionUpdate,
+    candidates: &[&'static str],
+) -> Result<(&'static str, ParsedInstruction), Box<dyn std::error::Error + Send + Sync>> {
+    let mut primary_error = None;
+    for parser_name in candidates {
+        match try_parse(update, parser_name).await {
+            Ok(parsed) => return Ok((parser_name, parsed)),
+            Err(e) => {
+                primary_error.get_or_insert(e);
+            }
+        }
+    }
+    Err(primary_error.unwrap_or_else(|| "No candidate parsers".into()))
+}
+
+/// Coarse classification of a parse failure, stored as failed_transactions.error_class
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorClass {
+    /// No instruction in the IDL matched the data's discriminator (often a stale IDL)
+    DiscriminatorMismatch,
+    /// Discriminator matched but the arguments failed to deserialize
+    BorshDecode,
+    /// The instruction had fewer accounts than the IDL expects
+    MissingAccounts,
+    /// Parsing didn't finish in time
+    Timeout,
+    Unknown,
+}
+
+impl ErrorClass {
+    /// Classify a parser error message (as produced by [`try_parse`])
+    pub fn classify(error: &str) -> Self {
+        let lower = error.to_ascii_lowercase();
+        if lower.contains("discriminator") || lower.contains("unknown alt instruction tag") {
+            Self::DiscriminatorMismatch
+        } else if lower.contains("account does not exist") {
+            Self::MissingAccounts
+        } else if lower.contains("timed out") || lower.contains("timeout") {
+            Self::Timeout
+        } else if lower.contains("unexpected length")
+            || lower.contains("unexpectedeof")
+            || lower.contains("invaliddata")
+            || lower.contains("invalidinput")
+            || lower.contains("not all bytes read")
+            || lower.contains("too short")
+            || lower.contains("invalid address count")
+        {
+            Self::BorshDecode
+        } else {
+            Self::Unknown
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DiscriminatorMismatch => "DiscriminatorMismatch",
+            Self::BorshDecode => "BorshDecode",
+            Self::MissingAccounts => "MissingAccounts",
+            Self::Timeout => "Timeout",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Extract instruction type name from parsed instruction string
+/// Format: "InstructionName { ... }" -> "InstructionName"
+pub fn extract_instruction_type(parsed: &str) -> String {
+    parsed
+        .split('{')
+        .next()
+        .unwrap_or(parsed)
+        .trim()
+        .to_string()
+}
+
+/// Program id bytes to candidate parser names, in priority order
+///
+/// The first candidate is the program's primary parser and names its protocol
+/// (protocol_name, metrics, `[protocols.*]` config). To keep decoding
+/// historical slots after a layout change, add the old IDL as its own module
+/// and list it after the current one, e.g. `vec!["whirlpool", "whirlpool_legacy"]`.
+pub type ParserMap = HashMap<Vec<u8>, Vec<&'static str>>;
+
+/// Map of program id bytes to candidate parsers (see [`ParserMap`])
+pub fn parser_map() -> ParserMap {
+    let mut map = HashMap::new();
+
+    // 1. Jupiter v6
+    #[cfg(feature = "jupiter")]
+    map.insert(
+        bs58::decode("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").into_vec().unwrap(),
+        vec!["jupiter_v6"],
+    );
+    // 2. Jupiter v4
+    #[cfg(feature = "jupiter")]
+    map.insert(
+        bs58::decode("JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB").into_vec().unwrap(),
+        vec!["jupiter_v4"],
+    );
+    // 3. Pump Amm
+    #[cfg(feature = "pumpfun")]
+    map.insert(
+        bs58::decode("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA").into_vec().unwrap(),
+        vec!["pump_amm"],
+    );
+    // 4. Pump fun
+    #[cfg(feature = "pumpfun")]
+    map.insert(
+        bs58::decode("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P").into_vec().unwrap(),
+        vec!["pump_fun"],
+    );
+    // 5. Raydium AMM V3
+    #[cfg(feature = "raydium")]
+    map.insert(
+        bs58::decode("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK").into_vec().unwrap(),
+        vec!["raydium_amm_v3"],
+    );
+    // 6. Raydium CP Swap
+    #[cfg(feature = "raydium")]
+    map.insert(
+        bs58::decode("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C").into_vec().unwrap(),
+        vec!["raydium_cp_swap"],
+    );
+    // 7. Whirlpool
+    #[cfg(feature = "orca")]
+    map.insert(
+        bs58::decode("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc").into_vec().unwrap(),
+        vec!["whirlpool"],
+    );
+    // 8. Meteora DLMM (position instructions only)
+    #[cfg(feature = "meteora")]
+    map.insert(
+        bs58::decode("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo").into_vec().unwrap(),
+        vec!["meteora_dlmm"],
+    );
+    // 9. Bubblegum (compressed NFTs)
+    #[cfg(feature = "bubblegum")]
+    map.insert(
+        bs58::decode(cnft::BUBBLEGUM_PROGRAM_ID).into_vec().unwrap(),
+        vec!["bubblegum"],
+    );
+    // 10. Address Lookup Table (native program, hand-written decoder)
+    map.insert(
+        bs58::decode(alt::ALT_PROGRAM_ID).into_vec().unwrap(),
+        vec!["address_lookup_table"],
+    );
+    // 11. Jupiter Limit Order v2 (order lifecycle instructions only)
+    #[cfg(feature = "jupiter")]
+    map.insert(
+        bs58::decode("j1o2qRpjcyUwEvwtcfhEQefh773ZgjxcVRry7LDqg5X").into_vec().unwrap(),
+        vec!["jupiter_limit_order_v2"],
+    );
+    // 12. Jupiter DCA (order lifecycle instructions only)
+    #[cfg(feature = "jupiter")]
+    map.insert(
+        bs58::decode("DCA265Vj8a9CEuX1eb1LWRnDT7uK6q1xMipnNyatn23M").into_vec().unwrap(),
+        vec!["jupiter_dca"],
+    );
+    // 13. Raydium Stable Swap (swap instructions only)
+    #[cfg(feature = "raydium")]
+    map.insert(
+        bs58::decode("5quBtoiQqxF9Jv6KYKctB59NT3gtJD2Y65kdnB1Uev3h").into_vec().unwrap(),
+        vec!["raydium_stable"],
+    );
+    // 14. Saber StableSwap
+    #[cfg(feature = "saber")]
+    map.insert(
+        bs58::decode("SSwpkEEcbUYqKq8Q5nYypTgFqs6LZfUMVoWDBebUgJN").into_vec().unwrap(),
+        vec!["saber"],
+    );
+    // 15. Tensor TSwap (pool buys and sells only)
+    #[cfg(feature = "tensor")]
+    map.insert(
+        bs58::decode("TSWAPaqyCSx2KABk68Shruf4rp7CxcNi8hAsbdwmHbN").into_vec().unwrap(),
+        vec!["tensor_swap"],
+    );
+    // 16. Tensor TComp (compressed NFT listing buys only)
+    #[cfg(feature = "tensor")]
+    map.insert(
+        bs58::decode("TCMPhJdwDryooaGtiocG1u3xcYbRpiJzb283XfCZsDp").into_vec().unwrap(),
+        vec!["tcomp"],
+    );
+    // 17. Magic Eden v2 (executed sales only)
+    #[cfg(feature = "magic_eden")]
+    map.insert(
+        bs58::decode("M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K").into_vec().unwrap(),
+        vec!["magic_eden_v2"],
+    );
+    // 18. Squads v4 multisig (proposal instructions only)
+    #[cfg(feature = "squads")]
+    map.insert(
+        bs58::decode("SQDS4ep65T869zMMBKyuUq6mjdLvvUrZm48mXtDYWp4").into_vec().unwrap(),
+        vec!["squads_v4"],
+    );
+    // 19. SPL Governance (proposal instructions only)
+    #[cfg(feature = "governance")]
+    map.insert(
+        bs58::decode("GovER5Lthms3bLBqWub97yVrMZEogzPR6vKbm5Vi3AB").into_vec().unwrap(),
+        vec!["spl_governance"],
+    );
+    // 20. Wormhole core bridge (message posting only)
+    #[cfg(feature = "wormhole")]
+    map.insert(
+        bs58::decode("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth").into_vec().unwrap(),
+        vec!["wormhole_core"],
+    );
+    // 21. Wormhole token bridge (transfers and redemptions only)
+    #[cfg(feature = "wormhole")]
+    map.insert(
+        bs58::decode("wormDTUJ6AWPNvk59vGQbDvGJmqbDTdgWgAqcLBCgUb").into_vec().unwrap(),
+        vec!["wormhole_token_bridge"],
+    );
+    // 22. deBridge DLN source (order creation only)
+    #[cfg(feature = "debridge")]
+    map.insert(
+        bs58::decode("src5qyZHqTqecJV4aY6Cb6zDZLMDzrDKKezs22MPHr4").into_vec().unwrap(),
+        vec!["debridge_dln"],
+    );
+    // 23. Boop.fun bonding curve (trades only)
+    #[cfg(feature = "boop")]
+    map.insert(
+        bs58::decode("boop8hVGQGqehUK2iVEMEnMrL5RbjywRzHKBmBE7ry4").into_vec().unwrap(),
+        vec!["boop"],
+    );
+    // 24. Moonshot token launchpad (trades only)
+    #[cfg(feature = "moonshot")]
+    map.insert(
+        bs58::decode("MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG").into_vec().unwrap(),
+        vec!["moonshot"],
+    );
+    // 25. Meteora Dynamic Bonding Curve (pool creation and swaps only)
+    #[cfg(feature = "meteora")]
+    map.insert(
+        bs58::decode("dbcij3LWUppWqq96dh6gJWwBifmcGfLSB5D4DuSMaqN").into_vec().unwrap(),
+        vec!["meteora_dbc"],
+    );
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_errors() {
+        let cases = [
+            ("Other(\"Invalid Instruction discriminator\")", ErrorClass::DiscriminatorMismatch),
+            ("Other(\"Account does not exist at index 7\")", ErrorClass::MissingAccounts),
+            (
+                "Other(Custom { kind: InvalidData, error: \"Unexpected length of input\" })",
+                ErrorClass::BorshDecode,
+            ),
+            ("Unknown parser: foo", ErrorClass::Unknown),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(ErrorClass::classify(message), expected, "{}", message);
+        }
+    }
+
+    #[test]
+    fn test_protocol_ids() {
+        let mut ids: Vec<u16> = registry::PROTOCOL_IDS.iter().map(|(_, id)| *id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), registry::PROTOCOL_IDS.len(), "protocol ids must be unique");
+        for parser_name in parser_map().values().flatten() {
+            assert_ne!(registry::protocol_id(parser_name), 0, "{} has no protocol id", parser_name);
+        }
+        // Ids are stored in ClickHouse, so the hash must never change
+        assert_eq!(registry::instruction_type_id(""), 0x811c_9dc5);
+        assert_eq!(registry::instruction_type_id("a"), 0xe40c_292c);
+    }
+
+    #[test]
+    fn test_decode_alt_extend() {
+        let mut data = vec![2, 0, 0, 0];
+        data.extend_from_slice(&2u64.to_le_bytes());
+        data.extend_from_slice(&[1u8; 32]);
+        data.extend_from_slice(&[2u8; 32]);
+
+        let ix = alt::AltInstruction::decode(&data).unwrap();
+        assert_eq!(
+            ix,
+            alt::AltInstruction::ExtendLookupTable {
+                new_addresses: vec![Pubkey::from([1u8; 32]), Pubkey::from([2u8; 32])],
+            }
+        );
+        assert_eq!(ix.action(), "extend");
+        assert!(alt::AltInstruction::decode(&data[..40]).is_err());
+    }
+
+    #[test]
+    fn test_decode_account_lifecycle() {
+        use lifecycle::{decode_account_lifecycle, LifecycleAction, LifecycleProgram};
+
+        let keys: Vec<Pubkey> = (0..6u8).map(|i| Pubkey::from([i; 32])).collect();
+        let create = decode_account_lifecycle(LifecycleProgram::AssociatedToken, &[1], &keys).unwrap();
+        assert_eq!(create.action, LifecycleAction::CreateAtaIdempotent);
+        assert_eq!(create.account, keys[1]);
+        assert_eq!(create.mint, Some(keys[3]));
+
+        let close = decode_account_lifecycle(LifecycleProgram::Token, &[9], &keys[..3]).unwrap();
+        assert_eq!(close.action, LifecycleAction::CloseAccount);
+        assert_eq!(close.destination, Some(keys[1]));
+
+        // Transfer (tag 3) is not a lifecycle event
+        assert!(decode_account_lifecycle(LifecycleProgram::Token2022, &[3], &keys).is_none());
+
+        use lifecycle::decode_initialize_mint;
+        assert_eq!(decode_initialize_mint(LifecycleProgram::Token2022, &[20, 6], &keys), Some(keys[0]));
+        assert!(decode_initialize_mint(LifecycleProgram::AssociatedToken, &[0], &keys).is_none());
+    }
+
+    #[test]
+    fn test_decode_leaf_schema_event() {
+        let mut event = vec![1u8, 0, 0];
+        event.extend_from_slice(&[7u8; 32]); // id
+        event.extend_from_slice(&[8u8; 32]); // owner
+        event.extend_from_slice(&[9u8; 32]); // delegate
+        event.extend_from_slice(&42u64.to_le_bytes());
+        event.extend_from_slice(&[0u8; 96]); // data_hash, creator_hash, leaf_hash
+        let mut data = vec![1u8, 0];
+        data.extend_from_slice(&(event.len() as u32).to_le_bytes());
+        data.extend_from_slice(&event);
+
+        let leaf = cnft::decode_leaf_schema_event(&data).unwrap();
+        assert_eq!(leaf.asset_id, Pubkey::from([7u8; 32]));
+        assert_eq!(leaf.owner, Pubkey::from([8u8; 32]));
+        assert_eq!(leaf.nonce, 42);
+        // Change log events (tag 0) are not leaf schemas
+        data[0] = 0;
+        assert!(cnft::decode_leaf_schema_event(&data).is_none());
+    }
+
+    #[test]
+    fn test_transfers_and_delta_pairing() {
+        use transfers::{decode_transfer, pair_deltas, TransferProgram};
+
+        let keys: Vec<Pubkey> = (0..4u8).map(|i| Pubkey::from([i; 32])).collect();
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        let sol = decode_transfer(TransferProgram::System, &data, &keys[..2]).unwrap();
+        assert_eq!((sol.source, sol.destination, sol.amount), (keys[0], keys[1], 1_000));
+
+        let mut data = vec![12];
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.push(6); // decimals
+        let checked = decode_transfer(TransferProgram::Token, &data, &keys).unwrap();
+        assert_eq!((checked.destination, checked.mint), (keys[2], Some(keys[1])));
+        assert!(decode_transfer(TransferProgram::Token, &[9], &keys).is_none());
+
+        let edges = pair_deltas(&[("a", -100), ("b", 60), ("c", 40), ("d", 0)]);
+        assert_eq!(edges, vec![("a", "b", 60), ("a", "c", 40)]);
+        // Unmatched outflow (e.g. a burn) produces no edge
+        assert_eq!(pair_deltas(&[("a", -100), ("b", 30)]), vec![("a", "b", 30)]);
+    }
+
+    #[test]
+    fn test_bridge_chains_and_addresses() {
+        use bridge::{chain_id_u64, debridge_chain, format_address, wormhole_chain, SOLANA};
+
+        assert_eq!(wormhole_chain(2), "ethereum");
+        assert_eq!(wormhole_chain(999), "wormhole:999");
+        assert_eq!(debridge_chain(7565164), SOLANA);
+
+        let mut padded = [0u8; 32];
+        padded[12..].copy_from_slice(&[0xab; 20]);
+        assert_eq!(format_address(&padded, "ethereum"), format!("0x{}", "ab".repeat(20)));
+        assert_eq!(format_address(&[7u8; 32], SOLANA), bs58::encode([7u8; 32]).into_string());
+
+        let mut chain_id = [0u8; 32];
+        chain_id[31] = 56;
+        assert_eq!(chain_id_u64(&chain_id), Some(56));
+        chain_id[0] = 1;
+        assert_eq!(chain_id_u64(&chain_id), None);
+    }
+
+    #[cfg(feature = "magic_eden")]
+    #[test]
+    fn test_nft_marketplace_fee() {
+        use nft::fee_from_bps;
+
+        assert_eq!(fee_from_bps(1_000_000_000, 0, 250), 25_000_000);
+        // Maker rebate offsets the taker fee
+        assert_eq!(fee_from_bps(1_000_000_000, -50, 250), 20_000_000);
+        assert_eq!(fee_from_bps(1_000_000_000, -300, 250), 0);
+    }
+}
@@ -0,0 +1,133 @@
+//! Token account lifecycle decoder
+//!
+//! Picks ATA creation, `CloseAccount` and `SyncNative` out of the SPL Token,
+//! Token-2022 and Associated Token Account programs. These programs appear in
+//! almost every transaction, so they aren't in [`crate::parser_map`] (which
+//! would store every transfer); callers check program ids and call
+//! [`decode_account_lifecycle`] directly. New mints are picked out the same
+//! way with [`decode_initialize_mint`].
+
+use crate::Pubkey;
+use std::collections::HashMap;
+
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const ATA_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+/// SPL Token instruction tags
+const INITIALIZE_MINT: u8 = 0;
+const CLOSE_ACCOUNT: u8 = 9;
+const SYNC_NATIVE: u8 = 17;
+const INITIALIZE_MINT_2: u8 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleAction {
+    CreateAta,
+    CreateAtaIdempotent,
+    CloseAccount,
+    SyncNative,
+}
+
+impl LifecycleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CreateAta => "create_ata",
+            Self::CreateAtaIdempotent => "create_ata_idempotent",
+            Self::CloseAccount => "close_account",
+            Self::SyncNative => "sync_native",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountLifecycleEvent {
+    pub action: LifecycleAction,
+    /// The token account created, closed or synced
+    pub account: Pubkey,
+    /// Wallet owning the account (ATA create, close)
+    pub owner: Option<Pubkey>,
+    /// Token mint (ATA create only)
+    pub mint: Option<Pubkey>,
+    /// Token program the account belongs to (ATA create names it explicitly)
+    pub token_program: Option<Pubkey>,
+    /// Lamport recipient (close only)
+    pub destination: Option<Pubkey>,
+}
+
+/// Programs whose instructions can be lifecycle events
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleProgram {
+    Token,
+    Token2022,
+    AssociatedToken,
+}
+
+/// Map of program id bytes to lifecycle program (see [`crate::parser_map`])
+pub fn lifecycle_programs() -> HashMap<Vec<u8>, LifecycleProgram> {
+    let mut map = HashMap::new();
+    map.insert(bs58::decode(TOKEN_PROGRAM_ID).into_vec().unwrap(), LifecycleProgram::Token);
+    map.insert(bs58::decode(TOKEN_2022_PROGRAM_ID).into_vec().unwrap(), LifecycleProgram::Token2022);
+    map.insert(bs58::decode(ATA_PROGRAM_ID).into_vec().unwrap(), LifecycleProgram::AssociatedToken);
+    map
+}
+
+/// Decode a lifecycle event, returning None for any other instruction
+pub fn decode_account_lifecycle(
+    program: LifecycleProgram,
+    data: &[u8],
+    accounts: &[Pubkey],
+) -> Option<AccountLifecycleEvent> {
+    match program {
+        LifecycleProgram::AssociatedToken => {
+            // Empty data is the original Create; 0 = Create, 1 = CreateIdempotent
+            let action = match data.first() {
+                None | Some(0) => LifecycleAction::CreateAta,
+                Some(1) => LifecycleAction::CreateAtaIdempotent,
+                _ => return None,
+            };
+            // [funder, ata, wallet, mint, system_program, token_program]
+            Some(AccountLifecycleEvent {
+                action,
+                account: *accounts.get(1)?,
+                owner: accounts.get(2).copied(),
+                mint: accounts.get(3).copied(),
+                token_program: accounts.get(5).copied(),
+                destination: None,
+            })
+        }
+        LifecycleProgram::Token | LifecycleProgram::Token2022 => match *data.first()? {
+            // [account, destination, owner]
+            CLOSE_ACCOUNT => Some(AccountLifecycleEvent {
+                action: LifecycleAction::CloseAccount,
+                account: *accounts.first()?,
+                owner: accounts.get(2).copied(),
+                mint: None,
+                token_program: None,
+                destination: accounts.get(1).copied(),
+            }),
+            // [account]
+            SYNC_NATIVE => Some(AccountLifecycleEvent {
+                action: LifecycleAction::SyncNative,
+                account: *accounts.first()?,
+                owner: None,
+                mint: None,
+                token_program: None,
+                destination: None,
+            }),
+            _ => None,
+        },
+    }
+}
+
+/// Mint initialized by an `InitializeMint`/`InitializeMint2` instruction
+/// (Token or Token-2022), None for any other instruction
+pub fn decode_initialize_mint(program: LifecycleProgram, data: &[u8], accounts: &[Pubkey]) -> Option<Pubkey> {
+    if program == LifecycleProgram::AssociatedToken {
+        return None;
+    }
+    match *data.first()? {
+        // [mint, rent sysvar] / [mint]
+        INITIALIZE_MINT | INITIALIZE_MINT_2 => accounts.first().copied(),
+        _ => None,
+    }
+}
@@ -0,0 +1,98 @@
+//! NFT marketplace trades for Tensor (TSwap, TComp) and Magic Eden v2
+//!
+//! Normalizes each marketplace's sale instruction into one shape. Prices are
+//! lamports and, like swap amounts, are the instruction's values: Magic Eden's
+//! `buyer_price` is the settled price, while Tensor instructions carry the
+//! taker's bound (the buyer's maximum on buys, the seller's minimum on sells)
+//! and the pool or listing sets the actual price. The marketplace fee is only
+//! known where the instruction carries the fee rates (Magic Eden); Tensor
+//! takes its fee from program config. TComp trades are compressed NFTs, whose
+//! mint is the Bubblegum asset id.
+
+use crate::{ParsedInstruction, Pubkey};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NftTradeEvent {
+    /// NFT mint (asset id for compressed NFTs)
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    /// Lamports
+    pub price: u64,
+    /// price is the settled price (otherwise the taker's max/min bound)
+    pub exact_price: bool,
+    /// Lamports, when the instruction carries the fee rates
+    pub marketplace_fee: Option<u64>,
+}
+
+/// Fee on `price` at maker + taker basis points (a maker rebate can make the maker side negative)
+#[cfg(feature = "magic_eden")]
+pub(crate) fn fee_from_bps(price: u64, maker_fee_bp: i16, taker_fee_bp: u16) -> u64 {
+    let bps = (maker_fee_bp as i128 + taker_fee_bp as i128).max(0);
+    (price as i128 * bps / 10_000) as u64
+}
+
+/// Extract an NFT trade, if the instruction is a marketplace sale
+pub fn nft_trade_event(parsed: &ParsedInstruction) -> Option<NftTradeEvent> {
+    match parsed {
+        #[cfg(feature = "tensor")]
+        ParsedInstruction::TensorSwap(ix) => {
+            use crate::tensor_swap::TensorSwapInstruction as Ix;
+            match ix {
+                Ix::BuyNft { accounts: a, args } => Some(NftTradeEvent {
+                    mint: a.nft_mint,
+                    buyer: a.buyer,
+                    seller: a.owner,
+                    price: args.max_price,
+                    exact_price: false,
+                    marketplace_fee: None,
+                }),
+                Ix::SellNftTokenPool { accounts: a, args } => Some(NftTradeEvent {
+                    mint: a.nft_mint,
+                    buyer: a.owner,
+                    seller: a.seller,
+                    price: args.min_price,
+                    exact_price: false,
+                    marketplace_fee: None,
+                }),
+                Ix::SellNftTradePool { accounts: a, args } => Some(NftTradeEvent {
+                    mint: a.nft_mint,
+                    buyer: a.owner,
+                    seller: a.seller,
+                    price: args.min_price,
+                    exact_price: false,
+                    marketplace_fee: None,
+                }),
+            }
+        }
+        #[cfg(feature = "tensor")]
+        ParsedInstruction::Tcomp(ix) => {
+            use crate::tcomp::TcompInstruction as Ix;
+            match ix {
+                Ix::Buy { accounts: a, args } => Some(NftTradeEvent {
+                    mint: crate::cnft::asset_id(&a.merkle_tree, args.nonce),
+                    buyer: a.buyer,
+                    seller: a.owner,
+                    price: args.max_amount,
+                    exact_price: false,
+                    marketplace_fee: None,
+                }),
+            }
+        }
+        #[cfg(feature = "magic_eden")]
+        ParsedInstruction::MagicEdenV2(ix) => {
+            use crate::magic_eden_v2::M2Instruction as Ix;
+            match ix {
+                Ix::ExecuteSaleV2 { accounts: a, args } => Some(NftTradeEvent {
+                    mint: a.token_mint,
+                    buyer: a.buyer,
+                    seller: a.seller,
+                    price: args.buyer_price,
+                    exact_price: true,
+                    marketplace_fee: Some(fee_from_bps(args.buyer_price, args.maker_fee_bp, args.taker_fee_bp)),
+                }),
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,136 @@
+//! Order lifecycle events for Jupiter Limit Order v2 and Jupiter DCA
+//!
+//! Normalizes open/fill/cancel/close instructions into one shape, keyed by the
+//! order (limit order) or DCA account. Amounts are the instruction's values:
+//! making/taking amounts on a limit order open, the deposit on a DCA open, and
+//! the output paid on a fill (the limit order's `max_taking_amount` bound, or
+//! the DCA keeper's `repay_amount`). Fill inputs aren't in the fill instruction
+//! and are left at 0.
+
+use crate::{ParsedInstruction, Pubkey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderAction {
+    Open,
+    Fill,
+    Cancel,
+    Close,
+}
+
+impl OrderAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Fill => "fill",
+            Self::Cancel => "cancel",
+            Self::Close => "close",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderEvent {
+    pub action: OrderAction,
+    /// Order state account (the DCA account for DCA orders)
+    pub order: Pubkey,
+    /// Order owner, when the instruction references it
+    pub maker: Option<Pubkey>,
+    /// Filler (taker or DCA keeper) on fills and keeper closes
+    pub taker: Option<Pubkey>,
+    pub input_mint: Option<Pubkey>,
+    pub output_mint: Option<Pubkey>,
+    pub in_amount: u64,
+    pub out_amount: u64,
+}
+
+impl OrderEvent {
+    #[cfg(feature = "jupiter")]
+    fn new(action: OrderAction, order: Pubkey) -> Self {
+        Self {
+            action,
+            order,
+            maker: None,
+            taker: None,
+            input_mint: None,
+            output_mint: None,
+            in_amount: 0,
+            out_amount: 0,
+        }
+    }
+}
+
+/// Extract an order event, if the instruction opens, fills, cancels or closes an order
+pub fn order_event(parsed: &ParsedInstruction) -> Option<OrderEvent> {
+    #[cfg(feature = "jupiter")]
+    use OrderAction::*;
+
+    match parsed {
+        #[cfg(feature = "jupiter")]
+        ParsedInstruction::JupiterLimitOrderV2(ix) => {
+            use crate::jupiter_limit_order_v2::LimitOrder2Instruction as Ix;
+            match ix {
+                Ix::InitializeOrder { accounts: a, args } => Some(OrderEvent {
+                    maker: Some(a.maker),
+                    input_mint: Some(a.input_mint),
+                    output_mint: Some(a.output_mint),
+                    in_amount: args.making_amount,
+                    out_amount: args.taking_amount,
+                    ..OrderEvent::new(Open, a.order)
+                }),
+                Ix::FlashFillOrder { accounts: a, args } => Some(OrderEvent {
+                    maker: Some(a.maker),
+                    taker: Some(a.taker),
+                    out_amount: args.max_taking_amount,
+                    ..OrderEvent::new(Fill, a.order)
+                }),
+                Ix::CancelOrder { accounts: a, .. } => Some(OrderEvent {
+                    maker: Some(a.maker),
+                    input_mint: Some(a.input_mint),
+                    ..OrderEvent::new(Cancel, a.order)
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "jupiter")]
+        ParsedInstruction::JupiterDca(ix) => {
+            use crate::jupiter_dca::DcaInstruction as Ix;
+            match ix {
+                Ix::OpenDca { accounts: a, args } => Some(OrderEvent {
+                    maker: Some(a.user),
+                    input_mint: Some(a.input_mint),
+                    output_mint: Some(a.output_mint),
+                    in_amount: args.in_amount,
+                    ..OrderEvent::new(Open, a.dca)
+                }),
+                Ix::OpenDcaV2 { accounts: a, args } => Some(OrderEvent {
+                    maker: Some(a.user),
+                    input_mint: Some(a.input_mint),
+                    output_mint: Some(a.output_mint),
+                    in_amount: args.in_amount,
+                    ..OrderEvent::new(Open, a.dca)
+                }),
+                Ix::FulfillFlashFill { accounts: a, args } => Some(OrderEvent {
+                    taker: Some(a.keeper),
+                    input_mint: Some(a.input_mint),
+                    output_mint: Some(a.output_mint),
+                    out_amount: args.repay_amount,
+                    ..OrderEvent::new(Fill, a.dca)
+                }),
+                Ix::CloseDca { accounts: a, .. } => Some(OrderEvent {
+                    maker: Some(a.user),
+                    input_mint: Some(a.input_mint),
+                    output_mint: Some(a.output_mint),
+                    ..OrderEvent::new(Close, a.dca)
+                }),
+                Ix::EndAndClose { accounts: a, .. } => Some(OrderEvent {
+                    taker: Some(a.keeper),
+                    input_mint: Some(a.input_mint),
+                    output_mint: Some(a.output_mint),
+                    ..OrderEvent::new(Close, a.dca)
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,287 @@
+//! Liquidity position events for concentrated liquidity protocols
+//!
+//! Normalizes open/increase/decrease/close instructions from Raydium CLMM
+//! (amm_v3), Orca Whirlpool and Meteora DLMM into one shape. Amounts are the
+//! instruction's bounds (max for open/increase, min for decrease), not the
+//! amounts actually transferred. For DLMM, the "tick" range is the bin id range.
+
+use crate::{ParsedInstruction, Pubkey};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionAction {
+    Open,
+    Increase,
+    Decrease,
+    Close,
+}
+
+impl PositionAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Increase => "increase",
+            Self::Decrease => "decrease",
+            Self::Close => "close",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionEvent {
+    pub action: PositionAction,
+    /// Position state account
+    pub position: Pubkey,
+    /// Position NFT mint (only present on open/close)
+    pub position_mint: Option<Pubkey>,
+    pub pool: Option<Pubkey>,
+    /// Pool token vaults (reserves), when the instruction references them
+    pub vault_a: Option<Pubkey>,
+    pub vault_b: Option<Pubkey>,
+    pub owner: Pubkey,
+    pub tick_lower: Option<i32>,
+    pub tick_upper: Option<i32>,
+    pub liquidity: u128,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+impl PositionEvent {
+    #[cfg(any(feature = "raydium", feature = "orca", feature = "meteora"))]
+    fn new(action: PositionAction, position: Pubkey, owner: Pubkey) -> Self {
+        Self {
+            action,
+            position,
+            position_mint: None,
+            pool: None,
+            vault_a: None,
+            vault_b: None,
+            owner,
+            tick_lower: None,
+            tick_upper: None,
+            liquidity: 0,
+            amount_a: 0,
+            amount_b: 0,
+        }
+    }
+}
+
+/// Extract a position event, if the instruction opens, changes or closes an LP position
+pub fn position_event(parsed: &ParsedInstruction) -> Option<PositionEvent> {
+    #[cfg(any(feature = "raydium", feature = "orca", feature = "meteora"))]
+    use PositionAction::*;
+
+    match parsed {
+        #[cfg(feature = "raydium")]
+        ParsedInstruction::RaydiumAmmV3(ix) => {
+            use crate::raydium_amm_v3::AmmV3Instruction as Ix;
+            match ix {
+                Ix::OpenPosition { accounts: a, args } => Some(PositionEvent {
+                    position_mint: Some(a.position_nft_mint),
+                    pool: Some(a.pool_state),
+                    vault_a: Some(a.token_vault0),
+                    vault_b: Some(a.token_vault1),
+                    tick_lower: Some(args.tick_lower_index),
+                    tick_upper: Some(args.tick_upper_index),
+                    liquidity: args.liquidity,
+                    amount_a: args.amount0_max,
+                    amount_b: args.amount1_max,
+                    ..PositionEvent::new(Open, a.personal_position, a.position_nft_owner)
+                }),
+                Ix::OpenPositionV2 { accounts: a, args } => Some(PositionEvent {
+                    position_mint: Some(a.position_nft_mint),
+                    pool: Some(a.pool_state),
+                    vault_a: Some(a.token_vault0),
+                    vault_b: Some(a.token_vault1),
+                    tick_lower: Some(args.tick_lower_index),
+                    tick_upper: Some(args.tick_upper_index),
+                    liquidity: args.liquidity,
+                    amount_a: args.amount0_max,
+                    amount_b: args.amount1_max,
+                    ..PositionEvent::new(Open, a.personal_position, a.position_nft_owner)
+                }),
+                Ix::OpenPositionWithToken22Nft { accounts: a, args } => Some(PositionEvent {
+                    position_mint: Some(a.position_nft_mint),
+                    pool: Some(a.pool_state),
+                    vault_a: Some(a.token_vault0),
+                    vault_b: Some(a.token_vault1),
+                    tick_lower: Some(args.tick_lower_index),
+                    tick_upper: Some(args.tick_upper_index),
+                    liquidity: args.liquidity,
+                    amount_a: args.amount0_max,
+                    amount_b: args.amount1_max,
+                    ..PositionEvent::new(Open, a.personal_position, a.position_nft_owner)
+                }),
+                Ix::IncreaseLiquidity { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.pool_state),
+                    vault_a: Some(a.token_vault0),
+                    vault_b: Some(a.token_vault1),
+                    liquidity: args.liquidity,
+                    amount_a: args.amount0_max,
+                    amount_b: args.amount1_max,
+                    ..PositionEvent::new(Increase, a.personal_position, a.nft_owner)
+                }),
+                Ix::IncreaseLiquidityV2 { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.pool_state),
+                    vault_a: Some(a.token_vault0),
+                    vault_b: Some(a.token_vault1),
+                    liquidity: args.liquidity,
+                    amount_a: args.amount0_max,
+                    amount_b: args.amount1_max,
+                    ..PositionEvent::new(Increase, a.personal_position, a.nft_owner)
+                }),
+                Ix::DecreaseLiquidity { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.pool_state),
+                    vault_a: Some(a.token_vault0),
+                    vault_b: Some(a.token_vault1),
+                    liquidity: args.liquidity,
+                    amount_a: args.amount0_min,
+                    amount_b: args.amount1_min,
+                    ..PositionEvent::new(Decrease, a.personal_position, a.nft_owner)
+                }),
+                Ix::DecreaseLiquidityV2 { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.pool_state),
+                    vault_a: Some(a.token_vault0),
+                    vault_b: Some(a.token_vault1),
+                    liquidity: args.liquidity,
+                    amount_a: args.amount0_min,
+                    amount_b: args.amount1_min,
+                    ..PositionEvent::new(Decrease, a.personal_position, a.nft_owner)
+                }),
+                Ix::ClosePosition { accounts: a, .. } => Some(PositionEvent {
+                    position_mint: Some(a.position_nft_mint),
+                    ..PositionEvent::new(Close, a.personal_position, a.nft_owner)
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "orca")]
+        ParsedInstruction::Whirlpool(ix) => {
+            use crate::whirlpool::WhirlpoolInstruction as Ix;
+            match ix {
+                Ix::OpenPosition { accounts: a, args } => Some(PositionEvent {
+                    position_mint: Some(a.position_mint),
+                    pool: Some(a.whirlpool),
+                    tick_lower: Some(args.tick_lower_index),
+                    tick_upper: Some(args.tick_upper_index),
+                    ..PositionEvent::new(Open, a.position, a.owner)
+                }),
+                Ix::OpenPositionWithMetadata { accounts: a, args } => Some(PositionEvent {
+                    position_mint: Some(a.position_mint),
+                    pool: Some(a.whirlpool),
+                    tick_lower: Some(args.tick_lower_index),
+                    tick_upper: Some(args.tick_upper_index),
+                    ..PositionEvent::new(Open, a.position, a.owner)
+                }),
+                Ix::OpenPositionWithTokenExtensions { accounts: a, args } => Some(PositionEvent {
+                    position_mint: Some(a.position_mint),
+                    pool: Some(a.whirlpool),
+                    tick_lower: Some(args.tick_lower_index),
+                    tick_upper: Some(args.tick_upper_index),
+                    ..PositionEvent::new(Open, a.position, a.owner)
+                }),
+                Ix::IncreaseLiquidity { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.whirlpool),
+                    vault_a: Some(a.token_vault_a),
+                    vault_b: Some(a.token_vault_b),
+                    liquidity: args.liquidity_amount,
+                    amount_a: args.token_max_a,
+                    amount_b: args.token_max_b,
+                    ..PositionEvent::new(Increase, a.position, a.position_authority)
+                }),
+                Ix::IncreaseLiquidityV2 { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.whirlpool),
+                    vault_a: Some(a.token_vault_a),
+                    vault_b: Some(a.token_vault_b),
+                    liquidity: args.liquidity_amount,
+                    amount_a: args.token_max_a,
+                    amount_b: args.token_max_b,
+                    ..PositionEvent::new(Increase, a.position, a.position_authority)
+                }),
+                Ix::DecreaseLiquidity { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.whirlpool),
+                    vault_a: Some(a.token_vault_a),
+                    vault_b: Some(a.token_vault_b),
+                    liquidity: args.liquidity_amount,
+                    amount_a: args.token_min_a,
+                    amount_b: args.token_min_b,
+                    ..PositionEvent::new(Decrease, a.position, a.position_authority)
+                }),
+                Ix::DecreaseLiquidityV2 { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.whirlpool),
+                    vault_a: Some(a.token_vault_a),
+                    vault_b: Some(a.token_vault_b),
+                    liquidity: args.liquidity_amount,
+                    amount_a: args.token_min_a,
+                    amount_b: args.token_min_b,
+                    ..PositionEvent::new(Decrease, a.position, a.position_authority)
+                }),
+                Ix::ClosePosition { accounts: a, .. } => Some(PositionEvent {
+                    position_mint: Some(a.position_mint),
+                    ..PositionEvent::new(Close, a.position, a.position_authority)
+                }),
+                Ix::ClosePositionWithTokenExtensions { accounts: a, .. } => Some(PositionEvent {
+                    position_mint: Some(a.position_mint),
+                    ..PositionEvent::new(Close, a.position, a.position_authority)
+                }),
+                _ => None,
+            }
+        }
+        #[cfg(feature = "meteora")]
+        ParsedInstruction::MeteoraDlmm(ix) => {
+            use crate::meteora_dlmm::LbClmmInstruction as Ix;
+            match ix {
+                Ix::InitializePosition { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.lb_pair),
+                    tick_lower: Some(args.lower_bin_id),
+                    tick_upper: Some(args.lower_bin_id.saturating_add(args.width).saturating_sub(1)),
+                    ..PositionEvent::new(Open, a.position, a.owner)
+                }),
+                Ix::AddLiquidity { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.lb_pair),
+                    vault_a: Some(a.reserve_x),
+                    vault_b: Some(a.reserve_y),
+                    amount_a: args.amount_x,
+                    amount_b: args.amount_y,
+                    ..PositionEvent::new(Increase, a.position, a.sender)
+                }),
+                Ix::AddLiquidityByWeight { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.lb_pair),
+                    vault_a: Some(a.reserve_x),
+                    vault_b: Some(a.reserve_y),
+                    amount_a: args.amount_x,
+                    amount_b: args.amount_y,
+                    ..PositionEvent::new(Increase, a.position, a.sender)
+                }),
+                Ix::AddLiquidityByStrategy { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.lb_pair),
+                    vault_a: Some(a.reserve_x),
+                    vault_b: Some(a.reserve_y),
+                    tick_lower: Some(args.min_bin_id),
+                    tick_upper: Some(args.max_bin_id),
+                    amount_a: args.amount_x,
+                    amount_b: args.amount_y,
+                    ..PositionEvent::new(Increase, a.position, a.sender)
+                }),
+                Ix::RemoveLiquidity { accounts: a, .. } => Some(PositionEvent {
+                    pool: Some(a.lb_pair),
+                    vault_a: Some(a.reserve_x),
+                    vault_b: Some(a.reserve_y),
+                    ..PositionEvent::new(Decrease, a.position, a.sender)
+                }),
+                Ix::RemoveLiquidityByRange { accounts: a, args } => Some(PositionEvent {
+                    pool: Some(a.lb_pair),
+                    vault_a: Some(a.reserve_x),
+                    vault_b: Some(a.reserve_y),
+                    tick_lower: Some(args.from_bin_id),
+                    tick_upper: Some(args.to_bin_id),
+                    ..PositionEvent::new(Decrease, a.position, a.sender)
+                }),
+                Ix::ClosePosition { accounts: a, .. } => Some(PositionEvent {
+                    pool: Some(a.lb_pair),
+                    ..PositionEvent::new(Close, a.position, a.sender)
+                }),
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,54 @@
+//! Stable numeric ids for protocols and instruction types
+//!
+//! Protocol ids are assigned here by hand and never reused, so renaming a
+//! parser keeps its id and queries keyed on `protocol_id` don't drift.
+//! Instruction type ids are a hash of the type name, so every indexer process
+//! derives the same id without coordinating.
+
+/// Parser name to protocol id
+///
+/// Append new parsers with the next free id. Never renumber or reuse an id;
+/// when a parser is renamed, keep its id and change the name here.
+pub const PROTOCOL_IDS: &[(&str, u16)] = &[
+    ("jupiter_v6", 1),
+    ("jupiter_v4", 2),
+    ("pump_amm", 3),
+    ("pump_fun", 4),
+    ("raydium_amm_v3", 5),
+    ("raydium_cp_swap", 6),
+    ("whirlpool", 7),
+    ("meteora_dlmm", 8),
+    ("bubblegum", 9),
+    ("address_lookup_table", 10),
+    ("jupiter_limit_order_v2", 11),
+    ("jupiter_dca", 12),
+    ("raydium_stable", 13),
+    ("saber", 14),
+    ("tensor_swap", 15),
+    ("tcomp", 16),
+    ("magic_eden_v2", 17),
+    ("squads_v4", 18),
+    ("spl_governance", 19),
+    ("wormhole_core", 20),
+    ("wormhole_token_bridge", 21),
+    ("debridge_dln", 22),
+    ("boop", 23),
+    ("moonshot", 24),
+    ("meteora_dbc", 25),
+];
+
+/// Protocol id for a parser name (0 for parsers missing from [`PROTOCOL_IDS`])
+pub fn protocol_id(parser_name: &str) -> u16 {
+    PROTOCOL_IDS
+        .iter()
+        .find(|(name, _)| *name == parser_name)
+        .map(|(_, id)| *id)
+        .unwrap_or(0)
+}
+
+/// Instruction type id: 32-bit FNV-1a of the type name (e.g. `SharedAccountsRoute`)
+pub fn instruction_type_id(instruction_type: &str) -> u32 {
+    instruction_type.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
@@ -0,0 +1,132 @@
+//! SOL and SPL token transfer decoder, plus balance delta pairing
+//!
+//! Like [`crate::lifecycle`], the System and Token programs aren't in
+//! [`crate::parser_map`]; callers check program ids against
+//! [`transfer_programs`] and call [`decode_transfer`] directly. Value that
+//! moves without a transfer instruction (lamport debits by a program, mints,
+//! burns) only shows up in balance deltas, which [`pair_deltas`] turns into
+//! approximate edges.
+
+use crate::lifecycle::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+use crate::Pubkey;
+use std::collections::HashMap;
+
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+/// System program instruction tags (u32 LE)
+const SYSTEM_TRANSFER: u32 = 2;
+const SYSTEM_TRANSFER_WITH_SEED: u32 = 11;
+
+/// SPL Token instruction tags
+const TOKEN_TRANSFER: u8 = 3;
+const TOKEN_TRANSFER_CHECKED: u8 = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferProgram {
+    System,
+    /// SPL Token or Token-2022
+    Token,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transfer {
+    /// Wallet (SOL) or token account the value leaves
+    pub source: Pubkey,
+    /// Wallet (SOL) or token account the value arrives at
+    pub destination: Pubkey,
+    /// Token mint, when the instruction names it (`TransferChecked`); None for SOL
+    pub mint: Option<Pubkey>,
+    /// Lamports or raw token units
+    pub amount: u64,
+}
+
+/// Map of program id bytes to transfer program
+pub fn transfer_programs() -> HashMap<Vec<u8>, TransferProgram> {
+    let mut map = HashMap::new();
+    map.insert(bs58::decode(SYSTEM_PROGRAM_ID).into_vec().unwrap(), TransferProgram::System);
+    map.insert(bs58::decode(TOKEN_PROGRAM_ID).into_vec().unwrap(), TransferProgram::Token);
+    map.insert(bs58::decode(TOKEN_2022_PROGRAM_ID).into_vec().unwrap(), TransferProgram::Token);
+    map
+}
+
+/// Decode a transfer, returning None for any other instruction
+pub fn decode_transfer(program: TransferProgram, data: &[u8], accounts: &[Pubkey]) -> Option<Transfer> {
+    let u64_at = |offset: usize| Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?));
+    match program {
+        TransferProgram::System => {
+            let tag = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+            match tag {
+                // [from, to]
+                SYSTEM_TRANSFER => Some(Transfer {
+                    source: *accounts.first()?,
+                    destination: *accounts.get(1)?,
+                    mint: None,
+                    amount: u64_at(4)?,
+                }),
+                // [from, base, to]
+                SYSTEM_TRANSFER_WITH_SEED => Some(Transfer {
+                    source: *accounts.first()?,
+                    destination: *accounts.get(2)?,
+                    mint: None,
+                    amount: u64_at(4)?,
+                }),
+                _ => None,
+            }
+        }
+        TransferProgram::Token => match *data.first()? {
+            // [source, destination, authority]
+            TOKEN_TRANSFER => Some(Transfer {
+                source: *accounts.first()?,
+                destination: *accounts.get(1)?,
+                mint: None,
+                amount: u64_at(1)?,
+            }),
+            // [source, mint, destination, authority]
+            TOKEN_TRANSFER_CHECKED => Some(Transfer {
+                source: *accounts.first()?,
+                destination: *accounts.get(2)?,
+                mint: accounts.get(1).copied(),
+                amount: u64_at(1)?,
+            }),
+            _ => None,
+        },
+    }
+}
+
+/// Pair net balance changes of one asset into (from, to, amount) edges
+///
+/// Greedily matches the largest outflow with the largest inflow until one
+/// side runs out, so a single sender or a single receiver is exact and
+/// anything else is a plausible, not definitive, attribution. Unmatched
+/// remainders (mints, burns, fees) produce no edge.
+pub fn pair_deltas<K: Clone + Ord>(deltas: &[(K, i128)]) -> Vec<(K, K, u64)> {
+    let mut senders: Vec<(K, u128)> = deltas
+        .iter()
+        .filter(|(_, d)| *d < 0)
+        .map(|(k, d)| (k.clone(), d.unsigned_abs()))
+        .collect();
+    let mut receivers: Vec<(K, u128)> = deltas
+        .iter()
+        .filter(|(_, d)| *d > 0)
+        .map(|(k, d)| (k.clone(), *d as u128))
+        .collect();
+    // Largest first, ties broken by key so the result is deterministic
+    senders.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    receivers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut edges = Vec::new();
+    let (mut s, mut r) = (0, 0);
+    while s < senders.len() && r < receivers.len() {
+        let amount = senders[s].1.min(receivers[r].1);
+        edges.push((senders[s].0.clone(), receivers[r].0.clone(), amount.min(u64::MAX as u128) as u64));
+        senders[s].1 -= amount;
+        receivers[r].1 -= amount;
+        if senders[s].1 == 0 {
+            s += 1;
+        }
+        if receivers[r].1 == 0 {
+            r += 1;
+        }
+    }
+    edges
+}
@@ -145,6 +145,14 @@ fn render_instructions(instructions: &[codama_nodes::InstructionNode]) -> TokenS
             }
         });
 
+        let named_accounts = instruction.accounts.iter().map(|account| {
+            let name = to_snake_case(&account.name);
+            let field_ident = format_ident!("{}", name);
+            quote! {
+                (#name, self.#field_ident)
+            }
+        });
+
         let args_fields = instruction.arguments.iter().filter_map(|argument| {
             if argument.default_value_strategy == Some(DefaultValueStrategy::Omitted) {
                 return None;
@@ -162,6 +170,13 @@ fn render_instructions(instructions: &[codama_nodes::InstructionNode]) -> TokenS
                 #(#accounts_fields),*
             }
 
+            impl #accounts_ident {
+                /// IDL account names (snake_case) and addresses, in instruction order
+                pub fn named(&self) -> Vec<(&'static str, yellowstone_vixen_core::Pubkey)> {
+                    vec![#(#named_accounts),*]
+                }
+            }
+
             #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Eq, PartialEq)]
             pub struct #args_ident {
                 #(#args_fields),*
@@ -350,6 +365,13 @@ fn render_instruction_parser(
         }
     });
 
+    let named_accounts_arms = instructions.iter().map(|instruction| {
+        let instr_ident = format_ident!("{}", to_pascal_case(&instruction.name));
+        quote! {
+            Self::#instr_ident { ref accounts, .. } => accounts.named()
+        }
+    });
+
     let instruction_matches = instructions.iter().filter_map(|instruction| {
         let discriminator = instruction.discriminators.first()?;
         let instr_ident = format_ident!("{}", to_pascal_case(&instruction.name));
@@ -468,6 +490,15 @@ fn render_instruction_parser(
             #(#instruction_enum_fields),*
         }
 
+        impl #instruction_enum_ident {
+            /// IDL account names (snake_case) and addresses of the instruction
+            pub fn named_accounts(&self) -> Vec<(&'static str, yellowstone_vixen_core::Pubkey)> {
+                match *self {
+                    #(#named_accounts_arms),*
+                }
+            }
+        }
+
         #[derive(Debug, Copy, Clone)]
         pub struct InstructionParser;
 
@@ -789,6 +820,13 @@ mod tests {
                 #[derive(Debug)]
                 pub enum TestInstruction {}
 
+                impl TestInstruction {
+                    /// IDL account names (snake_case) and addresses of the instruction
+                    pub fn named_accounts(&self) -> Vec<(&'static str, yellowstone_vixen_core::Pubkey)> {
+                        match *self {}
+                    }
+                }
+
                 #[derive(Debug, Copy, Clone)]
                 pub struct InstructionParser;
 
@@ -85,6 +85,7 @@ for i in $(seq 1 $NUM_CONTAINERS); do
       - THREADS=4
       - CLICKHOUSE_URL=${CLICKHOUSE_URL}
       - CLEAR_DB_ON_START=${CLEAR_DB}
+      - CLEAR_DB_CONFIRM=yes-i-mean-it
     restart: "no"
     networks:
       - indexer-network
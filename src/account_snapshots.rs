@@ -0,0 +1,151 @@
+//! Account state snapshot job (`[account_snapshots]`, account_snapshots table)
+//!
+//! Instructions say what was asked of a program; the accounts say what state
+//! it ended up in (pool reserves, bonding curve progress). Every
+//! `interval_slots` (by the RPC node's current slot), this reads every account
+//! of each tracked program with `getProgramAccounts` and writes one row per
+//! account with its raw data, so state can be decoded and joined at query time.
+//! Like TVL snapshots, RPC only serves current state: rows describe the chain
+//! at read time, not the slot range being backfilled.
+//!
+//! `getProgramAccounts` is expensive on programs with many accounts; narrow
+//! each program with `data_size` (e.g. only pool or bonding curve accounts)
+//! and use an RPC provider that allows the call.
+
+use crate::config::AccountSnapshotProgram;
+use crate::rpc::RpcClient;
+use crate::storage::{AccountSnapshot, ClickHouseStorage};
+use base64::Engine;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Rows per insert, so one large program doesn't become one huge block
+const ROWS_PER_INSERT: usize = 10_000;
+
+#[derive(Debug, Deserialize)]
+struct RpcContextResult<T> {
+    context: RpcContext,
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcContext {
+    slot: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+    account: Account,
+}
+
+#[derive(Debug, Deserialize)]
+struct Account {
+    lamports: u64,
+    /// `[data, "base64"]`
+    data: (String, String),
+}
+
+pub struct AccountSnapshotter {
+    rpc: Arc<RpcClient>,
+    storage: Arc<ClickHouseStorage>,
+    programs: Vec<AccountSnapshotProgram>,
+    interval_slots: u64,
+}
+
+impl AccountSnapshotter {
+    pub fn new(
+        rpc: Arc<RpcClient>,
+        storage: Arc<ClickHouseStorage>,
+        programs: Vec<AccountSnapshotProgram>,
+        interval_slots: u64,
+    ) -> Self {
+        Self {
+            rpc,
+            storage,
+            programs,
+            interval_slots,
+        }
+    }
+
+    /// Run until the task is dropped, snapshotting whenever the chain has
+    /// advanced `interval_slots` past the previous snapshot
+    pub async fn run(self) {
+        let mut last_snapshot_slot: Option<u64> = None;
+        loop {
+            let due = |slot: u64| match last_snapshot_slot {
+                Some(last) => slot >= last + self.interval_slots,
+                None => true,
+            };
+            match self.rpc.call::<u64>("getSlot", serde_json::json!([])).await {
+                Ok(Some(slot)) if due(slot) => {
+                    // Programs are independent; one failing doesn't hold back the others
+                    for program in &self.programs {
+                        match self.snapshot(program).await {
+                            Ok(count) => tracing::info!(
+                                "Wrote {} account snapshots for {} at slot {}",
+                                count,
+                                program.program_id,
+                                slot
+                            ),
+                            Err(e) => tracing::warn!("Account snapshot of {} failed: {}", program.program_id, e),
+                        }
+                    }
+                    last_snapshot_slot = Some(slot);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Account snapshot job failed to get current slot: {}", e),
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Snapshot every account of one program, returning the number of rows written
+    pub async fn snapshot(&self, program: &AccountSnapshotProgram) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut filters = Vec::new();
+        if let Some(size) = program.data_size {
+            filters.push(serde_json::json!({ "dataSize": size }));
+        }
+        let result: Option<RpcContextResult<Vec<KeyedAccount>>> = self
+            .rpc
+            .call(
+                "getProgramAccounts",
+                serde_json::json!([
+                    program.program_id,
+                    { "encoding": "base64", "withContext": true, "filters": filters }
+                ]),
+            )
+            .await?;
+        let Some(result) = result else { return Ok(0) };
+
+        let snapshot_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let rows: Vec<AccountSnapshot> = result
+            .value
+            .into_iter()
+            .filter_map(|keyed| {
+                let data = base64::engine::general_purpose::STANDARD
+                    .decode(&keyed.account.data.0)
+                    .ok()?;
+                Some(AccountSnapshot {
+                    snapshot_slot: result.context.slot,
+                    snapshot_time,
+                    program_id: program.program_id.clone(),
+                    account: keyed.pubkey,
+                    lamports: keyed.account.lamports,
+                    discriminator: hex::encode(&data[..data.len().min(8)]),
+                    data_len: data.len() as u32,
+                    data: keyed.account.data.0,
+                })
+            })
+            .collect();
+        for chunk in rows.chunks(ROWS_PER_INSERT) {
+            self.storage.insert_account_snapshots(chunk).await?;
+        }
+        Ok(rows.len())
+    }
+}
@@ -0,0 +1,219 @@
+//! Address Lookup Table (ALT) resolver
+//!
+//! Keeps a cache of lookup table contents, built from indexed ALT instructions
+//! (decoded by `solixdb_decoder::alt`), so V0 transactions with missing
+//! `loaded_addresses` can still be resolved. Tables the index hasn't seen can
+//! be fetched from RPC with [`AltFetcher`].
+
+use crate::rpc::RpcClient;
+use base64::Engine;
+use serde::Deserialize;
+use solana_address::Address;
+use solana_message::v0::MessageAddressTableLookup;
+pub use solixdb_decoder::alt::AltInstruction;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use tokio::sync::Mutex;
+
+/// Serialized lookup table metadata that precedes the addresses in the account data
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// How a transaction's loaded addresses were obtained (transactions.resolution_source)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolutionSource {
+    /// Static keys plus meta's `loaded_addresses` (every legacy transaction)
+    Meta,
+    /// Lookup table contents indexed from ALT instructions
+    AltIndex,
+    /// Lookup table accounts fetched from RPC
+    Rpc,
+    /// Meta had no loaded addresses and no table source could fill them;
+    /// instructions only see the static keys
+    Unresolved,
+    /// Rebuilt by `--reprocess-failed` from the accounts stored with the failure
+    Reprocessed,
+}
+
+impl ResolutionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Meta => "meta",
+            Self::AltIndex => "alt_index",
+            Self::Rpc => "rpc",
+            Self::Unresolved => "unresolved",
+            Self::Reprocessed => "reprocessed",
+        }
+    }
+}
+
+/// Encode an address as base58
+pub fn address_to_string(address: &Address) -> String {
+    bs58::encode(address.to_bytes()).into_string()
+}
+
+/// Decode a base58 address, returning None if it isn't a valid 32-byte key
+pub fn parse_address(s: &str) -> Option<Address> {
+    let bytes: [u8; 32] = bs58::decode(s).into_vec().ok()?.try_into().ok()?;
+    Some(Address::from(bytes))
+}
+
+/// In-memory view of lookup table contents, built from indexed ALT instructions
+///
+/// Extensions only ever append, so a table's current contents are a valid
+/// resolution for any earlier transaction that referenced it (as long as the
+/// table wasn't closed and recreated in between).
+#[derive(Default)]
+pub struct AltCache {
+    tables: RwLock<HashMap<Address, Vec<Address>>>,
+}
+
+impl AltCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a decoded ALT instruction for the given table
+    pub fn apply(&self, table: Address, instruction: &AltInstruction) {
+        let mut tables = self.tables.write().unwrap_or_else(|e| e.into_inner());
+        match instruction {
+            AltInstruction::CreateLookupTable { .. } => {
+                tables.entry(table).or_default();
+            }
+            AltInstruction::ExtendLookupTable { new_addresses } => {
+                tables
+                    .entry(table)
+                    .or_default()
+                    .extend(new_addresses.iter().map(|key| Address::from(key.0)));
+            }
+            AltInstruction::CloseLookupTable => {
+                tables.remove(&table);
+            }
+            AltInstruction::FreezeLookupTable | AltInstruction::DeactivateLookupTable => {}
+        }
+    }
+
+    /// Replace a table's contents with a fetched copy, unless the index already has it
+    pub fn insert(&self, table: Address, addresses: Vec<Address>) {
+        let mut tables = self.tables.write().unwrap_or_else(|e| e.into_inner());
+        tables.entry(table).or_insert(addresses);
+    }
+
+    /// Tables referenced by the lookups that the cache has no contents for
+    pub fn missing(&self, lookups: &[MessageAddressTableLookup]) -> Vec<Address> {
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        lookups
+            .iter()
+            .map(|lookup| lookup.account_key)
+            .filter(|key| !tables.contains_key(key))
+            .collect()
+    }
+
+    /// Number of tables currently cached
+    pub fn len(&self) -> usize {
+        self.tables.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Resolve loaded addresses for a V0 message's lookups
+    ///
+    /// Returns (writable, readonly) in the same order the runtime loads them,
+    /// or None if any referenced table or index is unknown.
+    pub fn resolve(
+        &self,
+        lookups: &[MessageAddressTableLookup],
+    ) -> Option<(Vec<Address>, Vec<Address>)> {
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        let mut writable = Vec::new();
+        let mut readonly = Vec::new();
+        for lookup in lookups {
+            let entries = tables.get(&lookup.account_key)?;
+            for idx in &lookup.writable_indexes {
+                writable.push(*entries.get(*idx as usize)?);
+            }
+            for idx in &lookup.readonly_indexes {
+                readonly.push(*entries.get(*idx as usize)?);
+            }
+        }
+        Some((writable, readonly))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountInfoResult {
+    value: Option<AccountInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountInfo {
+    /// `[data, encoding]`
+    data: (String, String),
+}
+
+/// Fetches lookup tables the index hasn't seen via `getAccountInfo`
+///
+/// The fetched contents are the table's current state. That resolves any
+/// earlier transaction, since tables only grow, unless the table was closed
+/// and recreated at the same address in between. Each table is requested at
+/// most once per run; closed tables and failed requests stay unresolved.
+pub struct AltFetcher {
+    rpc: Arc<RpcClient>,
+    attempted: Mutex<HashSet<Address>>,
+}
+
+impl AltFetcher {
+    pub fn new(rpc: Arc<RpcClient>) -> Self {
+        Self {
+            rpc,
+            attempted: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Fetch the lookups' tables missing from `cache` into it, returning the
+    /// resolved (writable, readonly) addresses if every table is now known
+    pub async fn resolve(
+        &self,
+        cache: &AltCache,
+        lookups: &[MessageAddressTableLookup],
+    ) -> Option<(Vec<Address>, Vec<Address>)> {
+        for table in cache.missing(lookups) {
+            // Holding the lock across the fetch makes concurrent callers wait
+            // for a single request per table
+            let mut attempted = self.attempted.lock().await;
+            if !attempted.insert(table) {
+                continue;
+            }
+            match self.fetch(&table).await {
+                Ok(Some(addresses)) => cache.insert(table, addresses),
+                Ok(None) => tracing::debug!("Lookup table {} not found via RPC", address_to_string(&table)),
+                Err(e) => tracing::warn!("Failed to fetch lookup table {}: {}", address_to_string(&table), e),
+            }
+        }
+        cache.resolve(lookups)
+    }
+
+    async fn fetch(&self, table: &Address) -> Result<Option<Vec<Address>>, Box<dyn std::error::Error + Send + Sync>> {
+        let result: Option<AccountInfoResult> = self
+            .rpc
+            .call(
+                "getAccountInfo",
+                serde_json::json!([address_to_string(table), { "encoding": "base64" }]),
+            )
+            .await?;
+        let Some(account) = result.and_then(|result| result.value) else {
+            return Ok(None);
+        };
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&account.data.0)
+            .map_err(|e| format!("invalid base64 account data: {}", e))?;
+        let addresses = data
+            .get(LOOKUP_TABLE_META_SIZE..)
+            .ok_or("account data shorter than lookup table metadata")?
+            .chunks_exact(32)
+            .map(|key| Address::from(<[u8; 32]>::try_from(key).unwrap()))
+            .collect();
+        Ok(Some(addresses))
+    }
+}
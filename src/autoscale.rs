@@ -0,0 +1,108 @@
+//! Idle-aware firehose thread autoscaling
+//!
+//! jetstreamer fixes its thread count for the duration of a `firehose` call, so
+//! auto mode splits the slot range into chunks and picks each chunk's thread
+//! count from how the previous chunk went: CPU utilization (from `/proc/stat`,
+//! Linux only) and storage backpressure (the share of pipeline time spent in
+//! buffer enqueue, which includes synchronous batch flushes).
+
+use crate::config::AutoscaleConfig;
+use crate::stage_metrics::{Stage, StageMetrics};
+use std::time::Duration;
+
+/// Scale up while CPU utilization is below this
+const CPU_LOW: f64 = 0.70;
+/// Scale down above this (the machine is saturated)
+const CPU_HIGH: f64 = 0.95;
+/// Scale down when storage takes more than this share of pipeline time
+pub(crate) const STORAGE_BOUND: f64 = 0.50;
+
+/// Aggregate CPU counters from /proc/stat: (busy, total) jiffies
+fn read_cpu_times() -> Option<(u64, u64)> {
+    let stat = std::fs::read_to_string("/proc/stat").ok()?;
+    let line = stat.lines().next()?.strip_prefix("cpu ")?;
+    let fields: Vec<u64> = line.split_whitespace().filter_map(|f| f.parse().ok()).collect();
+    let total: u64 = fields.iter().sum();
+    // idle + iowait
+    let idle = fields.get(3)? + fields.get(4).copied().unwrap_or(0);
+    Some((total - idle, total))
+}
+
+/// Counters captured at the start of a chunk
+pub struct ChunkSample {
+    cpu: Option<(u64, u64)>,
+    /// (all stages, buffer enqueue) time recorded so far
+    stage_time: (Duration, Duration),
+}
+
+/// (all stages, buffer enqueue) time recorded so far
+pub(crate) fn stage_time(stage_metrics: &StageMetrics) -> (Duration, Duration) {
+    let total = Stage::ALL.iter().map(|s| stage_metrics.histogram(*s).sum()).sum();
+    (total, stage_metrics.histogram(Stage::BufferEnqueue).sum())
+}
+
+pub struct Autoscaler {
+    min_threads: usize,
+    max_threads: usize,
+    threads: usize,
+}
+
+impl Autoscaler {
+    /// Start from `initial` threads, clamped to the configured bounds
+    pub fn new(config: &AutoscaleConfig, initial: usize) -> Self {
+        Self {
+            min_threads: config.min_threads,
+            max_threads: config.max_threads,
+            threads: initial.clamp(config.min_threads, config.max_threads),
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn begin(&self, stage_metrics: &StageMetrics) -> ChunkSample {
+        ChunkSample {
+            cpu: read_cpu_times(),
+            stage_time: stage_time(stage_metrics),
+        }
+    }
+
+    /// Adjust the thread count for the next chunk from what happened since `sample`
+    pub fn end(&mut self, sample: ChunkSample, stage_metrics: &StageMetrics) {
+        let cpu = match (sample.cpu, read_cpu_times()) {
+            (Some((busy0, total0)), Some((busy1, total1))) if total1 > total0 => {
+                Some((busy1 - busy0) as f64 / (total1 - total0) as f64)
+            }
+            _ => None,
+        };
+        let (total_now, enqueue_now) = stage_time(stage_metrics);
+        let total = total_now.saturating_sub(sample.stage_time.0);
+        let enqueue = enqueue_now.saturating_sub(sample.stage_time.1);
+        let storage_share = if total.is_zero() {
+            0.0
+        } else {
+            enqueue.as_secs_f64() / total.as_secs_f64()
+        };
+
+        let previous = self.threads;
+        self.threads = if storage_share > STORAGE_BOUND || cpu.is_some_and(|c| c > CPU_HIGH) {
+            previous.saturating_sub(1)
+        } else if cpu.map_or(storage_share < STORAGE_BOUND / 2.0, |c| c < CPU_LOW) {
+            // Grow by half (at least one thread) while there's headroom; without CPU
+            // stats, only while storage is keeping up comfortably
+            previous + (previous / 2).max(1)
+        } else {
+            previous
+        }
+        .clamp(self.min_threads, self.max_threads);
+
+        tracing::info!(
+            "Autoscale: cpu {}, storage {:.0}% of pipeline time, threads {} -> {}",
+            cpu.map(|c| format!("{:.0}%", c * 100.0)).unwrap_or_else(|| "n/a".to_string()),
+            storage_share * 100.0,
+            previous,
+            self.threads
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Download bandwidth limits for the firehose (`[firehose] max_mb_per_sec`, `max_total_mb`)
+//!
+//! jetstreamer does its own HTTP reads, so the limiter can't sit in front of
+//! them. Instead it samples the bytes received by the host's network
+//! interfaces (`/proc/net/dev`, loopback excluded) a few times a second:
+//!
+//! - Over `max_mb_per_sec` (a token bucket with one second of burst), the
+//!   transaction handler pauses until the rate is back under the cap. Paused
+//!   handlers stop the firehose consuming its streams, which throttles the
+//!   downloads behind them.
+//! - Past `max_total_mb` for the run, the exhaustion callback fires once; the
+//!   indexer stops the run the way an aborted firehose does, so everything
+//!   buffered is flushed and `--resume` continues from the watermark.
+//!
+//! Everything the host receives counts (ClickHouse responses and RPC calls
+//! too, and other processes in the same network namespace), so the figures
+//! are an upper bound on archive traffic. Linux only: elsewhere, and in
+//! containers without `/proc/net/dev`, the limits are logged and ignored.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+const NET_DEV: &str = "/proc/net/dev";
+const MB: u64 = 1024 * 1024;
+
+#[derive(Debug)]
+pub struct BandwidthLimiter {
+    /// Bytes per second (0 = unlimited)
+    max_rate: u64,
+    /// Bytes for the run (0 = unlimited)
+    max_total: u64,
+    /// Bytes received since the limiter started
+    received: AtomicU64,
+    /// Handlers wait while the token bucket is empty
+    paused: AtomicBool,
+    exhausted: AtomicBool,
+}
+
+impl BandwidthLimiter {
+    /// None unless a limit is set and `/proc/net/dev` is readable
+    pub fn new(max_mb_per_sec: u64, max_total_mb: u64) -> Option<Arc<Self>> {
+        if max_mb_per_sec == 0 && max_total_mb == 0 {
+            return None;
+        }
+        if received_bytes().is_none() {
+            tracing::warn!("{} is not readable; firehose bandwidth limits are ignored", NET_DEV);
+            return None;
+        }
+        Some(Arc::new(Self {
+            max_rate: max_mb_per_sec * MB,
+            max_total: max_total_mb * MB,
+            received: AtomicU64::new(0),
+            paused: AtomicBool::new(false),
+            exhausted: AtomicBool::new(false),
+        }))
+    }
+
+    /// Bytes received since the limiter started
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    pub fn exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::Relaxed)
+    }
+
+    /// Wait while the download rate is over the cap
+    pub async fn throttle(&self) {
+        while self.paused.load(Ordering::Relaxed) && !self.exhausted() {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+        }
+    }
+
+    /// Sample the interfaces until the task is dropped, calling `on_exhausted`
+    /// once the run's total budget is used up
+    pub async fn run(self: Arc<Self>, on_exhausted: impl FnOnce(String)) {
+        let Some(baseline) = received_bytes() else { return };
+        let mut on_exhausted = Some(on_exhausted);
+        let mut last = baseline;
+        let mut last_at = Instant::now();
+        // Token bucket: one second of burst at the capped rate
+        let mut tokens = self.max_rate as f64;
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            let Some(now) = received_bytes() else { continue };
+            let elapsed = last_at.elapsed().as_secs_f64();
+            let delta = now.saturating_sub(last);
+            (last, last_at) = (now, Instant::now());
+            self.received.store(now.saturating_sub(baseline), Ordering::Relaxed);
+
+            if self.max_rate > 0 {
+                let rate = self.max_rate as f64;
+                tokens = (tokens + rate * elapsed).min(rate) - delta as f64;
+                let paused = tokens < 0.0;
+                let was_paused = self.paused.swap(paused, Ordering::Relaxed);
+                if paused && !was_paused {
+                    tracing::debug!(
+                        "Download rate over {} MB/s, pausing the firehose",
+                        self.max_rate / MB
+                    );
+                }
+            }
+
+            if self.max_total > 0 && self.received() >= self.max_total {
+                self.exhausted.store(true, Ordering::Relaxed);
+                if let Some(on_exhausted) = on_exhausted.take() {
+                    on_exhausted(format!(
+                        "download budget of {} MB exhausted ({} MB received)",
+                        self.max_total / MB,
+                        self.received() / MB
+                    ));
+                }
+            }
+        }
+    }
+
+    /// One line for the run summary
+    pub fn summary(&self) -> String {
+        let mut line = format!("Downloaded: {:.1} MB", self.received() as f64 / MB as f64);
+        if self.max_total > 0 {
+            line.push_str(&format!(" of {} MB budget", self.max_total / MB));
+        }
+        if self.max_rate > 0 {
+            line.push_str(&format!(", capped at {} MB/s", self.max_rate / MB));
+        }
+        line
+    }
+}
+
+/// Bytes received by every interface but loopback, from `/proc/net/dev`
+pub(crate) fn received_bytes() -> Option<u64> {
+    let dev = std::fs::read_to_string(NET_DEV).ok()?;
+    Some(
+        dev.lines()
+            .skip(2)
+            .filter_map(|line| {
+                let (interface, counters) = line.split_once(':')?;
+                if interface.trim() == "lo" {
+                    return None;
+                }
+                counters.split_whitespace().next()?.parse::<u64>().ok()
+            })
+            .sum(),
+    )
+}
@@ -0,0 +1,49 @@
+//! Flushing and alerting on aged buffers (`[clickhouse] buffer_age_alert_secs`)
+//!
+//! Buffers otherwise flush only at `batch_size` rows, at checkpoints and at
+//! the end of a run, so a quiet table can hold a handful of rows for as long
+//! as the run lasts. Once any table's oldest unwritten row reaches the
+//! threshold, every buffer is flushed; one that is still there at twice the
+//! threshold means flushes aren't getting through (a stuck insert or an
+//! unreachable ClickHouse), and each such table is logged with its buffered
+//! row count. The ages are exported as `solixdb_buffer_oldest_row_age_seconds`
+//! either way.
+
+use crate::storage::ClickHouseStorage;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Check buffer ages until the task is dropped
+pub async fn run(storage: Arc<ClickHouseStorage>, threshold: Duration) {
+    let flushing = Arc::new(AtomicBool::new(false));
+    let mut interval = tokio::time::interval(threshold.min(MAX_CHECK_INTERVAL));
+    loop {
+        interval.tick().await;
+        let buffered = storage.watermark().buffered();
+        for table in buffered.iter().filter(|table| table.rows > 0 && table.oldest >= threshold * 2) {
+            tracing::warn!(
+                "{} rows of {} have been buffered for {}s (threshold {}s); is a flush stuck or ClickHouse unreachable?",
+                table.rows,
+                table.table,
+                table.oldest.as_secs(),
+                threshold.as_secs()
+            );
+        }
+        // A flush in progress is left alone: dropping it would lose the rows it drained
+        if buffered.iter().any(|table| table.rows > 0 && table.oldest >= threshold)
+            && !flushing.swap(true, Ordering::SeqCst)
+        {
+            let storage = Arc::clone(&storage);
+            let flushing = Arc::clone(&flushing);
+            tokio::spawn(async move {
+                if let Err(e) = storage.flush_all().await {
+                    tracing::warn!("Flushing aged buffers failed: {}", e);
+                }
+                flushing.store(false, Ordering::SeqCst);
+            });
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Per-chunk resume checkpoints (range_checkpoints)
+//!
+//! jetstreamer splits a firehose call's range across its threads internally,
+//! so a crash mid-call leaves no record of which thread finished what. With
+//! `[checkpoint] enabled = true` the run is cut into `chunk_slots` chunks
+//! (autoscale's chunks when autoscaling), one firehose call each, and a chunk
+//! is checkpointed once it has been processed and its rows flushed. A
+//! `--resume` run over the same range and parser set only processes chunks
+//! not yet covered by checkpoints, so at most the chunks in flight at the
+//! crash are redone.
+
+use std::ops::Range;
+
+/// Cut `range` into `chunk_slots` chunks, dropping those entirely covered by
+/// the `completed` ranges (which may overlap or use a different chunk size)
+pub fn pending_chunks(range: Range<u64>, chunk_slots: u64, completed: &[(u64, u64)]) -> Vec<Range<u64>> {
+    let mut completed = completed.to_vec();
+    completed.sort_unstable();
+    // Merge overlapping and adjacent ranges
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+    for (start, end) in completed {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    let covered = |chunk: &Range<u64>| {
+        merged
+            .iter()
+            .any(|(start, end)| *start <= chunk.start && chunk.end <= *end)
+    };
+
+    let chunk_slots = chunk_slots.max(1);
+    let mut chunks = Vec::new();
+    let mut start = range.start;
+    while start < range.end {
+        let chunk = start..start.saturating_add(chunk_slots).min(range.end);
+        start = chunk.end;
+        if !covered(&chunk) {
+            chunks.push(chunk);
+        }
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_checkpoints() {
+        assert_eq!(pending_chunks(0..250, 100, &[]), [0..100, 100..200, 200..250]);
+        assert_eq!(pending_chunks(0..0, 100, &[]), []);
+    }
+
+    #[test]
+    fn test_overlapping_and_adjacent_ranges_merge() {
+        // 0..100 and 100..200 are only covered together; 150..300 overlaps both
+        let completed = [(100, 200), (0, 100), (150, 300)];
+        assert_eq!(pending_chunks(0..400, 100, &completed), [300..400]);
+        // A chunk straddling two ranges with a gap between them stays pending
+        assert_eq!(pending_chunks(0..200, 200, &[(0, 90), (110, 200)]), [0..200]);
+    }
+
+    #[test]
+    fn test_different_chunk_size() {
+        // Checkpointed in 50-slot chunks, resumed with 100-slot chunks
+        let completed = [(0, 50), (50, 100), (100, 150)];
+        assert_eq!(pending_chunks(0..300, 100, &completed), [100..200, 200..300]);
+        // and the other way round
+        assert_eq!(pending_chunks(0..200, 50, &[(0, 100)]), [100..150, 150..200]);
+    }
+
+    #[test]
+    fn test_fully_covered_range() {
+        assert_eq!(pending_chunks(100..300, 100, &[(0, 1_000)]), []);
+        assert_eq!(pending_chunks(100..300, 100, &[(100, 200), (200, 300)]), []);
+    }
+}
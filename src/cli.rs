@@ -0,0 +1,126 @@
+//! Command line arguments
+//!
+//! Runtime settings (slots, ClickHouse, threads) come from config.toml and env
+//! vars; flags here are for run modes that don't belong in a config file.
+
+use crate::cursor_export::Cursor;
+use crate::warehouse::Warehouse;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(name = "solixdb-indexer", version, about = "Solana protocol indexer for ClickHouse")]
+pub struct Cli {
+    /// Write a sample of raw parser inputs (with transaction context) to this
+    /// directory as JSON fixtures
+    #[arg(long, value_name = "DIR")]
+    pub capture_fixtures: Option<PathBuf>,
+
+    /// Fraction of parsed instructions to capture with --capture-fixtures
+    #[arg(long, default_value_t = 0.001, value_name = "RATE")]
+    pub sample_rate: f64,
+
+    /// Run even if indexed_ranges shows the slot range was already indexed
+    /// with the same parser set
+    #[arg(long)]
+    pub force: bool,
+
+    /// Continue an interrupted run over the same slot range and parser set,
+    /// skipping chunks already recorded in range_checkpoints (requires
+    /// `[checkpoint] enabled`)
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Report signatures in derived tables (alt_updates, liquidity_positions,
+    /// compressed_assets) with no transactions row in the slot range, then exit
+    #[arg(long)]
+    pub check_consistency: bool,
+
+    /// Reparse this protocol's failed_transactions rows in the slot range with
+    /// the current parsers, moving recovered instructions to transactions, then exit
+    #[arg(long, value_name = "PROTOCOL")]
+    pub reprocess_failed: Option<String>,
+
+    /// Group the slot range's swaps and trades into per-wallet sessions
+    /// (`[wallet_sessions] gap_secs` of inactivity apart) in wallet_sessions, then exit
+    #[arg(long)]
+    pub wallet_sessions: bool,
+
+    /// Compute FIFO realized PnL per wallet and mint (in SOL or USDC) from the
+    /// slot range's swaps and trades into wallet_pnl, then exit
+    #[arg(long)]
+    pub wallet_pnl: bool,
+
+    /// Export the slot range's tables to `[export] destination` as Parquet and
+    /// write a load script for this warehouse, then exit
+    #[arg(long, value_name = "WAREHOUSE")]
+    pub export_warehouse: Option<Warehouse>,
+
+    /// Rewrite failed_transactions rows in the slot range into
+    /// `[processing] raw_data_encoding`, then exit
+    #[arg(long)]
+    pub migrate_raw_data: bool,
+
+    /// After a complete run, OPTIMIZE ... FINAL the partitions holding the
+    /// slot range and report their compression before and after (replaces
+    /// `[backfill] optimize_final` for the run)
+    #[arg(long)]
+    pub optimize_written: bool,
+
+    /// Write the end-of-run summary (per-protocol counts, storage stats,
+    /// compaction, validation) to this file as JSON
+    #[arg(long, value_name = "PATH")]
+    pub report_json: Option<PathBuf>,
+
+    /// Index the slot range into tables under this prefix (e.g. `v2_`) instead
+    /// of the configured ones, then report row count and instruction type
+    /// differences against the configured tables
+    #[arg(long, value_name = "PREFIX")]
+    pub reindex_into: Option<String>,
+
+    /// Confirm `[clickhouse] clear_on_start` (or set CLEAR_DB_CONFIRM=yes-i-mean-it);
+    /// without it a run with clear_on_start refuses to start
+    #[arg(long)]
+    pub yes_i_mean_it: bool,
+
+    /// Exit non-zero if the end-of-run validation finds discrepancies
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Show a live terminal dashboard (slot progress, per-protocol rates,
+    /// buffers, insert latency, recent errors) instead of log lines while
+    /// indexing (requires the `tui` feature)
+    #[arg(long)]
+    pub tui: bool,
+
+    /// Write the table's rows after --cursor to stdout as JSON lines, in
+    /// (slot, signature) order, then exit. The next cursor is logged (on
+    /// stderr) and saved to --cursor-file.
+    #[arg(long, value_name = "TABLE")]
+    pub export_after: Option<String>,
+
+    /// Cursor (SLOT:SIGNATURE) to export rows after; defaults to the one in
+    /// --cursor-file, or the start of the table
+    #[arg(long, value_name = "CURSOR", requires = "export_after")]
+    pub cursor: Option<Cursor>,
+
+    /// Read the cursor to export after from this file and write the next one
+    /// back once the page is written
+    #[arg(long, value_name = "PATH", requires = "export_after")]
+    pub cursor_file: Option<PathBuf>,
+
+    /// Signatures per --export-after page
+    #[arg(long, default_value_t = 10_000, value_name = "N")]
+    pub export_limit: u64,
+
+    /// Print the CREATE/ALTER statements the indexer runs at startup, with
+    /// this config's prefix, partitioning and timezone, then exit (no server
+    /// is contacted)
+    #[arg(long)]
+    pub dump_schema: bool,
+
+    /// Serve indexed tables over Arrow Flight on this address instead of
+    /// indexing (requires the `flight` feature)
+    #[arg(long, value_name = "ADDR")]
+    pub serve_flight: Option<std::net::SocketAddr>,
+}
@@ -1,4 +1,13 @@
+use crate::epoch::SLOTS_PER_EPOCH;
+use crate::firehose_errors::FirehoseErrorPolicy;
+use crate::fixtures::{sample_by_signature, SampleWindow};
+use crate::logging::{LogFormat, LogRotation};
+use crate::network::Network;
+use crate::payload_limits::TRUNCATION_MARKER;
+use crate::raw_data::RawDataEncoding;
+use crate::storage::PartitionStrategy;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,6 +15,596 @@ pub struct Config {
     pub slots: SlotConfig,
     pub clickhouse: ClickHouseConfig,
     pub processing: ProcessingConfig,
+    /// Per-protocol settings keyed by parser name (e.g. `[protocols.jupiter_v6]`)
+    #[serde(default)]
+    pub protocols: HashMap<String, ProtocolConfig>,
+    #[serde(default)]
+    pub rpc: RpcConfig,
+    #[serde(default)]
+    pub enrichment: EnrichmentConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub tvl: TvlConfig,
+    #[serde(default)]
+    pub account_snapshots: AccountSnapshotsConfig,
+    #[serde(default)]
+    pub firehose: FirehoseConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub autoscale: AutoscaleConfig,
+    #[serde(default)]
+    pub filters: FiltersConfig,
+    #[serde(default)]
+    pub transfer_edges: TransferEdgesConfig,
+    #[serde(default)]
+    pub slot_status: SlotStatusConfig,
+    #[serde(default)]
+    pub slot_time_oracle: SlotTimeOracleConfig,
+    #[serde(default)]
+    pub hourly_summary: HourlySummaryConfig,
+    #[serde(default)]
+    pub daily_users: DailyUsersConfig,
+    #[serde(default)]
+    pub protocol_cooccurrence: ProtocolCooccurrenceConfig,
+    #[serde(default)]
+    pub log_invocations: LogInvocationsConfig,
+    #[serde(default)]
+    pub wallet_first_seen: WalletFirstSeenConfig,
+    #[serde(default)]
+    pub pools: PoolsConfig,
+    #[serde(default)]
+    pub backfill: BackfillConfig,
+    #[serde(default)]
+    pub checkpoint: CheckpointConfig,
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub stale_idl: StaleIdlConfig,
+    #[serde(default)]
+    pub failed_rows: FailedRowsConfig,
+    #[serde(default)]
+    pub payload_limits: PayloadLimitsConfig,
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub wallet_sessions: WalletSessionsConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+}
+
+/// Log output (see `logging`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// `pretty` (human-readable) or `json` (one object per line)
+    pub format: LogFormat,
+    /// `EnvFilter` directive, e.g. `info` or `warn,solixdb_indexer=debug`
+    pub level: String,
+    /// Also write logs to rolling files in this directory
+    pub dir: Option<String>,
+    /// Log file name; the rotation date is appended
+    pub file_prefix: String,
+    /// `daily`, `hourly` or `never`
+    pub rotation: LogRotation,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::Pretty,
+            level: "info".to_string(),
+            dir: None,
+            file_prefix: "solixdb-indexer.log".to_string(),
+            rotation: LogRotation::Daily,
+        }
+    }
+}
+
+/// Staged Parquet export for warehouses (`--export-warehouse`, see `warehouse`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// `s3://bucket/prefix`, `gs://bucket/prefix` or an S3-compatible https URL
+    pub destination: String,
+    /// Tables to export; empty means every per-transaction table
+    pub tables: Vec<String>,
+    /// S3 access key, or a GCS HMAC key; empty uses the ClickHouse server's own credentials
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Local directory for the generated load scripts
+    pub manifest_dir: String,
+    pub bigquery_dataset: String,
+    pub snowflake_stage: String,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            destination: String::new(),
+            tables: Vec::new(),
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            manifest_dir: "export".to_string(),
+            bigquery_dataset: "solixdb".to_string(),
+            snowflake_stage: "solixdb_stage".to_string(),
+        }
+    }
+}
+
+/// Wallet session grouping (`--wallet-sessions`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalletSessionsConfig {
+    /// Seconds without a swap or trade that end a wallet's session
+    pub gap_secs: u64,
+}
+
+impl Default for WalletSessionsConfig {
+    fn default() -> Self {
+        Self { gap_secs: 1_800 }
+    }
+}
+
+/// Work-stealing slot scheduling (see `scheduler`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub work_stealing: bool,
+    /// Slots per chunk handed to a worker
+    pub chunk_slots: u64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            work_stealing: false,
+            chunk_slots: 2_000,
+        }
+    }
+}
+
+/// Liveness rows in indexer_heartbeats (see `heartbeat::Heartbeat`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    /// Seconds between heartbeats; 0 disables them
+    pub interval_secs: u64,
+    /// Defaults to the host name (the container id under Docker)
+    pub instance_id: Option<String>,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 30,
+            instance_id: None,
+        }
+    }
+}
+
+/// Unknown discriminator reporting (see `discriminators::UnknownDiscriminators`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaleIdlConfig {
+    /// Discriminator-mismatch failures of one (program, discriminator) in a run
+    /// before it is reported; 0 disables reporting
+    pub min_failures: u64,
+}
+
+impl Default for StaleIdlConfig {
+    fn default() -> Self {
+        Self { min_failures: 100 }
+    }
+}
+
+/// Caps on failed_transactions rows (see `failed_rows::FailedRowLimiter`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FailedRowsConfig {
+    /// Failed rows stored per run; 0 means unlimited
+    pub max_rows: u64,
+    /// Failed rows stored per protocol per run; 0 means unlimited
+    pub max_rows_per_protocol: u64,
+    /// Fraction of failures stored, chosen by signature
+    pub sample_rate: f64,
+}
+
+impl Default for FailedRowsConfig {
+    fn default() -> Self {
+        Self {
+            max_rows: 0,
+            max_rows_per_protocol: 0,
+            sample_rate: 1.0,
+        }
+    }
+}
+
+/// Size caps on stored logs and instruction data (see `payload_limits`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PayloadLimitsConfig {
+    /// Bytes per log/error text field; 0 means unlimited
+    pub max_text_bytes: usize,
+    /// Bytes per instruction data or `Program data:` field; 0 means unlimited
+    pub max_data_bytes: usize,
+}
+
+impl Default for PayloadLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_text_bytes: 65_536,
+            max_data_bytes: 65_536,
+        }
+    }
+}
+
+/// SOL/token flow edges for every stored transaction (transfer_edges table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransferEdgesConfig {
+    pub enabled: bool,
+}
+
+/// Produced/skipped status of every slot in the range (slot_status table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlotStatusConfig {
+    pub enabled: bool,
+}
+
+/// Block times from real blocks (slot_time_oracle table, see `slot_time`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlotTimeOracleConfig {
+    pub enabled: bool,
+}
+
+impl Default for SlotTimeOracleConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// In-process per-hour protocol totals (protocol_hourly_summary table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HourlySummaryConfig {
+    pub enabled: bool,
+}
+
+/// In-process per-day unique user sketches (protocol_daily_users table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyUsersConfig {
+    pub enabled: bool,
+}
+
+/// In-process per-day protocol pair counts (protocol_cooccurrence table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProtocolCooccurrenceConfig {
+    pub enabled: bool,
+}
+
+/// Invocation trees of transactions with parse failures (log_invocations table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogInvocationsConfig {
+    pub enabled: bool,
+}
+
+/// First slot/protocol per fee-paying wallet (wallet_first_seen table)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalletFirstSeenConfig {
+    pub enabled: bool,
+}
+
+/// Pool dimension table from the pools seen in swaps (see `pools`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolsConfig {
+    pub enabled: bool,
+    /// Look up the mints of pools whose swaps don't name them via [rpc] url
+    pub resolve_via_rpc: bool,
+}
+
+/// Per-chunk resume checkpoints (see `checkpoint`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckpointConfig {
+    pub enabled: bool,
+    /// Slots per checkpointed chunk (autoscale's `chunk_slots` is used when autoscaling)
+    pub chunk_slots: u64,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chunk_slots: 10_000,
+        }
+    }
+}
+
+/// Periodic self-maintenance (see `maintenance`). Each task runs every
+/// `interval_secs` in the background; 0 disables it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaintenanceConfig {
+    pub optimize_partitions: OptimizePartitionsTask,
+    pub vacuum_checkpoints: VacuumCheckpointsTask,
+}
+
+/// OPTIMIZE partitions of the per-transaction tables that have piled up parts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptimizePartitionsTask {
+    pub interval_secs: u64,
+    /// Active parts a partition may have before it is merged
+    pub max_parts: u64,
+}
+
+impl Default for OptimizePartitionsTask {
+    fn default() -> Self {
+        Self {
+            interval_secs: 0,
+            max_parts: 100,
+        }
+    }
+}
+
+/// Delete old range_checkpoints rows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VacuumCheckpointsTask {
+    pub interval_secs: u64,
+    /// Checkpoints of complete runs older than this are deleted; unfinished runs keep theirs
+    pub retention_days: u64,
+}
+
+impl Default for VacuumCheckpointsTask {
+    fn default() -> Self {
+        Self {
+            interval_secs: 0,
+            retention_days: 30,
+        }
+    }
+}
+
+/// Insert-friendly ClickHouse settings for bulk backfills
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackfillConfig {
+    pub enabled: bool,
+    pub max_insert_block_size: u64,
+    pub min_insert_block_size_rows: u64,
+    /// Pause background merges on the indexer tables during the run
+    pub stop_merges: bool,
+    /// OPTIMIZE TABLE ... FINAL every table after a successful run
+    pub optimize_final: bool,
+}
+
+impl Default for BackfillConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_insert_block_size: 4_194_304,
+            min_insert_block_size_rows: 4_194_304,
+            stop_merges: false,
+            optimize_final: true,
+        }
+    }
+}
+
+/// Ingest-time account filters (see `filters::AccountFilter`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FiltersConfig {
+    /// Only store transactions that reference at least one of these accounts. Empty means all.
+    pub include_accounts: Vec<String>,
+    /// Skip transactions that reference any of these accounts (applied after the allowlist)
+    pub exclude_accounts: Vec<String>,
+}
+
+/// Scale firehose threads between chunks of the slot range (see `autoscale`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoscaleConfig {
+    pub enabled: bool,
+    pub min_threads: usize,
+    /// Defaults to the number of CPUs
+    pub max_threads: usize,
+    /// Slots per firehose call; the thread count is re-evaluated after each
+    pub chunk_slots: u64,
+}
+
+impl Default for AutoscaleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_threads: 1,
+            max_threads: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4),
+            chunk_slots: 10_000,
+        }
+    }
+}
+
+/// Old Faithful archive source (passed to jetstreamer through its environment variables)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FirehoseConfig {
+    pub network: String,
+    /// Where compact indexes and CAR files are read from: the public archive, a
+    /// self-hosted mirror (http/https), a `file://` URL or a local directory path
+    pub compact_index_base_url: String,
+    /// Network read budget for the firehose, in MB
+    pub network_capacity_mb: u64,
+    /// Directory for jetstreamer's on-disk cache (unset keeps its default)
+    pub cache_dir: Option<String>,
+    /// Cap on the download rate, in MB/s (0 = unlimited; see `bandwidth`)
+    pub max_mb_per_sec: u64,
+    /// Stop the run once this many MB were downloaded (0 = unlimited)
+    pub max_total_mb: u64,
+    /// Archive mirrors probed at startup; the reachable one with the lowest latency
+    /// replaces `compact_index_base_url` (see `mirrors`)
+    pub mirrors: Vec<String>,
+    /// Stop the run and resume it on another mirror when the download rate stays
+    /// under this many MB/s for `stall_secs` (0 = never; needs `[checkpoint] enabled`)
+    pub min_mb_per_sec: u64,
+    pub stall_secs: u64,
+}
+
+impl Default for FirehoseConfig {
+    fn default() -> Self {
+        Self {
+            network: "mainnet".to_string(),
+            compact_index_base_url: "https://files.old-faithful.net".to_string(),
+            network_capacity_mb: 100_000,
+            cache_dir: None,
+            max_mb_per_sec: 0,
+            max_total_mb: 0,
+            mirrors: Vec::new(),
+            min_mb_per_sec: 0,
+            stall_secs: 120,
+        }
+    }
+}
+
+impl FirehoseConfig {
+    /// Environment variables jetstreamer reads its configuration from
+    pub fn jetstreamer_env(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            ("JETSTREAMER_NETWORK", self.network.clone()),
+            ("JETSTREAMER_COMPACT_INDEX_BASE_URL", self.compact_index_base_url.clone()),
+            ("JETSTREAMER_NETWORK_CAPACITY_MB", self.network_capacity_mb.to_string()),
+        ];
+        if let Some(dir) = &self.cache_dir {
+            vars.push(("JETSTREAMER_CACHE_DIR", dir.clone()));
+        }
+        vars
+    }
+}
+
+/// Chain being indexed (see `network`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// `solana`, or the name of another SVM chain (e.g. `eclipse`, `soon`)
+    pub name: String,
+    /// Unix time of slot 0; required for chains other than Solana
+    pub genesis_timestamp: Option<u64>,
+    /// Nominal slot duration, for estimating block times
+    pub slot_duration_ms: u64,
+    /// Fixed epoch length; devnet and testnet clusters may differ from mainnet's 432,000
+    pub slots_per_epoch: u64,
+    /// Parser name -> program id on this chain (`""` drops the parser)
+    pub program_ids: HashMap<String, String>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            name: "solana".to_string(),
+            genesis_timestamp: None,
+            slot_duration_ms: 400,
+            slots_per_epoch: SLOTS_PER_EPOCH,
+            program_ids: HashMap::new(),
+        }
+    }
+}
+
+/// Turn a local directory path into a `file://` URL (URLs are returned as-is)
+fn resolve_archive_url(value: &str) -> Result<String, String> {
+    let value = value.trim().trim_end_matches('/');
+    if value.starts_with("http://") || value.starts_with("https://") || value.starts_with("file://") {
+        return Ok(value.to_string());
+    }
+    let path = Path::new(value)
+        .canonicalize()
+        .map_err(|e| format!("Archive path '{}' is not readable: {}", value, e))?;
+    if !path.is_dir() {
+        return Err(format!("Archive path '{}' is not a directory", path.display()));
+    }
+    Ok(format!("file://{}", path.display()))
+}
+
+/// Solana JSON-RPC endpoint used for optional enrichment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcConfig {
+    pub url: Option<String>,
+}
+
+/// Optional row enrichment (epoch is always derived from slot)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnrichmentConfig {
+    /// Fetch the leader schedule via RPC and fill the `leader` column
+    pub leader_schedule: bool,
+    /// Fetch lookup tables via RPC when a V0 transaction has no loaded
+    /// addresses and the tables aren't in the indexed ALT state
+    pub lookup_tables: bool,
+}
+
+/// Periodic pool TVL snapshots (reads vault balances via RPC)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TvlConfig {
+    pub enabled: bool,
+    /// Snapshot every N slots of chain progress (9000 slots is about an hour)
+    pub interval_slots: u64,
+}
+
+impl Default for TvlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_slots: 9000,
+        }
+    }
+}
+
+/// Periodic account state snapshots of tracked programs (reads accounts via RPC getProgramAccounts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountSnapshotsConfig {
+    pub enabled: bool,
+    /// Snapshot every N slots of chain progress (9000 slots is about an hour)
+    pub interval_slots: u64,
+    pub programs: Vec<AccountSnapshotProgram>,
+}
+
+impl Default for AccountSnapshotsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_slots: 9000,
+            programs: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountSnapshotProgram {
+    pub program_id: String,
+    /// Only accounts of exactly this many bytes (getProgramAccounts dataSize filter)
+    #[serde(default)]
+    pub data_size: Option<u64>,
+}
+
+/// Metrics export
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Periodically write Prometheus text metrics to this file (node_exporter textfile collector)
+    pub textfile: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,11 +617,182 @@ pub struct SlotConfig {
 pub struct ClickHouseConfig {
     pub url: String,
     pub clear_on_start: bool,
+    /// Buffer each transaction's rows together and flush them in one batch set
+    #[serde(default)]
+    pub write_groups: bool,
+    /// Partitioning for newly created tables: month, day, epoch or slot_bucket
+    #[serde(default = "default_partition_by")]
+    pub partition_by: PartitionStrategy,
+    /// Slots per partition when partition_by = "slot_bucket"
+    #[serde(default = "default_partition_slot_bucket")]
+    pub partition_slot_bucket: u64,
+    /// Also write swaps, trades and liquidity events to the combined protocol_events table
+    #[serde(default)]
+    pub legacy_protocol_events: bool,
+    /// ClickHouse Cloud: wait for idle-suspended services to wake and retry inserts longer
+    #[serde(default)]
+    pub cloud: bool,
+    /// Prepended to every table name (e.g. "exp1_") to keep experimental runs apart
+    #[serde(default)]
+    pub table_prefix: String,
+    /// Fail on startup if existing tables' columns differ from what this binary creates
+    /// (checked unless clear_on_start)
+    #[serde(default = "default_strict_schema")]
+    pub strict_schema: bool,
+    /// ClickHouse URLs to fail over to, in order, when `url` is unreachable
+    #[serde(default)]
+    pub failover_urls: Vec<String>,
+    /// While failed over, how often to check whether `url` is back
+    #[serde(default = "default_primary_check_secs")]
+    pub primary_check_secs: u64,
+    /// clear_on_start renames existing tables to `<table>_backup_<unix time>` instead of dropping them
+    #[serde(default)]
+    pub clear_backup: bool,
+    /// clear_on_start refuses to drop tables holding more rows than this in total (0 = no limit);
+    /// doesn't apply with clear_backup
+    #[serde(default = "default_clear_max_rows")]
+    pub clear_max_rows: u64,
+    /// Set from CLEAR_DB_CONFIRM=yes-i-mean-it; clear_on_start also accepts --yes-i-mean-it
+    #[serde(skip)]
+    pub clear_confirmed: bool,
+    /// Record each table's flushed watermark in the commit_watermark table
+    #[serde(default)]
+    pub commit_watermarks: bool,
+    /// Split each flushed batch into this many slot ranges, inserted concurrently
+    #[serde(default = "default_flush_buckets")]
+    pub flush_buckets: usize,
+    /// Flush buffers holding a row older than this many seconds, and warn at
+    /// twice it (0 = off)
+    #[serde(default = "default_buffer_age_alert_secs")]
+    pub buffer_age_alert_secs: u64,
+}
+
+fn default_strict_schema() -> bool {
+    true
+}
+
+fn default_primary_check_secs() -> u64 {
+    30
+}
+
+fn default_clear_max_rows() -> u64 {
+    10_000_000
+}
+
+fn default_flush_buckets() -> usize {
+    1
+}
+
+fn default_buffer_age_alert_secs() -> u64 {
+    60
+}
+
+fn default_partition_by() -> PartitionStrategy {
+    PartitionStrategy::Month
+}
+
+fn default_partition_slot_bucket() -> u64 {
+    1_000_000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessingConfig {
     pub threads: usize,
+    /// IANA timezone for the materialized date/hour columns (block_time stays UTC)
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Store each instruction's accounts by IDL name in transactions.named_accounts
+    #[serde(default)]
+    pub named_accounts: bool,
+    /// Protocols processed first within a transaction, highest priority first
+    #[serde(default)]
+    pub protocol_priority: Vec<String>,
+    /// Skip lower-priority listed protocols once a higher one stored a row for the transaction
+    #[serde(default)]
+    pub count_once_per_tx: bool,
+    /// Encoding of failed_transactions.raw_data: hex, base64 or zstd
+    #[serde(default)]
+    pub raw_data_encoding: RawDataEncoding,
+    /// What to do when the firehose reports an error: skip, retry or abort
+    #[serde(default)]
+    pub on_firehose_error: FirehoseErrorPolicy,
+    /// Errors tolerated at one slot with `on_firehose_error = "retry"`
+    #[serde(default = "default_firehose_error_retries")]
+    pub firehose_error_retries: u32,
+    /// Threads decoding instructions off the async runtime (0 = one per core; see `parse_pool`)
+    #[serde(default)]
+    pub parse_threads: usize,
+    /// Instructions queued or decoding at once before transaction tasks wait
+    #[serde(default = "default_parse_queue")]
+    pub parse_queue: usize,
+}
+
+fn default_parse_queue() -> usize {
+    1024
+}
+
+fn default_firehose_error_retries() -> u32 {
+    3
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+/// Per-protocol instruction filtering
+///
+/// Instruction types are matched in snake_case (`shared_accounts_route`),
+/// so both `SharedAccountsRoute` and `shared_accounts_route` work in config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProtocolConfig {
+    /// Allowlist of instruction types to store. Empty means all types are stored.
+    pub instructions: Vec<String>,
+    /// Denylist of instruction types to skip (applied after the allowlist)
+    pub exclude_instructions: Vec<String>,
+    /// Fraction of transactions whose instructions are processed, chosen by
+    /// signature so the same transactions are kept on every run
+    pub sample_rate: f64,
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        Self {
+            instructions: Vec::new(),
+            exclude_instructions: Vec::new(),
+            sample_rate: 1.0,
+        }
+    }
+}
+
+impl ProtocolConfig {
+    /// Returns true if the transaction with this signature is in the protocol's sample
+    pub fn samples(&self, signature: &[u8]) -> bool {
+        sample_by_signature(signature, SampleWindow::Protocol, self.sample_rate)
+    }
+
+    /// Returns true if rows for this instruction type should be stored
+    pub fn allows(&self, instruction_type: &str) -> bool {
+        let name = to_snake_case(instruction_type);
+        if !self.instructions.is_empty()
+            && !self.instructions.iter().any(|i| to_snake_case(i) == name)
+        {
+            return false;
+        }
+        !self.exclude_instructions.iter().any(|i| to_snake_case(i) == name)
+    }
+}
+
+/// Convert `SharedAccountsRoute` to `shared_accounts_route` (snake_case input is returned as-is)
+fn to_snake_case(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 4);
+    for (i, ch) in s.trim().chars().enumerate() {
+        if ch.is_uppercase() && i != 0 {
+            out.push('_');
+        }
+        out.push(ch.to_ascii_lowercase());
+    }
+    out
 }
 
 impl Config {
@@ -62,12 +832,409 @@ impl Config {
             config.clickhouse.clear_on_start = val == "true";
         }
 
-        if let Ok(val) = std::env::var("THREADS") {
+        if let Ok(val) = std::env::var("CLEAR_DB_CONFIRM") {
+            config.clickhouse.clear_confirmed = val == "yes-i-mean-it";
+        }
+
+        if let Ok(val) = std::env::var("CLEAR_DB_BACKUP") {
+            config.clickhouse.clear_backup = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("CLEAR_DB_MAX_ROWS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.clickhouse.clear_max_rows = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("WRITE_GROUPS") {
+            config.clickhouse.write_groups = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("COMMIT_WATERMARKS") {
+            config.clickhouse.commit_watermarks = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("FLUSH_BUCKETS") {
             if let Ok(parsed) = val.parse::<usize>() {
+                config.clickhouse.flush_buckets = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("BUFFER_AGE_ALERT_SECS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.clickhouse.buffer_age_alert_secs = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("LEGACY_PROTOCOL_EVENTS") {
+            config.clickhouse.legacy_protocol_events = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("CLICKHOUSE_CLOUD") {
+            config.clickhouse.cloud = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("TABLE_PREFIX") {
+            config.clickhouse.table_prefix = val;
+        }
+
+        if let Ok(val) = std::env::var("STRICT_SCHEMA") {
+            config.clickhouse.strict_schema = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("CLICKHOUSE_FAILOVER_URLS") {
+            config.clickhouse.failover_urls =
+                val.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect();
+        }
+
+        if let Ok(val) = std::env::var("PRIMARY_CHECK_SECS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.clickhouse.primary_check_secs = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("PARTITION_BY") {
+            config.clickhouse.partition_by = match val.as_str() {
+                "month" => PartitionStrategy::Month,
+                "day" => PartitionStrategy::Day,
+                "epoch" => PartitionStrategy::Epoch,
+                "slot_bucket" => PartitionStrategy::SlotBucket,
+                other => {
+                    return Err(format!(
+                        "Invalid PARTITION_BY: '{}' (expected month, day, epoch or slot_bucket)",
+                        other
+                    )
+                    .into())
+                }
+            };
+        }
+
+        if let Ok(val) = std::env::var("THREADS") {
+            if val == "auto" {
+                config.autoscale.enabled = true;
+            } else if let Ok(parsed) = val.parse::<usize>() {
                 config.processing.threads = parsed;
             }
         }
 
+        if let Ok(val) = std::env::var("TIMEZONE") {
+            config.processing.timezone = val;
+        }
+
+        if let Ok(val) = std::env::var("PARSE_THREADS") {
+            if let Ok(parsed) = val.parse::<usize>() {
+                config.processing.parse_threads = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("PARSE_QUEUE") {
+            if let Ok(parsed) = val.parse::<usize>() {
+                config.processing.parse_queue = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("NAMED_ACCOUNTS") {
+            config.processing.named_accounts = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("PROTOCOL_PRIORITY") {
+            config.processing.protocol_priority = val
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+
+        if let Ok(val) = std::env::var("COUNT_ONCE_PER_TX") {
+            config.processing.count_once_per_tx = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("RAW_DATA_ENCODING") {
+            config.processing.raw_data_encoding = RawDataEncoding::parse(&val)
+                .ok_or_else(|| format!("Invalid RAW_DATA_ENCODING: '{}' (expected hex, base64 or zstd)", val))?;
+        }
+
+        if let Ok(val) = std::env::var("ON_FIREHOSE_ERROR") {
+            config.processing.on_firehose_error = FirehoseErrorPolicy::parse(&val)
+                .ok_or_else(|| format!("Invalid ON_FIREHOSE_ERROR: '{}' (expected skip, retry or abort)", val))?;
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_ERROR_RETRIES") {
+            if let Ok(parsed) = val.parse::<u32>() {
+                config.processing.firehose_error_retries = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("RPC_URL") {
+            config.rpc.url = Some(val);
+        }
+
+        if let Ok(val) = std::env::var("LEADER_SCHEDULE") {
+            config.enrichment.leader_schedule = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("LOOKUP_TABLE_RPC") {
+            config.enrichment.lookup_tables = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("OLD_FAITHFUL_URL") {
+            config.firehose.compact_index_base_url = val;
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_NETWORK_CAPACITY_MB") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.firehose.network_capacity_mb = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_CACHE_DIR") {
+            config.firehose.cache_dir = Some(val);
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_MAX_MB_PER_SEC") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.firehose.max_mb_per_sec = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_MAX_TOTAL_MB") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.firehose.max_total_mb = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_MIRRORS") {
+            config.firehose.mirrors =
+                val.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect();
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_MIN_MB_PER_SEC") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.firehose.min_mb_per_sec = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("FIREHOSE_STALL_SECS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.firehose.stall_secs = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("NETWORK") {
+            config.network.name = val;
+        }
+
+        if let Ok(val) = std::env::var("NETWORK_GENESIS_TIMESTAMP") {
+            config.network.genesis_timestamp = Some(
+                val.parse()
+                    .map_err(|_| format!("Invalid NETWORK_GENESIS_TIMESTAMP: '{}'", val))?,
+            );
+        }
+
+        if let Ok(val) = std::env::var("NETWORK_SLOTS_PER_EPOCH") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.network.slots_per_epoch = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("TVL_SNAPSHOTS") {
+            config.tvl.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("TVL_INTERVAL_SLOTS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.tvl.interval_slots = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("ACCOUNT_SNAPSHOTS") {
+            config.account_snapshots.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("ACCOUNT_SNAPSHOTS_INTERVAL_SLOTS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.account_snapshots.interval_slots = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("METRICS_TEXTFILE") {
+            config.metrics.textfile = Some(val);
+        }
+
+        if let Ok(val) = std::env::var("WORK_STEALING") {
+            config.scheduler.work_stealing = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("WORK_STEALING_CHUNK_SLOTS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.scheduler.chunk_slots = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("WALLET_SESSION_GAP_SECS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.wallet_sessions.gap_secs = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("EXPORT_DESTINATION") {
+            config.export.destination = val;
+        }
+
+        if let Ok(val) = std::env::var("EXPORT_ACCESS_KEY_ID") {
+            config.export.access_key_id = val;
+        }
+
+        if let Ok(val) = std::env::var("EXPORT_SECRET_ACCESS_KEY") {
+            config.export.secret_access_key = val;
+        }
+
+        if let Ok(val) = std::env::var("HEARTBEAT_INTERVAL_SECS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.heartbeat.interval_secs = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("INSTANCE_ID") {
+            config.heartbeat.instance_id = Some(val);
+        }
+
+        let split_list = |val: String| -> Vec<String> {
+            val.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect()
+        };
+
+        if let Ok(val) = std::env::var("INCLUDE_ACCOUNTS") {
+            config.filters.include_accounts = split_list(val);
+        }
+
+        if let Ok(val) = std::env::var("EXCLUDE_ACCOUNTS") {
+            config.filters.exclude_accounts = split_list(val);
+        }
+
+        if let Ok(val) = std::env::var("TRANSFER_EDGES") {
+            config.transfer_edges.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("SLOT_STATUS") {
+            config.slot_status.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("SLOT_TIME_ORACLE") {
+            config.slot_time_oracle.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("HOURLY_SUMMARY") {
+            config.hourly_summary.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("DAILY_USERS") {
+            config.daily_users.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("PROTOCOL_COOCCURRENCE") {
+            config.protocol_cooccurrence.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("LOG_INVOCATIONS") {
+            config.log_invocations.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("WALLET_FIRST_SEEN") {
+            config.wallet_first_seen.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("LOG_FORMAT") {
+            config.logging.format = LogFormat::parse(&val)
+                .ok_or_else(|| format!("Invalid LOG_FORMAT: '{}' (expected pretty or json)", val))?;
+        }
+
+        if let Ok(val) = std::env::var("LOG_LEVEL") {
+            config.logging.level = val;
+        }
+
+        if let Ok(val) = std::env::var("LOG_DIR") {
+            config.logging.dir = Some(val).filter(|dir| !dir.is_empty());
+        }
+
+        if let Ok(val) = std::env::var("LOG_ROTATION") {
+            config.logging.rotation = LogRotation::parse(&val)
+                .ok_or_else(|| format!("Invalid LOG_ROTATION: '{}' (expected daily, hourly or never)", val))?;
+        }
+
+        if let Ok(val) = std::env::var("POOLS") {
+            config.pools.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("POOLS_RESOLVE_VIA_RPC") {
+            config.pools.resolve_via_rpc = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("BACKFILL") {
+            config.backfill.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("BACKFILL_STOP_MERGES") {
+            config.backfill.stop_merges = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("CHECKPOINT") {
+            config.checkpoint.enabled = val == "true";
+        }
+
+        if let Ok(val) = std::env::var("CHECKPOINT_CHUNK_SLOTS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.checkpoint.chunk_slots = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("MAINTENANCE_OPTIMIZE_INTERVAL_SECS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.maintenance.optimize_partitions.interval_secs = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("MAINTENANCE_VACUUM_CHECKPOINTS_INTERVAL_SECS") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.maintenance.vacuum_checkpoints.interval_secs = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("STALE_IDL_MIN_FAILURES") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.stale_idl.min_failures = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("FAILED_ROWS_MAX") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.failed_rows.max_rows = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("FAILED_ROWS_MAX_PER_PROTOCOL") {
+            if let Ok(parsed) = val.parse::<u64>() {
+                config.failed_rows.max_rows_per_protocol = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("FAILED_ROWS_SAMPLE_RATE") {
+            if let Ok(parsed) = val.parse::<f64>() {
+                config.failed_rows.sample_rate = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("PAYLOAD_MAX_TEXT_BYTES") {
+            if let Ok(parsed) = val.parse::<usize>() {
+                config.payload_limits.max_text_bytes = parsed;
+            }
+        }
+
+        if let Ok(val) = std::env::var("PAYLOAD_MAX_DATA_BYTES") {
+            if let Ok(parsed) = val.parse::<usize>() {
+                config.payload_limits.max_data_bytes = parsed;
+            }
+        }
+
         // Validate
         if config.slots.start >= config.slots.end {
             return Err(format!(
@@ -76,10 +1243,141 @@ impl Config {
             ).into());
         }
 
+        if config.clickhouse.partition_slot_bucket == 0 {
+            return Err("clickhouse.partition_slot_bucket must be greater than 0".into());
+        }
+
+        if config.clickhouse.flush_buckets == 0 {
+            return Err("clickhouse.flush_buckets must be greater than 0".into());
+        }
+
+        if !config.clickhouse.table_prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!(
+                "clickhouse.table_prefix may only contain letters, digits and '_', got '{}'",
+                config.clickhouse.table_prefix
+            ).into());
+        }
+
         if config.processing.threads == 0 {
             return Err("THREADS must be greater than 0".into());
         }
 
+        if !(0.0..=1.0).contains(&config.failed_rows.sample_rate) {
+            return Err(format!(
+                "failed_rows.sample_rate must be between 0 and 1, got {}",
+                config.failed_rows.sample_rate
+            )
+            .into());
+        }
+
+        let mut protocol_names: Vec<&String> = config.protocols.keys().collect();
+        protocol_names.sort();
+        for name in protocol_names {
+            let sample_rate = config.protocols[name].sample_rate;
+            if !(0.0..=1.0).contains(&sample_rate) {
+                return Err(format!(
+                    "protocols.{}.sample_rate must be between 0 and 1, got {}",
+                    name, sample_rate
+                )
+                .into());
+            }
+        }
+
+        if (1..TRUNCATION_MARKER.len()).contains(&config.payload_limits.max_text_bytes) {
+            return Err(format!(
+                "payload_limits.max_text_bytes must be 0 (unlimited) or at least {}, got {}",
+                TRUNCATION_MARKER.len(),
+                config.payload_limits.max_text_bytes
+            )
+            .into());
+        }
+
+        if config.autoscale.enabled {
+            let a = &config.autoscale;
+            if a.min_threads == 0 || a.min_threads > a.max_threads {
+                return Err(format!(
+                    "autoscale needs 0 < min_threads <= max_threads (got {} and {})",
+                    a.min_threads, a.max_threads
+                )
+                .into());
+            }
+            if a.chunk_slots == 0 {
+                return Err("autoscale.chunk_slots must be greater than 0".into());
+            }
+        }
+
+        if config.scheduler.work_stealing && config.scheduler.chunk_slots == 0 {
+            return Err("scheduler.chunk_slots must be greater than 0".into());
+        }
+
+        if config.checkpoint.enabled && config.checkpoint.chunk_slots == 0 {
+            return Err("checkpoint.chunk_slots must be greater than 0".into());
+        }
+
+        // Timezone is spliced into DDL, so only allow IANA name characters
+        let tz = &config.processing.timezone;
+        if tz.is_empty() || !tz.chars().all(|c| c.is_ascii_alphanumeric() || "/_-+".contains(c)) {
+            return Err(format!("Invalid timezone: '{}' (expected an IANA name like America/New_York)", tz).into());
+        }
+
+        if config.enrichment.leader_schedule && config.rpc.url.is_none() {
+            return Err("enrichment.leader_schedule requires [rpc] url (or RPC_URL)".into());
+        }
+        if config.enrichment.lookup_tables && config.rpc.url.is_none() {
+            return Err("enrichment.lookup_tables requires [rpc] url (or RPC_URL)".into());
+        }
+
+        config.firehose.compact_index_base_url =
+            resolve_archive_url(&config.firehose.compact_index_base_url)?;
+        config.firehose.mirrors = config
+            .firehose
+            .mirrors
+            .iter()
+            .map(|mirror| resolve_archive_url(mirror))
+            .collect::<Result<_, _>>()?;
+        if config.firehose.min_mb_per_sec > 0 && config.firehose.stall_secs == 0 {
+            return Err("firehose.stall_secs must be greater than 0".into());
+        }
+        // A stalled run continues with --resume, which needs checkpoints
+        if config.firehose.min_mb_per_sec > 0 && !config.checkpoint.enabled {
+            return Err("firehose.min_mb_per_sec requires [checkpoint] enabled (or CHECKPOINT=true)".into());
+        }
+
+        if config.firehose.network.is_empty() {
+            return Err("firehose.network must not be empty".into());
+        }
+
+        if config.firehose.network_capacity_mb == 0 {
+            return Err("firehose.network_capacity_mb must be greater than 0".into());
+        }
+
+        Network::from_config(&config.network)?;
+
+        if config.pools.resolve_via_rpc && config.rpc.url.is_none() {
+            return Err("pools.resolve_via_rpc requires [rpc] url (or RPC_URL)".into());
+        }
+
+        if config.tvl.enabled {
+            if config.rpc.url.is_none() {
+                return Err("tvl.enabled requires [rpc] url (or RPC_URL)".into());
+            }
+            if config.tvl.interval_slots == 0 {
+                return Err("tvl.interval_slots must be greater than 0".into());
+            }
+        }
+
+        if config.account_snapshots.enabled {
+            if config.rpc.url.is_none() {
+                return Err("account_snapshots.enabled requires [rpc] url (or RPC_URL)".into());
+            }
+            if config.account_snapshots.interval_slots == 0 {
+                return Err("account_snapshots.interval_slots must be greater than 0".into());
+            }
+            if config.account_snapshots.programs.is_empty() {
+                return Err("account_snapshots.enabled requires at least one [[account_snapshots.programs]] entry".into());
+            }
+        }
+
         Ok(config)
     }
 }
@@ -94,10 +1392,64 @@ impl Default for Config {
             clickhouse: ClickHouseConfig {
                 url: "http://localhost:8123".to_string(),
                 clear_on_start: false,
+                write_groups: false,
+                partition_by: default_partition_by(),
+                partition_slot_bucket: default_partition_slot_bucket(),
+                legacy_protocol_events: false,
+                cloud: false,
+                table_prefix: String::new(),
+                strict_schema: default_strict_schema(),
+                failover_urls: Vec::new(),
+                primary_check_secs: default_primary_check_secs(),
+                clear_backup: false,
+                clear_max_rows: default_clear_max_rows(),
+                clear_confirmed: false,
+                commit_watermarks: false,
+                flush_buckets: default_flush_buckets(),
+                buffer_age_alert_secs: default_buffer_age_alert_secs(),
             },
             processing: ProcessingConfig {
                 threads: 1,
+                timezone: default_timezone(),
+                named_accounts: false,
+                protocol_priority: Vec::new(),
+                count_once_per_tx: false,
+                raw_data_encoding: RawDataEncoding::Hex,
+                on_firehose_error: FirehoseErrorPolicy::Skip,
+                firehose_error_retries: default_firehose_error_retries(),
+                parse_threads: 0,
+                parse_queue: default_parse_queue(),
             },
+            protocols: HashMap::new(),
+            rpc: RpcConfig::default(),
+            enrichment: EnrichmentConfig::default(),
+            metrics: MetricsConfig::default(),
+            tvl: TvlConfig::default(),
+            account_snapshots: AccountSnapshotsConfig::default(),
+            firehose: FirehoseConfig::default(),
+            network: NetworkConfig::default(),
+            autoscale: AutoscaleConfig::default(),
+            filters: FiltersConfig::default(),
+            transfer_edges: TransferEdgesConfig::default(),
+            slot_status: SlotStatusConfig::default(),
+            slot_time_oracle: SlotTimeOracleConfig::default(),
+            hourly_summary: HourlySummaryConfig::default(),
+            daily_users: DailyUsersConfig::default(),
+            protocol_cooccurrence: ProtocolCooccurrenceConfig::default(),
+            log_invocations: LogInvocationsConfig::default(),
+            wallet_first_seen: WalletFirstSeenConfig::default(),
+            pools: PoolsConfig::default(),
+            backfill: BackfillConfig::default(),
+            checkpoint: CheckpointConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            stale_idl: StaleIdlConfig::default(),
+            failed_rows: FailedRowsConfig::default(),
+            payload_limits: PayloadLimitsConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            wallet_sessions: WalletSessionsConfig::default(),
+            export: ExportConfig::default(),
+            logging: LoggingConfig::default(),
         }
     }
 }
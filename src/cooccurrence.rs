@@ -0,0 +1,94 @@
+//! Daily protocol co-occurrence counts (protocol_cooccurrence)
+//!
+//! Counts, per UTC day of block time and pair of protocols, the transactions
+//! that stored rows for both (e.g. `jupiter_v6` routing through `whirlpool`),
+//! so aggregator routing can be read from a few rows per day instead of a
+//! self-join of transactions on signature. Pairs are unordered and stored with
+//! `protocol_a < protocol_b`; a transaction touching three protocols counts
+//! once for each of its three pairs.
+//!
+//! Like protocol_daily_users, each flush rewrites the running row of every
+//! pair and day touched since the last flush, keyed by the run's
+//! `slot_start`, and ReplacingMergeTree keeps the latest. Counts of runs that
+//! share a day add up.
+
+use crate::storage::{ProtocolCooccurrence, Storage};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Mutex;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+type Key = (u64, &'static str, &'static str);
+
+#[derive(Debug, Default)]
+struct State {
+    pairs: HashMap<Key, u64>,
+    /// Pairs changed since the last flush
+    dirty: HashSet<Key>,
+}
+
+#[derive(Debug)]
+pub struct Cooccurrence {
+    slot_start: u64,
+    state: Mutex<State>,
+}
+
+impl Cooccurrence {
+    pub fn new(slot_start: u64) -> Self {
+        Self {
+            slot_start,
+            state: Mutex::default(),
+        }
+    }
+
+    /// A transaction at `block_time` stored rows for each of `protocols`
+    pub fn record(&self, block_time: u64, protocols: &BTreeSet<&'static str>) {
+        if protocols.len() < 2 {
+            return;
+        }
+        let day = block_time - block_time % SECONDS_PER_DAY;
+        let mut state = self.state.lock().unwrap();
+        // The set is ordered, so each pair comes out as (a, b) with a < b
+        for (i, &a) in protocols.iter().enumerate() {
+            for &b in protocols.iter().skip(i + 1) {
+                let key = (day, a, b);
+                *state.pairs.entry(key).or_default() += 1;
+                state.dirty.insert(key);
+            }
+        }
+    }
+
+    /// Keys and current rows of the pairs changed since the last call
+    fn take_dirty(&self) -> (Vec<Key>, Vec<ProtocolCooccurrence>) {
+        let mut state = self.state.lock().unwrap();
+        let dirty: Vec<_> = state.dirty.drain().collect();
+        let rows = dirty
+            .iter()
+            .filter_map(|key| {
+                let tx_count = *state.pairs.get(key)?;
+                Some(ProtocolCooccurrence {
+                    day: key.0,
+                    protocol_a: key.1.to_string(),
+                    protocol_b: key.2.to_string(),
+                    slot_start: self.slot_start,
+                    tx_count,
+                    updated_at: 0,
+                })
+            })
+            .collect();
+        (dirty, rows)
+    }
+
+    /// Write the changed pairs to protocol_cooccurrence, retrying them on the
+    /// next flush if the insert fails
+    pub async fn flush(&self, storage: &dyn Storage) {
+        let (keys, rows) = self.take_dirty();
+        if rows.is_empty() {
+            return;
+        }
+        if let Err(e) = storage.insert_cooccurrence(&rows).await {
+            tracing::warn!("Failed to write protocol_cooccurrence: {:?}", e);
+            self.state.lock().unwrap().dirty.extend(keys);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! First-buyer tracking for token_creators
+//!
+//! Remembers each mint created during this run for [`FIRST_BUYER_SLOTS`]
+//! slots, along with the earliest buys seen for it by (slot, transaction
+//! index). A buy is recorded as a first buyer when fewer than
+//! [`FIRST_BUYERS`] other wallets are known to have bought earlier. Firehose
+//! threads work on separate slot ranges and don't reach a mint's buys in
+//! order, so a mint can get a few more rows than [`FIRST_BUYERS`]; the rank is
+//! left to queries over (slot, tx_index) rather than taken from processing
+//! order. Mints created before the run started are never tracked, so their
+//! buyers aren't misreported as first buyers, and a buy processed by a later
+//! range's thread before the create is seen is missed.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Distinct buyers recorded per mint
+pub const FIRST_BUYERS: usize = 10;
+
+/// Slots after its create during which a mint's buys are considered
+pub const FIRST_BUYER_SLOTS: u64 = 1500;
+
+/// A mint collecting buyers
+struct Mint {
+    created_slot: u64,
+    /// Earliest buy of each of the earliest buyers, by (slot, tx index)
+    buyers: Vec<((u64, u32), String)>,
+}
+
+#[derive(Default)]
+pub struct FirstBuyers {
+    /// Mints still within [`FIRST_BUYER_SLOTS`] of their create
+    mints: Mutex<HashMap<String, Mint>>,
+}
+
+impl FirstBuyers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking a mint created in `slot`
+    pub fn track(&self, mint: &str, slot: u64) {
+        let mut mints = self.mints.lock().unwrap_or_else(|e| e.into_inner());
+        mints.entry(mint.to_string()).or_insert_with(|| Mint {
+            created_slot: slot,
+            buyers: Vec::new(),
+        });
+    }
+
+    /// Record a buy in transaction `tx_index` of `slot`, returning whether it is
+    /// among the first [`FIRST_BUYERS`] distinct buyers seen so far
+    pub fn record(&self, mint: &str, buyer: &str, slot: u64, tx_index: u32) -> bool {
+        let mut mints = self.mints.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entry) = mints.get_mut(mint) else { return false };
+        if slot > entry.created_slot + FIRST_BUYER_SLOTS {
+            return false;
+        }
+        let key = (slot, tx_index);
+        if let Some(position) = entry.buyers.iter().position(|(_, wallet)| wallet == buyer) {
+            if entry.buyers[position].0 <= key {
+                return false;
+            }
+            entry.buyers.remove(position);
+        }
+        let rank = entry.buyers.partition_point(|(earlier, _)| *earlier <= key);
+        if rank >= FIRST_BUYERS {
+            return false;
+        }
+        entry.buyers.insert(rank, (key, buyer.to_string()));
+        entry.buyers.truncate(FIRST_BUYERS);
+        true
+    }
+
+    /// Stop tracking mints whose buy window ended before `slot`, once every
+    /// firehose thread has processed the slots below it
+    pub fn expire(&self, slot: u64) {
+        let mut mints = self.mints.lock().unwrap_or_else(|e| e.into_inner());
+        mints.retain(|_, entry| entry.created_slot + FIRST_BUYER_SLOTS >= slot);
+    }
+
+    /// Number of mints still collecting buyers
+    pub fn len(&self) -> usize {
+        self.mints.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_first_buyers_of_tracked_mints() {
+        let first_buyers = FirstBuyers::new();
+        assert!(!first_buyers.record("mint", "wallet0", 100, 0));
+
+        first_buyers.track("mint", 100);
+        for i in 0..FIRST_BUYERS {
+            assert!(first_buyers.record("mint", &format!("wallet{}", i), 101, i as u32));
+        }
+        // Repeat buys and buyers past the first FIRST_BUYERS are skipped
+        assert!(!first_buyers.record("mint", "wallet0", 102, 0));
+        assert!(!first_buyers.record("mint", "late", 102, 0));
+        // An earlier buy processed out of order still counts
+        assert!(first_buyers.record("mint", "early", 100, 5));
+        assert!(first_buyers.record("mint", "wallet5", 100, 2));
+        // Buys after the window don't
+        first_buyers.track("other", 100);
+        assert!(!first_buyers.record("other", "wallet0", 100 + FIRST_BUYER_SLOTS + 1, 0));
+    }
+
+    #[test]
+    fn test_expire_drops_mints_past_their_window() {
+        let first_buyers = FirstBuyers::new();
+        first_buyers.track("old", 100);
+        first_buyers.track("new", 1000);
+        first_buyers.expire(100 + FIRST_BUYER_SLOTS);
+        assert_eq!(first_buyers.len(), 2);
+        first_buyers.expire(101 + FIRST_BUYER_SLOTS);
+        assert_eq!(first_buyers.len(), 1);
+        assert!(!first_buyers.record("old", "wallet", 200, 0));
+        assert!(first_buyers.record("new", "wallet", 1000, 0));
+    }
+}
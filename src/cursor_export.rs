@@ -0,0 +1,211 @@
+//! Cursor-based incremental export (`--export-after TABLE`)
+//!
+//! Streams a table's rows after a `(slot, signature)` cursor to stdout as
+//! JSON lines, in `(slot, signature)` order with the table's sorting key as
+//! the tiebreaker, so consumers can sync the indexed data into their own
+//! systems a page at a time instead of re-dumping whole tables:
+//!
+//! ```text
+//! solixdb-indexer --export-after swaps --cursor-file swaps.cursor >> swaps.jsonl
+//! ```
+//!
+//! A page holds at most `limit` signatures and never splits one, so the next
+//! cursor (the page's last key, logged and written to `--cursor-file`) always
+//! resumes on a signature boundary. Only tables with both a slot column and a
+//! `signature` column can be exported. Like Arrow Flight, this only reads:
+//! no tables are created or cleared.
+//!
+//! Rows ClickHouse receives later for slots behind a consumer's cursor (a range
+//! still being indexed, or re-indexed) are not picked up again; export ranges
+//! once they're complete.
+
+use crate::storage::{slot_column, TABLES};
+use serde::Deserialize;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+/// Position after the last exported signature
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cursor {
+    pub slot: u64,
+    pub signature: String,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.slot, self.signature)
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = String;
+
+    /// `SLOT:SIGNATURE`, or just `SLOT` to start at the beginning of the slot
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (slot, signature) = s.trim().split_once(':').unwrap_or((s.trim(), ""));
+        Ok(Self {
+            slot: slot
+                .parse()
+                .map_err(|_| format!("invalid cursor '{}' (expected SLOT:SIGNATURE)", s.trim()))?,
+            signature: signature.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PageEnd {
+    slot: u64,
+    signature: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TableInfo {
+    columns: u64,
+    has_signature: u64,
+    sorting_key: String,
+}
+
+pub struct CursorExport {
+    clickhouse_url: String,
+    table_prefix: String,
+    http: reqwest::Client,
+}
+
+impl CursorExport {
+    pub fn new(clickhouse_url: &str, table_prefix: &str) -> Self {
+        Self {
+            clickhouse_url: clickhouse_url.to_string(),
+            table_prefix: table_prefix.to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Write the page of `table` after `after` (at most `limit` signatures) to
+    /// `out`, returning the cursor to continue from (None when there are no
+    /// rows past `after`)
+    pub async fn export(
+        &self,
+        table: &str,
+        after: &Cursor,
+        limit: u64,
+        out: &mut impl Write,
+    ) -> Result<Option<Cursor>, Box<dyn std::error::Error + Send + Sync>> {
+        if !TABLES.contains(&table) {
+            return Err(format!("unknown table: {}", table).into());
+        }
+        let slot = slot_column(table).ok_or_else(|| format!("{} has no slot column to page on", table))?;
+        let qualified = format!("{}{}", self.table_prefix, table);
+
+        let info: Option<TableInfo> = self
+            .query_row(
+                "SELECT count() AS columns, countIf(name = 'signature') AS has_signature, \
+                 any(sorting_key) AS sorting_key \
+                 FROM system.columns AS c \
+                 INNER JOIN system.tables AS t ON t.database = c.database AND t.name = c.table \
+                 WHERE c.database = currentDatabase() AND c.table = {table:String}",
+                &[("param_table", qualified.clone())],
+            )
+            .await?;
+        let info = info.unwrap_or_default();
+        if info.columns == 0 {
+            return Err(format!("table {} does not exist", qualified).into());
+        }
+        if info.has_signature == 0 {
+            return Err(format!("{} has no signature column to page on", table).into());
+        }
+
+        let after_params = [
+            ("param_slot", after.slot.to_string()),
+            ("param_signature", after.signature.clone()),
+        ];
+        let past_cursor = format!(
+            "({slot}, signature) > ({{slot:UInt64}}, {{signature:String}})",
+            slot = slot
+        );
+        let end: Option<PageEnd> = self
+            .query_row(
+                &format!(
+                    "SELECT {slot} AS slot, signature FROM \
+                     (SELECT DISTINCT {slot}, signature FROM {table} WHERE {past} \
+                      ORDER BY {slot}, signature LIMIT {limit}) \
+                     ORDER BY {slot} DESC, signature DESC LIMIT 1",
+                    slot = slot,
+                    table = qualified,
+                    past = past_cursor,
+                    limit = limit.max(1)
+                ),
+                &after_params,
+            )
+            .await?;
+        let Some(end) = end else { return Ok(None) };
+
+        let mut order = format!("{}, signature", slot);
+        if !info.sorting_key.is_empty() {
+            order.push_str(", ");
+            order.push_str(&info.sorting_key);
+        }
+        let mut params = after_params.to_vec();
+        params.push(("param_end_slot", end.slot.to_string()));
+        params.push(("param_end_signature", end.signature.clone()));
+        let mut response = self
+            .send(
+                &format!(
+                    "SELECT * FROM {table} WHERE {past} \
+                     AND ({slot}, signature) <= ({{end_slot:UInt64}}, {{end_signature:String}}) \
+                     ORDER BY {order} FORMAT JSONEachRow",
+                    table = qualified,
+                    past = past_cursor,
+                    slot = slot,
+                    order = order
+                ),
+                &params,
+            )
+            .await?;
+        // Copy chunks as they arrive instead of buffering the page
+        while let Some(chunk) = response.chunk().await? {
+            out.write_all(&chunk)?;
+        }
+        out.flush()?;
+
+        Ok(Some(Cursor {
+            slot: end.slot,
+            signature: end.signature,
+        }))
+    }
+
+    async fn send(
+        &self,
+        sql: &str,
+        params: &[(&str, String)],
+    ) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+        let response = self
+            .http
+            .post(&self.clickhouse_url)
+            .query(params)
+            .body(sql.to_string())
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(format!("ClickHouse error: {}", message.trim()).into());
+        }
+        Ok(response)
+    }
+
+    /// First row of a query as JSON, None if it returned no rows
+    async fn query_row<T: for<'de> Deserialize<'de>>(
+        &self,
+        sql: &str,
+        params: &[(&str, String)],
+    ) -> Result<Option<T>, Box<dyn std::error::Error + Send + Sync>> {
+        // Unquoted 64-bit integers, so slots deserialize as numbers
+        let mut params = params.to_vec();
+        params.push(("output_format_json_quote_64bit_integers", "0".to_string()));
+        let text = self.send(&format!("{} FORMAT JSONEachRow", sql), &params).await?.text().await?;
+        match text.lines().next() {
+            Some(line) => Ok(Some(serde_json::from_str(line)?)),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! Daily unique users per protocol (protocol_daily_users)
+//!
+//! Keeps, per UTC day of block time and protocol, a HyperLogLog of the fee
+//! payers of transactions that stored at least one row for the protocol, and
+//! stores the sketch's registers along with its estimate. Registers merge
+//! exactly (register-wise max), so DAU, WAU and MAU over any span of days, and
+//! across runs that share a day, are one `maxForEach` over a few rows per
+//! protocol and day instead of a `uniq` over billions of events.
+//!
+//! Like protocol_hourly_summary, each flush rewrites the running row of every
+//! day touched since the last flush, keyed by the run's `slot_start`, and
+//! ReplacingMergeTree keeps the latest. A run keeps every day it has seen in
+//! memory (about 4 KiB per day and protocol).
+
+use crate::hll::HyperLogLog;
+use crate::storage::{ProtocolDailyUsers, Storage};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, Default)]
+struct State {
+    days: HashMap<(u64, &'static str), HyperLogLog>,
+    /// Days changed since the last flush
+    dirty: HashSet<(u64, &'static str)>,
+}
+
+#[derive(Debug)]
+pub struct DailyUsers {
+    slot_start: u64,
+    state: Mutex<State>,
+}
+
+impl DailyUsers {
+    pub fn new(slot_start: u64) -> Self {
+        Self {
+            slot_start,
+            state: Mutex::default(),
+        }
+    }
+
+    /// `user` sent a transaction at `block_time` that stored rows for `protocol`
+    pub fn record(&self, block_time: u64, protocol: &'static str, user: &[u8]) {
+        let key = (block_time - block_time % SECONDS_PER_DAY, protocol);
+        let mut state = self.state.lock().unwrap();
+        state.days.entry(key).or_default().insert(user);
+        state.dirty.insert(key);
+    }
+
+    /// Keys and current rows of the days changed since the last call
+    fn take_dirty(&self) -> (Vec<(u64, &'static str)>, Vec<ProtocolDailyUsers>) {
+        let mut state = self.state.lock().unwrap();
+        let dirty: Vec<_> = state.dirty.drain().collect();
+        let rows = dirty
+            .iter()
+            .filter_map(|key| {
+                let users = state.days.get(key)?;
+                Some(ProtocolDailyUsers {
+                    day: key.0,
+                    protocol_name: key.1.to_string(),
+                    slot_start: self.slot_start,
+                    unique_users: users.estimate(),
+                    registers: users.registers().to_vec(),
+                    updated_at: 0,
+                })
+            })
+            .collect();
+        (dirty, rows)
+    }
+
+    /// Write the changed days to protocol_daily_users, retrying them on the
+    /// next flush if the insert fails
+    pub async fn flush(&self, storage: &dyn Storage) {
+        let (keys, rows) = self.take_dirty();
+        if rows.is_empty() {
+            return;
+        }
+        if let Err(e) = storage.insert_daily_users(&rows).await {
+            tracing::warn!("Failed to write protocol_daily_users: {:?}", e);
+            self.state.lock().unwrap().dirty.extend(keys);
+        }
+    }
+}
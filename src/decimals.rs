@@ -0,0 +1,71 @@
+//! Token decimals, for the `*_ui` amounts of swaps, trades and liquidity_events
+//!
+//! Instruction amounts are raw integer units. Every transaction's meta lists
+//! the mint and decimals of each token account whose balance it reports, which
+//! covers the mints a swap or trade moves, so decimals are read from there and
+//! remembered for the rest of the run. A row whose mint no transaction has
+//! reported yet (an aggregator route's intermediate mint, say) gets a NULL UI
+//! amount rather than a guess.
+//!
+//! UI amounts are Float64: exact up to 2^53 raw units, and within a part in
+//! 10^15 beyond, which only ever touches digits far below a token's precision
+//! in practice. The raw columns stay authoritative.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Decimals of SOL, for lamport amounts
+pub const SOL_DECIMALS: u8 = 9;
+
+/// `amount` raw units of a token with `decimals` decimals
+pub fn ui_amount(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(decimals as i32)
+}
+
+#[derive(Debug)]
+pub struct TokenDecimals {
+    decimals: RwLock<HashMap<String, u8>>,
+}
+
+impl Default for TokenDecimals {
+    fn default() -> Self {
+        Self {
+            decimals: RwLock::new(HashMap::from([(WSOL_MINT.to_string(), SOL_DECIMALS)])),
+        }
+    }
+}
+
+impl TokenDecimals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember the decimals of each (mint, decimals) reported in a transaction's token balances
+    pub fn observe<'a>(&self, balances: impl IntoIterator<Item = (&'a str, u8)>) {
+        let unseen: Vec<_> = {
+            let known = self.decimals.read().unwrap_or_else(|e| e.into_inner());
+            balances
+                .into_iter()
+                .filter(|(mint, _)| !mint.is_empty() && !known.contains_key(*mint))
+                .collect()
+        };
+        if unseen.is_empty() {
+            return;
+        }
+        let mut known = self.decimals.write().unwrap_or_else(|e| e.into_inner());
+        for (mint, decimals) in unseen {
+            known.insert(mint.to_string(), decimals);
+        }
+    }
+
+    pub fn get(&self, mint: &str) -> Option<u8> {
+        self.decimals.read().unwrap_or_else(|e| e.into_inner()).get(mint).copied()
+    }
+
+    /// `amount` raw units of `mint`, or None while its decimals are unknown
+    pub fn ui_amount(&self, mint: &str, amount: u64) -> Option<f64> {
+        self.get(mint).map(|decimals| ui_amount(amount, decimals))
+    }
+}
@@ -0,0 +1,82 @@
+//! Stale IDL detection (unknown_discriminators)
+//!
+//! Counts discriminator-mismatch parse failures per (program, discriminator).
+//! A discriminator the IDL doesn't know that keeps showing up usually means
+//! the program shipped a new instruction and the IDL needs updating, so once
+//! one reaches `[stale_idl] min_failures` in this run it is reported (logged
+//! and written to unknown_discriminators) exactly once. Counts are per run;
+//! parallel indexers each report their own.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Distinct (program, discriminator) pairs counted before new ones are ignored
+const MAX_TRACKED: usize = 100_000;
+
+/// Leading instruction bytes treated as the discriminator (Anchor sighash length)
+const DISCRIMINATOR_LEN: usize = 8;
+
+struct Seen {
+    failures: u64,
+    first_slot: u64,
+    first_signature: String,
+    reported: bool,
+}
+
+/// A discriminator that just crossed the reporting threshold
+#[derive(Debug, Clone)]
+pub struct Detection {
+    pub program_id: Arc<str>,
+    /// Hex of the leading instruction bytes
+    pub discriminator: String,
+    pub failures: u64,
+    pub first_slot: u64,
+    pub first_signature: String,
+}
+
+pub struct UnknownDiscriminators {
+    min_failures: u64,
+    seen: Mutex<HashMap<(Arc<str>, String), Seen>>,
+}
+
+impl UnknownDiscriminators {
+    /// `min_failures` of 0 disables detection
+    pub fn new(min_failures: u64) -> Self {
+        Self {
+            min_failures,
+            seen: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a discriminator-mismatch failure, returning a detection the first
+    /// time the discriminator reaches the threshold
+    pub fn record(&self, program_id: &Arc<str>, data: &[u8], slot: u64, signature: &str) -> Option<Detection> {
+        if self.min_failures == 0 {
+            return None;
+        }
+        let discriminator = hex::encode(&data[..data.len().min(DISCRIMINATOR_LEN)]);
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (Arc::clone(program_id), discriminator);
+        if !seen.contains_key(&key) && seen.len() >= MAX_TRACKED {
+            return None;
+        }
+        let entry = seen.entry(key.clone()).or_insert_with(|| Seen {
+            failures: 0,
+            first_slot: slot,
+            first_signature: signature.to_string(),
+            reported: false,
+        });
+        entry.failures += 1;
+        if entry.reported || entry.failures < self.min_failures {
+            return None;
+        }
+        entry.reported = true;
+        Some(Detection {
+            program_id: key.0,
+            discriminator: key.1,
+            failures: entry.failures,
+            first_slot: entry.first_slot,
+            first_signature: entry.first_signature.clone(),
+        })
+    }
+}
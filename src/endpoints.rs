@@ -0,0 +1,149 @@
+//! ClickHouse endpoint failover (`[clickhouse] failover_urls`)
+//!
+//! The indexer talks to one endpoint at a time: `url` (the primary) while it
+//! is reachable, otherwise the next of `failover_urls` in order. An insert
+//! attempt that fails moves the active endpoint on before it is retried, so a
+//! replica outage costs one attempt instead of halting the run. While failed
+//! over, the primary is pinged every `primary_check_secs` and the indexer
+//! returns to it as soon as it answers.
+//!
+//! All endpoints must serve the same tables (replicas of a `Replicated`
+//! database, or nodes of a cluster with replicated tables): rows written
+//! through a failover endpoint are expected to reach the primary by
+//! replication.
+
+use clickhouse::Client;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub struct Endpoint {
+    /// URL without credentials, for logs and metrics
+    pub name: String,
+    pub client: Client,
+}
+
+pub struct Endpoints {
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+    failovers: AtomicU64,
+    check_interval: Duration,
+    /// When the primary was last pinged while failed over
+    last_primary_check: Mutex<Option<Instant>>,
+}
+
+impl Endpoints {
+    /// `endpoints` in preference order, the primary first
+    pub fn new(endpoints: Vec<Endpoint>, check_interval: Duration) -> Self {
+        assert!(!endpoints.is_empty(), "at least one ClickHouse endpoint is required");
+        Self {
+            endpoints,
+            active: AtomicUsize::new(0),
+            failovers: AtomicU64::new(0),
+            check_interval,
+            last_primary_check: Mutex::new(None),
+        }
+    }
+
+    /// Index of the endpoint queries currently go to (0 is the primary)
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.endpoints[self.active()].client
+    }
+
+    pub fn primary(&self) -> &Client {
+        &self.endpoints[0].client
+    }
+
+    pub fn name(&self, index: usize) -> &str {
+        &self.endpoints[index].name
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// Times the active endpoint moved off a failing one
+    pub fn failovers(&self) -> u64 {
+        self.failovers.load(Ordering::Relaxed)
+    }
+
+    /// Move off endpoint `from` to the next one; false if there is no other
+    /// endpoint or another caller already moved on
+    pub fn fail_over(&self, from: usize) -> bool {
+        if self.endpoints.len() < 2 {
+            return false;
+        }
+        let next = (from + 1) % self.endpoints.len();
+        if self
+            .active
+            .compare_exchange(from, next, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+        self.failovers.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            "ClickHouse endpoint {} failed, switching to {}",
+            self.name(from),
+            self.name(next)
+        );
+        true
+    }
+
+    /// Whether the primary is due a ping: failed over and not checked within
+    /// the check interval (claims the check, so only one caller pings)
+    pub fn primary_check_due(&self) -> bool {
+        if self.active() == 0 {
+            return false;
+        }
+        let mut last = self.last_primary_check.lock().unwrap();
+        if last.is_some_and(|at| at.elapsed() < self.check_interval) {
+            return false;
+        }
+        *last = Some(Instant::now());
+        true
+    }
+
+    /// The primary answered again
+    pub fn return_to_primary(&self) {
+        let from = self.active.swap(0, Ordering::Relaxed);
+        if from != 0 {
+            tracing::info!("ClickHouse primary {} is back, switching from {}", self.name(0), self.name(from));
+        }
+    }
+
+    /// Render in Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP solixdb_clickhouse_active_endpoint Index of the ClickHouse endpoint in use (0 is the primary)\n");
+        out.push_str("# TYPE solixdb_clickhouse_active_endpoint gauge\n");
+        let _ = writeln!(out, "solixdb_clickhouse_active_endpoint {}", self.active());
+        out.push_str("# HELP solixdb_clickhouse_failovers_total Switches away from a failing ClickHouse endpoint\n");
+        out.push_str("# TYPE solixdb_clickhouse_failovers_total counter\n");
+        let _ = writeln!(out, "solixdb_clickhouse_failovers_total {}", self.failovers());
+        out
+    }
+}
+
+/// `url` without the `user:password@` part
+pub fn endpoint_name(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let authority_end = rest.find('/').unwrap_or(rest.len());
+            match rest[..authority_end].rsplit_once('@') {
+                Some((_, host)) => format!("{}://{}{}", scheme, host, &rest[authority_end..]),
+                None => url.to_string(),
+            }
+        }
+        None => url.to_string(),
+    }
+}
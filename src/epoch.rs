@@ -0,0 +1,82 @@
+//! Epoch and leader schedule enrichment
+
+use crate::rpc::RpcClient;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Epoch length on Solana mainnet-beta, the `[network] slots_per_epoch` default
+pub const SLOTS_PER_EPOCH: u64 = 432_000;
+
+/// Epoch of `slot` on a network with fixed-length epochs (no warmup epochs)
+pub fn epoch_for_slot(slot: u64, slots_per_epoch: u64) -> u64 {
+    slot / slots_per_epoch
+}
+
+/// Leader schedule lookups via `getLeaderSchedule`, cached per epoch
+///
+/// RPC nodes only keep schedules for recent epochs, so historical backfills may
+/// get no schedule; those slots are stored with an empty leader.
+pub struct LeaderSchedule {
+    rpc: Arc<RpcClient>,
+    slots_per_epoch: u64,
+    // epoch -> leader identity per slot index (None if the node had no schedule)
+    epochs: Mutex<HashMap<u64, Option<Arc<Vec<String>>>>>,
+}
+
+impl LeaderSchedule {
+    pub fn new(rpc: Arc<RpcClient>, slots_per_epoch: u64) -> Self {
+        Self {
+            rpc,
+            slots_per_epoch,
+            epochs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Leader identity for a slot, or an empty string if unknown
+    pub async fn leader_for_slot(&self, slot: u64) -> String {
+        let epoch = epoch_for_slot(slot, self.slots_per_epoch);
+        let schedule = {
+            // Holding the lock across the fetch makes concurrent callers wait for
+            // a single request per epoch instead of stampeding the RPC node
+            let mut epochs = self.epochs.lock().await;
+            if !epochs.contains_key(&epoch) {
+                let fetched = match self.fetch(epoch).await {
+                    Ok(schedule) => schedule.map(Arc::new),
+                    Err(e) => {
+                        tracing::warn!("Failed to fetch leader schedule for epoch {}: {}", epoch, e);
+                        None
+                    }
+                };
+                epochs.insert(epoch, fetched);
+            }
+            epochs.get(&epoch).cloned().flatten()
+        };
+
+        schedule
+            .and_then(|leaders| leaders.get((slot % self.slots_per_epoch) as usize).cloned())
+            .unwrap_or_default()
+    }
+
+    async fn fetch(&self, epoch: u64) -> Result<Option<Vec<String>>, Box<dyn std::error::Error + Send + Sync>> {
+        let first_slot = epoch * self.slots_per_epoch;
+        let schedule: Option<HashMap<String, Vec<u64>>> = self
+            .rpc
+            .call("getLeaderSchedule", serde_json::json!([first_slot]))
+            .await?;
+        let Some(schedule) = schedule else {
+            return Ok(None);
+        };
+
+        let mut leaders = vec![String::new(); self.slots_per_epoch as usize];
+        for (identity, slot_indexes) in schedule {
+            for idx in slot_indexes {
+                if let Some(entry) = leaders.get_mut(idx as usize) {
+                    *entry = identity.clone();
+                }
+            }
+        }
+        tracing::info!("Loaded leader schedule for epoch {}", epoch);
+        Ok(Some(leaders))
+    }
+}
@@ -0,0 +1,104 @@
+//! Caps on failed_transactions rows (`[failed_rows]`)
+//!
+//! A parser that fails on every instruction (an outdated IDL, a program
+//! upgrade) would otherwise write a failed_transactions row for each of them,
+//! which over a large range is hundreds of millions of rows of the same
+//! error. Failed rows can be capped per run and per protocol, and sampled by
+//! signature (so reruns keep the same ones, independently of the protocol's
+//! own `sample_rate`). Only the rows are suppressed:
+//! the failure counters, stale IDL detection and the run summary still see
+//! every failure, and the suppressed counts are printed at the end of the run.
+//! Caps are per process; parallel indexers each get their own.
+
+use crate::fixtures::{sample_by_signature, SampleWindow};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Counts {
+    written: u64,
+    capped: u64,
+    sampled_out: u64,
+}
+
+#[derive(Debug)]
+pub struct FailedRowLimiter {
+    /// Rows for the run (0 = unlimited)
+    max_rows: u64,
+    /// Rows per protocol for the run (0 = unlimited)
+    max_rows_per_protocol: u64,
+    sample_rate: f64,
+    written: AtomicU64,
+    protocols: Mutex<HashMap<String, Counts>>,
+}
+
+impl FailedRowLimiter {
+    pub fn new(max_rows: u64, max_rows_per_protocol: u64, sample_rate: f64) -> Self {
+        Self {
+            max_rows,
+            max_rows_per_protocol,
+            sample_rate,
+            written: AtomicU64::new(0),
+            protocols: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Unlimited, for tests and library use
+    pub fn unlimited() -> Self {
+        Self::new(0, 0, 1.0)
+    }
+
+    /// Whether to write the failed row of this protocol's instruction, counting it either way
+    pub fn admit(&self, protocol: &str, signature: &[u8]) -> bool {
+        let mut protocols = self.protocols.lock().unwrap_or_else(|e| e.into_inner());
+        if !protocols.contains_key(protocol) {
+            protocols.insert(protocol.to_string(), Counts::default());
+        }
+        let counts = protocols.get_mut(protocol).unwrap();
+        if !sample_by_signature(signature, SampleWindow::FailedRows, self.sample_rate) {
+            counts.sampled_out += 1;
+            return false;
+        }
+        let protocol_full = self.max_rows_per_protocol > 0 && counts.written >= self.max_rows_per_protocol;
+        let run_full = self.max_rows > 0 && self.written.load(Ordering::Relaxed) >= self.max_rows;
+        if protocol_full || run_full {
+            if counts.capped == 0 {
+                tracing::warn!(
+                    protocol,
+                    "failed_transactions cap reached ({}); further failures are counted but not stored",
+                    if protocol_full { "per protocol" } else { "per run" }
+                );
+            }
+            counts.capped += 1;
+            return false;
+        }
+        counts.written += 1;
+        self.written.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    /// (protocol, rows dropped by a cap, rows sampled out), for protocols with any suppressed
+    pub fn suppressed(&self) -> Vec<(String, u64, u64)> {
+        let protocols = self.protocols.lock().unwrap_or_else(|e| e.into_inner());
+        let mut suppressed: Vec<_> = protocols
+            .iter()
+            .filter(|(_, counts)| counts.capped + counts.sampled_out > 0)
+            .map(|(protocol, counts)| (protocol.clone(), counts.capped, counts.sampled_out))
+            .collect();
+        suppressed.sort();
+        suppressed
+    }
+
+    pub fn print_summary(&self) {
+        let suppressed = self.suppressed();
+        if suppressed.is_empty() {
+            return;
+        }
+        println!("\n=== Suppressed failed rows ===");
+        println!("Stored: {}", self.written.load(Ordering::Relaxed));
+        for (protocol, capped, sampled_out) in suppressed {
+            println!("{}: {} over cap, {} sampled out", protocol, capped, sampled_out);
+        }
+    }
+}
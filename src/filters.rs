@@ -0,0 +1,42 @@
+//! Ingest-time account filters (`[filters]`)
+//!
+//! Matched against every account a transaction references, including
+//! addresses loaded from lookup tables and program ids, so a pool, a wallet or
+//! a program can be listed alike.
+
+use crate::alt::parse_address;
+use crate::config::FiltersConfig;
+use crate::multi_parser::AccountKeys;
+use solana_address::Address;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default)]
+pub struct AccountFilter {
+    include: HashSet<Address>,
+    exclude: HashSet<Address>,
+}
+
+impl AccountFilter {
+    pub fn new(config: &FiltersConfig) -> Result<Self, String> {
+        let parse = |list: &[String], key: &str| {
+            list.iter()
+                .map(|a| {
+                    parse_address(a.trim())
+                        .ok_or_else(|| format!("Invalid address in filters.{}: '{}'", key, a))
+                })
+                .collect::<Result<HashSet<_>, _>>()
+        };
+        Ok(Self {
+            include: parse(&config.include_accounts, "include_accounts")?,
+            exclude: parse(&config.exclude_accounts, "exclude_accounts")?,
+        })
+    }
+
+    /// Returns true if a transaction referencing `accounts` should be stored
+    pub fn allows(&self, accounts: &AccountKeys) -> bool {
+        if !self.include.is_empty() && !accounts.iter().any(|a| self.include.contains(a)) {
+            return false;
+        }
+        !accounts.iter().any(|a| self.exclude.contains(a))
+    }
+}
@@ -0,0 +1,119 @@
+//! Firehose error policy (`[processing] on_firehose_error`) and the firehose_errors table
+//!
+//! The firehose reports archive errors (timeouts, dropped connections, missing
+//! or corrupt CAR data) through its error handler and resumes the failing
+//! thread from the slot it stopped at. Every report is recorded with its slot
+//! and a coarse error class, so coverage gaps can be traced back to their
+//! cause. The policy decides when to give up:
+//!
+//! - `skip` (default): record the error and let the firehose carry on
+//! - `retry`: let the firehose resume up to `firehose_error_retries` times at
+//!   the same slot, then abort
+//! - `abort`: stop the run at the first error
+//!
+//! An aborted run fails like any other firehose error: buffered rows are
+//! flushed, the range claim is marked failed and the watermark is logged.
+//! Other reasons to stop a run early (an exhausted download budget) abort it
+//! through [`FirehoseErrors::abort`] the same way.
+
+use crate::storage::FirehoseError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FirehoseErrorPolicy {
+    #[default]
+    Skip,
+    Retry,
+    Abort,
+}
+
+impl FirehoseErrorPolicy {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "skip" => Some(Self::Skip),
+            "retry" => Some(Self::Retry),
+            "abort" => Some(Self::Abort),
+            _ => None,
+        }
+    }
+}
+
+/// Coarse class of a firehose error message
+pub fn error_class(message: &str) -> &'static str {
+    let message = message.to_ascii_lowercase();
+    if message.contains("timed out") || message.contains("timeout") {
+        "timeout"
+    } else if message.contains("connection") || message.contains("connect") || message.contains("reset by peer") {
+        "connection"
+    } else if message.contains("404") || message.contains("not found") {
+        "not_found"
+    } else if message.contains("decode") || message.contains("parse") || message.contains("invalid") {
+        "decode"
+    } else {
+        "other"
+    }
+}
+
+#[derive(Debug)]
+pub struct FirehoseErrors {
+    policy: FirehoseErrorPolicy,
+    max_retries: u32,
+    /// Errors seen per slot
+    attempts: Mutex<HashMap<u64, u32>>,
+    /// Why the run was aborted, once it has been
+    aborted: Mutex<Option<String>>,
+}
+
+impl FirehoseErrors {
+    pub fn new(policy: FirehoseErrorPolicy, max_retries: u32) -> Self {
+        Self {
+            policy,
+            max_retries,
+            attempts: Mutex::default(),
+            aborted: Mutex::default(),
+        }
+    }
+
+    /// Apply the policy to an error at `slot`, returning the row to record
+    pub fn record(&self, thread_id: usize, slot: u64, message: &str) -> FirehoseError {
+        let attempt = {
+            let mut attempts = self.attempts.lock().unwrap();
+            let attempt = attempts.entry(slot).or_default();
+            *attempt += 1;
+            *attempt
+        };
+        let action = match self.policy {
+            FirehoseErrorPolicy::Skip => "skipped",
+            FirehoseErrorPolicy::Retry if attempt <= self.max_retries => "retried",
+            FirehoseErrorPolicy::Retry | FirehoseErrorPolicy::Abort => "aborted",
+        };
+        if action == "aborted" {
+            self.aborted
+                .lock()
+                .unwrap()
+                .get_or_insert_with(|| format!("firehose error at slot {} (attempt {}): {}", slot, attempt, message));
+        }
+        FirehoseError {
+            slot,
+            thread_id: thread_id as u32,
+            attempt,
+            error_class: error_class(message).to_string(),
+            error_message: message.to_string(),
+            action: action.to_string(),
+            occurred_at: 0,
+        }
+    }
+
+    /// Abort the run for a reason other than a firehose error (the first reason sticks)
+    pub fn abort(&self, reason: String) {
+        self.aborted.lock().unwrap().get_or_insert(reason);
+    }
+
+    /// The error that aborted the run, if the policy gave up
+    pub fn aborted(&self) -> Option<String> {
+        self.aborted.lock().unwrap().clone()
+    }
+}
@@ -0,0 +1,40 @@
+//! First-seen wallets (wallet_first_seen)
+//!
+//! Records the first slot, signature and protocol at which each user wallet
+//! (a stored transaction's fee payer) appears. A run writes a row when it sees
+//! a wallet at an earlier slot than any it has seen before, so threads working
+//! out of slot order write at most a few rows per wallet. Across runs the
+//! table's ReplacingMergeTree keeps each wallet's lowest slot, so indexing
+//! ranges in any order converges on the true first appearance.
+
+use solana_address::Address;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+#[derive(Debug, Default)]
+pub struct FirstSeen {
+    /// Lowest slot this run has seen each wallet at
+    slots: Mutex<HashMap<Address, u64>>,
+}
+
+impl FirstSeen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note `wallet` at `slot`, returning true if that's the earliest this run has seen it
+    pub fn observe(&self, wallet: &Address, slot: u64) -> bool {
+        let mut slots = self.slots.lock().unwrap();
+        match slots.get_mut(wallet) {
+            Some(first) if *first <= slot => false,
+            Some(first) => {
+                *first = slot;
+                true
+            }
+            None => {
+                slots.insert(*wallet, slot);
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Fixture capture for building parser regression corpora
+//!
+//! With `--capture-fixtures <dir>`, a deterministic sample of instructions sent to
+//! parsers is written as JSON (raw inputs plus transaction context and the parse
+//! outcome) to `<dir>/<protocol>/<signature>_<index>.json`. The fixture corpus
+//! test (`tests/fixture_corpus.rs`) replays these to catch parser regressions.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use yellowstone_vixen_core::instruction::InstructionUpdate;
+use yellowstone_vixen_core::Pubkey;
+
+/// One captured parser input and its outcome at capture time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionFixture {
+    pub signature: String,
+    pub slot: u64,
+    pub instruction_index: u16,
+    pub protocol_name: String,
+    pub program_id: String,
+    /// Hex-encoded instruction data
+    pub data: String,
+    pub accounts: Vec<String>,
+    pub fee: u64,
+    pub compute_units: u64,
+    pub log_messages: Vec<String>,
+    /// Instruction type if parsing succeeded at capture time
+    pub instruction_type: Option<String>,
+    /// Error message if parsing failed at capture time
+    pub error: Option<String>,
+}
+
+impl InstructionFixture {
+    /// Rebuild the parser input this fixture was captured from
+    pub fn to_instruction_update(&self) -> Result<InstructionUpdate, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(InstructionUpdate {
+            program: parse_pubkey(&self.program_id)?,
+            data: hex::decode(&self.data).map_err(|e| format!("Invalid fixture data: {}", e))?,
+            accounts: self
+                .accounts
+                .iter()
+                .map(|a| parse_pubkey(a))
+                .collect::<Result<_, _>>()?,
+            shared: Default::default(),
+            inner: vec![],
+        })
+    }
+
+    /// Load all fixtures under a capture directory (recursively)
+    pub fn load_dir(dir: &Path) -> Result<Vec<(PathBuf, Self)>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut fixtures = Vec::new();
+        if !dir.exists() {
+            return Ok(fixtures);
+        }
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                fixtures.extend(Self::load_dir(&path)?);
+            } else if path.extension().is_some_and(|ext| ext == "json") {
+                let content = std::fs::read_to_string(&path)?;
+                let fixture = serde_json::from_str(&content)
+                    .map_err(|e| format!("Invalid fixture {}: {}", path.display(), e))?;
+                fixtures.push((path, fixture));
+            }
+        }
+        fixtures.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(fixtures)
+    }
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes: [u8; 32] = bs58::decode(s)
+        .into_vec()
+        .map_err(|e| format!("Invalid pubkey {}: {}", s, e))?
+        .try_into()
+        .map_err(|_| format!("Invalid pubkey length: {}", s))?;
+    Ok(Pubkey::from(bytes))
+}
+
+/// Independent signature samples: each reads its own 8 bytes of the signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleWindow {
+    /// `[protocols.*] sample_rate`
+    Protocol = 0,
+    /// `--sample-rate` of `--capture-fixtures`
+    Fixtures = 1,
+    /// `[failed_rows] sample_rate`
+    FailedRows = 2,
+}
+
+/// Deterministic sampling by signature: signatures are uniformly random, so any
+/// 8 of their bytes give an unbiased, reproducible sample across runs. Samples
+/// using different windows are independent, so a transaction kept by one rate
+/// is no more likely to be kept by another.
+pub fn sample_by_signature(signature: &[u8], window: SampleWindow, rate: f64) -> bool {
+    if rate >= 1.0 {
+        return true;
+    }
+    if rate <= 0.0 {
+        return false;
+    }
+    let mut bytes = [0u8; 8];
+    for (dst, src) in bytes.iter_mut().zip(signature.iter().skip(window as usize * 8)) {
+        *dst = *src;
+    }
+    (u64::from_le_bytes(bytes) as f64 / u64::MAX as f64) < rate
+}
+
+pub struct FixtureCapture {
+    dir: PathBuf,
+    sample_rate: f64,
+}
+
+impl FixtureCapture {
+    pub fn new(dir: PathBuf, sample_rate: f64) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create fixture directory {}: {}", dir.display(), e))?;
+        Ok(Self { dir, sample_rate })
+    }
+
+    pub fn should_capture(&self, signature: &[u8]) -> bool {
+        sample_by_signature(signature, SampleWindow::Fixtures, self.sample_rate)
+    }
+
+    /// Write a fixture; errors are logged rather than failing the run
+    pub fn write(&self, fixture: &InstructionFixture) {
+        let protocol_dir = self.dir.join(&fixture.protocol_name);
+        let path = protocol_dir.join(format!("{}_{}.json", fixture.signature, fixture.instruction_index));
+        let result = std::fs::create_dir_all(&protocol_dir)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(fixture).map_err(|e| e.to_string()))
+            .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to write fixture {}: {}", path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Arrow Flight export of indexed tables
+//!
+//! `--serve-flight ADDR` serves ClickHouse tables as Arrow record batches so
+//! pandas/polars/DataFusion clients can pull data without the HTTP interface.
+//! ClickHouse produces the Arrow data itself (`FORMAT ArrowStream`); this
+//! service only re-frames it as Flight messages.
+//!
+//! Tickets are a table name (`transactions`) or JSON with an optional slot
+//! range: `{"table": "transactions", "slot_start": 1000, "slot_end": 2000}`.
+//! Only indexer tables can be read; tickets never carry SQL. Each `do_get`
+//! result is buffered in memory, so use slot ranges for large tables.
+
+use crate::storage::{slot_column, TABLES};
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
+};
+use arrow_ipc::reader::StreamReader;
+use futures_util::stream::BoxStream;
+use futures_util::{StreamExt, TryStreamExt};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use tonic::{Request, Response, Status, Streaming};
+
+#[derive(Debug, Deserialize)]
+struct TableTicket {
+    table: String,
+    slot_start: Option<u64>,
+    slot_end: Option<u64>,
+}
+
+impl TableTicket {
+    fn parse(bytes: &[u8]) -> Result<Self, Status> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| Status::invalid_argument("ticket must be UTF-8"))?
+            .trim();
+        if text.starts_with('{') {
+            serde_json::from_str(text).map_err(|e| Status::invalid_argument(format!("invalid ticket: {}", e)))
+        } else {
+            Ok(Self {
+                table: text.to_string(),
+                slot_start: None,
+                slot_end: None,
+            })
+        }
+    }
+
+    fn sql(&self, table_prefix: &str) -> Result<String, Status> {
+        if !TABLES.contains(&self.table.as_str()) {
+            return Err(Status::not_found(format!("unknown table: {}", self.table)));
+        }
+        let slot_column = slot_column(&self.table);
+        let mut filters = Vec::new();
+        if self.slot_start.is_some() || self.slot_end.is_some() {
+            let column = slot_column.ok_or_else(|| {
+                Status::invalid_argument(format!("{} has no slot column to filter on", self.table))
+            })?;
+            if let Some(start) = self.slot_start {
+                filters.push(format!("{} >= {}", column, start));
+            }
+            if let Some(end) = self.slot_end {
+                filters.push(format!("{} < {}", column, end));
+            }
+        }
+        let filter = if filters.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", filters.join(" AND "))
+        };
+        Ok(format!("SELECT * FROM {}{}{} FORMAT ArrowStream", table_prefix, self.table, filter))
+    }
+}
+
+pub struct FlightExport {
+    clickhouse_url: String,
+    table_prefix: String,
+    http: reqwest::Client,
+}
+
+impl FlightExport {
+    pub fn new(clickhouse_url: &str, table_prefix: &str) -> Self {
+        Self {
+            clickhouse_url: clickhouse_url.to_string(),
+            table_prefix: table_prefix.to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Serve until the process exits
+    pub async fn serve(self, addr: SocketAddr) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        tracing::info!("Serving Arrow Flight on {}", addr);
+        tonic::transport::Server::builder()
+            .add_service(FlightServiceServer::new(self))
+            .serve(addr)
+            .await
+            .map_err(|e| format!("{}", e).into())
+    }
+}
+
+#[tonic::async_trait]
+impl FlightService for FlightExport {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+
+    /// One flight per table; the ticket is the table name
+    async fn list_flights(&self, _request: Request<Criteria>) -> Result<Response<Self::ListFlightsStream>, Status> {
+        let flights: Vec<Result<FlightInfo, Status>> = TABLES
+            .iter()
+            .map(|table| {
+                Ok(FlightInfo::new()
+                    .with_descriptor(FlightDescriptor::new_path(vec![table.to_string()]))
+                    .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new(table.to_string()))))
+            })
+            .collect();
+        Ok(Response::new(futures_util::stream::iter(flights).boxed()))
+    }
+
+    async fn do_get(&self, request: Request<Ticket>) -> Result<Response<Self::DoGetStream>, Status> {
+        let ticket = TableTicket::parse(&request.into_inner().ticket)?;
+        let sql = ticket.sql(&self.table_prefix)?;
+
+        let response = self
+            .http
+            .post(&self.clickhouse_url)
+            .body(sql)
+            .send()
+            .await
+            .map_err(|e| Status::unavailable(format!("ClickHouse request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(Status::internal(format!("ClickHouse error: {}", message.trim())));
+        }
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| Status::unavailable(format!("ClickHouse response failed: {}", e)))?;
+
+        let reader = StreamReader::try_new(std::io::Cursor::new(body), None)
+            .map_err(|e| Status::internal(format!("Invalid Arrow stream from ClickHouse: {}", e)))?;
+        let batches: Vec<_> = reader.map(|batch| batch.map_err(FlightError::from)).collect();
+        let stream = FlightDataEncoderBuilder::new()
+            .build(futures_util::stream::iter(batches))
+            .map_err(|e| Status::internal(e.to_string()))
+            .boxed();
+        Ok(Response::new(stream))
+    }
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake is not required"))
+    }
+
+    async fn get_flight_info(&self, _request: Request<FlightDescriptor>) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("use list_flights"))
+    }
+
+    async fn poll_flight_info(&self, _request: Request<FlightDescriptor>) -> Result<Response<PollInfo>, Status> {
+        Err(Status::unimplemented("use list_flights"))
+    }
+
+    async fn get_schema(&self, _request: Request<FlightDescriptor>) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("schemas are sent with do_get"))
+    }
+
+    async fn do_put(&self, _request: Request<Streaming<FlightData>>) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("the export service is read-only"))
+    }
+
+    async fn do_action(&self, _request: Request<Action>) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("no actions"))
+    }
+
+    async fn list_actions(&self, _request: Request<Empty>) -> Result<Response<Self::ListActionsStream>, Status> {
+        Ok(Response::new(futures_util::stream::empty().boxed()))
+    }
+
+    async fn do_exchange(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Err(Status::unimplemented("the export service is read-only"))
+    }
+}
@@ -0,0 +1,68 @@
+//! Liveness rows in indexer_heartbeats
+//!
+//! Every `[heartbeat] interval_secs`, writes the instance's progress (last
+//! processed slot, flushed watermark, buffered rows) so a fleet can be
+//! monitored with SQL alone. A final row with the run's outcome is written
+//! when the firehose returns; an instance whose latest row is `running` and
+//! old has stalled or died.
+
+use crate::storage::{ClickHouseStorage, IndexerHeartbeat};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct Heartbeat {
+    storage: Arc<ClickHouseStorage>,
+    instance_id: String,
+    run_id: String,
+    slot_start: u64,
+    slot_end: u64,
+}
+
+impl Heartbeat {
+    pub fn new(storage: Arc<ClickHouseStorage>, instance_id: String, run_id: String, slot_start: u64, slot_end: u64) -> Self {
+        Self {
+            storage,
+            instance_id,
+            run_id,
+            slot_start,
+            slot_end,
+        }
+    }
+
+    /// Write one heartbeat row with the given status
+    pub async fn beat(&self, status: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let watermark = self.storage.watermark();
+        let row = IndexerHeartbeat {
+            instance_id: self.instance_id.clone(),
+            run_id: self.run_id.clone(),
+            slot_start: self.slot_start,
+            slot_end: self.slot_end,
+            last_slot: watermark.last_slot().unwrap_or(0),
+            watermark_slot: watermark.slot(),
+            buffered_rows: self.storage.buffered_rows().await as u64,
+            status: status.to_string(),
+            heartbeat_at: 0,
+        };
+        self.storage.insert_heartbeat(row).await
+    }
+
+    /// Beat as `running` every `interval` until the task is dropped
+    pub async fn run(self, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.beat("running").await {
+                tracing::warn!("Failed to write heartbeat: {:?}", e);
+            }
+        }
+    }
+}
+
+/// Default instance id: the host name (the container id under Docker), else the pid
+pub fn default_instance_id() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| format!("pid-{}", std::process::id()))
+}
@@ -1,52 +1,273 @@
-use crate::multi_parser::{build_full_account_list, extract_instruction_type, try_parse};
-use crate::storage::{ClickHouseStorage, FailedTransaction, Transaction};
-use jetstreamer_firehose::firehose::TransactionData;
+use crate::alt::{address_to_string, AltCache, AltFetcher, AltInstruction, ResolutionSource};
+use crate::config::ProtocolConfig;
+use crate::cooccurrence::Cooccurrence;
+use crate::creators::FirstBuyers;
+use crate::daily_users::DailyUsers;
+use crate::decimals::{ui_amount, TokenDecimals, SOL_DECIMALS};
+use crate::discriminators::UnknownDiscriminators;
+use crate::epoch::{epoch_for_slot, LeaderSchedule};
+use crate::failed_rows::FailedRowLimiter;
+use crate::filters::AccountFilter;
+use crate::first_seen::FirstSeen;
+use crate::fixtures::{FixtureCapture, InstructionFixture};
+use crate::intern::Interner;
+use crate::log_invocations::parse_invocations;
+use crate::mints::MintCounter;
+use crate::network::Network;
+use crate::parse_pool::ParsePool;
+use crate::payload_limits::PayloadLimits;
+use crate::pools::PoolRegistry;
+use crate::stage_metrics::{StageMetrics, StageTimings};
+use crate::summary::HourlySummary;
+use crate::tx_error::instruction_failure;
+use crate::multi_parser::{
+    account_roles, build_full_account_list, try_parse_candidates, AccountKeys, ErrorClass, ParsedInstruction,
+    ParserMap,
+};
+use crate::priority::ProtocolPriority;
+use crate::raw_data::RawDataEncoding;
+use crate::slot_time::SlotTimeOracle;
+#[cfg(feature = "bubblegum")]
+use crate::storage::CompressedAsset;
+use crate::storage::{
+    AccountLifecycle, AltUpdate, BridgeEvent, FailedTransaction, GovernanceEvent,
+    LiquidityEvent, LiquidityPosition, LogInvocation, NftTrade, OrderEvent, SlotStatus, SlotTime, Storage, Swap,
+    TokenCreator, Trade, Transaction, TransferEdge, UnknownDiscriminator, WalletFirstSeen, WriteGroup,
+};
+use base64::Engine;
+use jetstreamer_firehose::firehose::{BlockData, TransactionData};
+use solana_address::Address;
 use solana_message::VersionedMessage;
-use std::collections::HashMap;
+use solixdb_decoder::bridge::bridge_event;
+use solixdb_decoder::clmm::{clmm_swap_state, ClmmSwapState};
+#[cfg(feature = "bubblegum")]
+use solixdb_decoder::cnft::{self, compressed_asset_event, decode_leaf_schema_event};
+use solixdb_decoder::creators::{token_buy, token_creation};
+use solixdb_decoder::events::{liquidity_event, swap_event, trade_event};
+use solixdb_decoder::governance::governance_event;
+use solixdb_decoder::lifecycle::{decode_account_lifecycle, decode_initialize_mint, LifecycleProgram};
+use solixdb_decoder::nft::nft_trade_event;
+use solixdb_decoder::orders::order_event;
+use solixdb_decoder::positions::position_event;
+use solixdb_decoder::registry::{instruction_type_id, protocol_id};
+use solixdb_decoder::transfers::{decode_transfer, pair_deltas, TransferProgram};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Instant, SystemTime};
 use yellowstone_vixen_core::instruction::InstructionUpdate;
 
-// Calculate block_time from slot (Solana genesis: 2020-09-23 00:00:00 UTC = 1600646400)
-const GENESIS_TIMESTAMP: u64 = 1600646400;
-const SLOT_DURATION_SECONDS: f64 = 0.4; // ~400ms per slot
+/// `[protocols.<name>]` key for account_lifecycle action filtering
+pub const ACCOUNT_LIFECYCLE_PROTOCOL: &str = "account_lifecycle";
+
+/// new_mints_hourly source for mints not created through a launchpad
+const SPL_TOKEN_MINT_SOURCE: &str = "spl_token";
+
+/// Shared state for process_transaction, built once in main and shared across firehose threads
+pub struct ProcessingContext {
+    /// Genesis timestamp and slot duration block times are estimated from
+    pub network: Network,
+    /// Block times from real blocks, for slot -> block_time (None estimates from `network`)
+    pub slot_times: Option<Arc<SlotTimeOracle>>,
+    /// Program id -> candidate parsers, with the network's program ids applied
+    pub parser_map: ParserMap,
+    /// Token/Token-2022/ATA program ids, scanned for account_lifecycle events
+    pub lifecycle_programs: HashMap<Vec<u8>, LifecycleProgram>,
+    /// System/Token/Token-2022 program ids, scanned for transfer_edges (None when disabled)
+    pub transfer_programs: Option<HashMap<Vec<u8>, TransferProgram>>,
+    /// (success, failed) counters per parser name
+    pub metrics: HashMap<String, (Arc<AtomicU64>, Arc<AtomicU64>)>,
+    pub protocols: HashMap<String, ProtocolConfig>,
+    /// `[processing] protocol_priority` order and `count_once_per_tx`
+    pub protocol_priority: ProtocolPriority,
+    /// `[filters]` include/exclude account lists
+    pub account_filter: AccountFilter,
+    /// Fill transactions.named_accounts from the IDL account names
+    pub named_accounts: bool,
+    /// Encoding of failed_transactions.raw_data
+    pub raw_data_encoding: RawDataEncoding,
+    /// Store the log invocation tree of transactions with parse failures
+    pub log_invocations: bool,
+    /// Shared strings for program ids, names and instruction types
+    pub interner: Interner,
+    pub alt_cache: AltCache,
+    /// Fetches lookup tables missing from `alt_cache` via RPC (None when disabled)
+    pub alt_fetcher: Option<AltFetcher>,
+    pub leader_schedule: Option<LeaderSchedule>,
+    pub fixture_capture: Option<FixtureCapture>,
+    /// First buyers of mints launched during this run, for token_creators
+    pub first_buyers: FirstBuyers,
+    /// Mints initialized per hour, for new_mints_hourly
+    pub new_mints: MintCounter,
+    /// Mint decimals from the token balances seen so far, for UI amounts
+    pub decimals: TokenDecimals,
+    /// Per-hour protocol totals, for protocol_hourly_summary (None when disabled)
+    pub hourly_summary: Option<HourlySummary>,
+    /// Per-day unique user sketches, for protocol_daily_users (None when disabled)
+    pub daily_users: Option<DailyUsers>,
+    /// Per-day protocol pair counts, for protocol_cooccurrence (None when disabled)
+    pub cooccurrence: Option<Cooccurrence>,
+    /// Earliest slot per wallet this run, for wallet_first_seen (None when disabled)
+    pub first_seen: Option<FirstSeen>,
+    /// Pools seen this run, for pools (None when disabled)
+    pub pools: Option<Arc<PoolRegistry>>,
+    /// Discriminator-mismatch failure counts, for unknown_discriminators
+    pub unknown_discriminators: UnknownDiscriminators,
+    /// `[failed_rows]` caps and sampling of failed_transactions rows
+    pub failed_rows: FailedRowLimiter,
+    /// `[payload_limits]` caps on stored logs and instruction data
+    pub payload_limits: PayloadLimits,
+    /// Decodes instructions off the async runtime (None parses inline, as tests do)
+    pub parse_pool: Option<ParsePool>,
+    pub stage_metrics: StageMetrics,
+    pub storage: Arc<dyn Storage>,
+}
 
 pub async fn process_transaction(
     tx: TransactionData,
-    parser_map: &HashMap<Vec<u8>, &'static str>,
-    metrics: &HashMap<String, (Arc<AtomicU64>, Arc<AtomicU64>)>,
-    storage: &Arc<ClickHouseStorage>,
+    ctx: &ProcessingContext,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let ProcessingContext {
+        network,
+        slot_times,
+        parser_map,
+        lifecycle_programs,
+        transfer_programs,
+        metrics,
+        protocols,
+        protocol_priority,
+        account_filter,
+        named_accounts,
+        raw_data_encoding,
+        log_invocations,
+        interner,
+        alt_cache,
+        alt_fetcher,
+        leader_schedule,
+        fixture_capture,
+        first_buyers,
+        new_mints,
+        decimals,
+        hourly_summary,
+        daily_users,
+        cooccurrence,
+        first_seen,
+        pools,
+        unknown_discriminators,
+        failed_rows,
+        payload_limits,
+        parse_pool,
+        stage_metrics,
+        storage,
+    } = ctx;
+    let mut timings = StageTimings::default();
+    let stage_start = Instant::now();
+
+    // Most transactions invoke no program we decode; skip them after a scan of their static
+    // keys (invoked program ids can't come from a lookup table), before any ALT resolution
+    let is_known = |key: &Address| {
+        let key = key.to_bytes();
+        parser_map.contains_key(key.as_slice())
+            || lifecycle_programs.contains_key(key.as_slice())
+            || transfer_programs.as_ref().is_some_and(|programs| programs.contains_key(key.as_slice()))
+    };
+    if !tx.transaction.message.static_account_keys().iter().any(is_known) {
+        timings.account_resolution += stage_start.elapsed();
+        stage_metrics.record(&timings);
+        return Ok(());
+    }
+
+    // Check if transaction was successful on-chain
+    // If transaction failed on-chain, skip it entirely (only store successful transactions),
+    // before resolving lookup tables (possibly over RPC) for it
+    if tx.transaction_status_meta.status.is_err() {
+        if let Some(failure) = instruction_failure(&tx.transaction_status_meta.status) {
+            tracing::trace!(
+                "Skipping {} (failed on-chain in instruction {}: {})",
+                tx.signature,
+                failure.instruction_index,
+                failure.code()
+            );
+        }
+        timings.account_resolution += stage_start.elapsed();
+        stage_metrics.record(&timings);
+        return Ok(());
+    }
+
+    // V0 transactions normally carry their loaded addresses in meta. When those are
+    // missing, fall back to the lookup table contents indexed so far, then to RPC.
+    let loaded_addresses = &tx.transaction_status_meta.loaded_addresses;
+    let (resolved_from_alt, resolution_source) = match &tx.transaction.message {
+        VersionedMessage::V0(msg)
+            if !msg.address_table_lookups.is_empty()
+                && loaded_addresses.writable.is_empty()
+                && loaded_addresses.readonly.is_empty() =>
+        {
+            let lookups = &msg.address_table_lookups;
+            if let Some(resolved) = alt_cache.resolve(lookups) {
+                (Some(resolved), ResolutionSource::AltIndex)
+            } else if let Some(resolved) = match alt_fetcher {
+                Some(fetcher) => fetcher.resolve(alt_cache, lookups).await,
+                None => None,
+            } {
+                (Some(resolved), ResolutionSource::Rpc)
+            } else {
+                (None, ResolutionSource::Unresolved)
+            }
+        }
+        _ => (None, ResolutionSource::Meta),
+    };
+    let (loaded_writable, loaded_readonly) = match &resolved_from_alt {
+        Some((writable, readonly)) => (writable.as_slice(), readonly.as_slice()),
+        None => (loaded_addresses.writable.as_slice(), loaded_addresses.readonly.as_slice()),
+    };
+
     let all_accounts = build_full_account_list(
         &tx.transaction.message,
-        &tx.transaction_status_meta.loaded_addresses.writable,
-        &tx.transaction_status_meta.loaded_addresses.readonly,
+        loaded_writable,
+        loaded_readonly,
     );
 
+    let roles = account_roles(&tx.transaction.message, loaded_writable.len(), loaded_readonly.len());
+    timings.account_resolution += stage_start.elapsed();
+
+    // Skip transactions outside the [filters] account lists before any parsing
+    if !account_filter.allows(&all_accounts) {
+        stage_metrics.record(&timings);
+        return Ok(());
+    }
+
     let instructions = match &tx.transaction.message {
         VersionedMessage::Legacy(msg) => &msg.instructions,
         VersionedMessage::V0(msg) => &msg.instructions,
     };
 
-    // Check if transaction was successful on-chain
-    // If transaction failed on-chain, skip it entirely (only store successful transactions)
-    // status field is an enum: Ok(()) for success, Err(...) for failure
-    let status_str = format!("{:?}", tx.transaction_status_meta.status);
-    if status_str.starts_with("Err") {
-        // Transaction failed on-chain, skip storing it
-        return Ok(());
-    }
-
     // Extract transaction metadata
     let signature = tx.signature.to_string();
     let fee = tx.transaction_status_meta.fee;
     let compute_units = tx.transaction_status_meta.compute_units_consumed.unwrap_or(0);
     
-    // Calculate block_time from slot (Solana genesis: 2020-09-23 00:00:00 UTC = 1600646400)
-    // Note: Slot duration is ~400ms, but actual block times can vary
-    // Using calculated value as fallback, but prefer actual block_time if available
-    let block_time = GENESIS_TIMESTAMP + ((tx.slot as f64 * SLOT_DURATION_SECONDS) as u64);
+    // Transactions don't carry their block's time: take it from the slot time oracle
+    // (real block times), or estimate it from the network's genesis timestamp
+    let block_time = match slot_times {
+        Some(slot_times) => slot_times.block_time(tx.slot),
+        None => network.block_time(tx.slot),
+    };
+
+    // Epoch is derived from slot; leader needs the (optional) RPC leader schedule
+    let epoch = epoch_for_slot(tx.slot, network.slots_per_epoch) as u32;
+    let leader = match leader_schedule {
+        Some(schedule) => schedule.leader_for_slot(tx.slot).await,
+        None => String::new(),
+    };
+    // Repeated on every transactions row, so rows can be attributed to wallets
+    let fee_payer = all_accounts.first().map(address_to_string).unwrap_or_default();
+    let signers: Vec<String> = all_accounts
+        .iter()
+        .zip(&roles)
+        .filter(|(_, role)| role.signer)
+        .map(|(address, _)| address_to_string(address))
+        .collect();
     
     // Extract log messages for failed transactions (for debugging)
     let log_messages: Vec<String> = tx
@@ -56,32 +277,57 @@ pub async fn process_transaction(
         .unwrap_or_default()
         .into_iter()
         .collect();
-    let log_messages_str = log_messages.join("\n");
+    let mut log_messages_str = log_messages.join("\n");
+    let log_messages_truncated = payload_limits.text(&mut log_messages_str);
     
     // Date and hour are now calculated automatically by ClickHouse using MATERIALIZED columns
     // No need to calculate them in Rust - ClickHouse will compute them from block_time
 
-    // Track instruction index (for future use if needed for deduplication)
-    let mut _instruction_index = 0u16;
-    for ix in instructions {
-        let program_idx = ix.program_id_index as usize;
-        if program_idx >= all_accounts.len() {
-            continue;
-        }
-        let program_id = all_accounts[program_idx];
+    // All rows for this transaction, handed to storage together at the end
+    let mut group = WriteGroup::default();
+    // Mints launched (by launchpad) and initialized in this transaction, for new_mints_hourly
+    let mut launched_mints: HashMap<String, &'static str> = HashMap::new();
+    let mut initialized_mints: BTreeSet<String> = BTreeSet::new();
+    // Protocols that stored a transactions row, for protocol_hourly_summary, protocol_daily_users
+    // and protocol_cooccurrence
+    let mut stored_protocols: BTreeSet<&'static str> = BTreeSet::new();
+
+    // Listed protocols first, highest priority first (see `priority`)
+    let order = protocol_priority.order(instructions.iter().map(|ix| {
+        all_accounts
+            .get(ix.program_id_index as usize)
+            .and_then(|program_id| parser_map.get(program_id.to_bytes().as_slice()))
+            .and_then(|candidates| candidates.first().copied())
+    }));
+    // Rank of the first listed protocol that stored a row, for count_once_per_tx
+    let mut counted_rank = None;
+
+    for position in order {
+        let ix = &instructions[position];
+        let Some(program_id) = all_accounts.get(ix.program_id_index as usize) else { continue };
         let program_id_bytes = program_id.to_bytes();
-        let program_id_str = bs58::encode(program_id_bytes.as_slice()).into_string();
+        let program_id_str = interner.address(program_id);
 
         // Check if we have a parser for this program
-        if let Some(parser_name) = parser_map.get(program_id_bytes.as_slice()) {
+        if let Some(candidates) = parser_map.get(program_id_bytes.as_slice()) {
+            // The primary candidate names the protocol; `parser` records which one decoded it
+            let Some(parser_name) = candidates.first() else { continue };
+            let rank = protocol_priority.rank(parser_name);
+            if protocol_priority.skips(rank, counted_rank) {
+                continue;
+            }
+            // Skip transactions outside the protocol's [protocols.<name>] sample_rate
+            if protocols.get(*parser_name).is_some_and(|p| !p.samples(tx.signature.as_ref())) {
+                continue;
+            }
+            let protocol_id = protocol_id(parser_name);
             // Resolve accounts
+            let stage_start = Instant::now();
             let mut resolved_accounts = Vec::new();
             for account_idx in &ix.accounts {
-                let idx = *account_idx as usize;
-                if idx >= all_accounts.len() {
-                    continue;
+                if let Some(account) = all_accounts.get(*account_idx as usize) {
+                    resolved_accounts.push(account.to_bytes().into());
                 }
-                resolved_accounts.push(all_accounts[idx].to_bytes().into());
             }
 
             let instruction_update = InstructionUpdate {
@@ -92,39 +338,240 @@ pub async fn process_transaction(
                 inner: vec![],
             };
 
-            let raw_data = hex::encode(&ix.data);
+            timings.account_resolution += stage_start.elapsed();
 
             // Try parsing
-            match try_parse(&instruction_update, parser_name).await {
-                Ok(parsed_instruction) => {
-                    if let Some((success, _)) = metrics.get(*parser_name) {
+            let stage_start = Instant::now();
+            let (instruction_update, parse_result) = match parse_pool {
+                Some(pool) => pool.parse(instruction_update, candidates.clone()).await,
+                None => {
+                    let result = try_parse_candidates(&instruction_update, candidates).await;
+                    (instruction_update, result)
+                }
+            };
+            timings.parse += stage_start.elapsed();
+
+            // Sampled capture of raw parser inputs for regression corpora
+            if let Some(capture) = fixture_capture {
+                if capture.should_capture(tx.signature.as_ref()) {
+                    capture.write(&InstructionFixture {
+                        signature: signature.clone(),
+                        slot: tx.slot,
+                        instruction_index: position as u16,
+                        protocol_name: parser_name.to_string(),
+                        program_id: program_id_str.to_string(),
+                        data: hex::encode(&ix.data),
+                        accounts: instruction_update.accounts.iter().map(|a| a.to_string()).collect(),
+                        fee,
+                        compute_units,
+                        log_messages: log_messages.clone(),
+                        instruction_type: parse_result.as_ref().ok().map(|(_, p)| p.instruction_type()),
+                        error: parse_result.as_ref().err().map(|e| format!("{:?}", e)),
+                    });
+                }
+            }
+
+            match parse_result {
+                Ok((parser, parsed_instruction)) => {
+                    if let Some((success, _)) = metrics.get(parser) {
                         success.fetch_add(1, Ordering::Relaxed);
                     }
 
                     // Extract instruction type
-                    let instruction_type = extract_instruction_type(&parsed_instruction);
+                    let stage_start = Instant::now();
+                    let instruction_type = parsed_instruction.instruction_type();
+
+                    // Skip instruction types filtered out by [protocols.<name>] config
+                    if let Some(protocol_config) = protocols.get(*parser_name) {
+                        if !protocol_config.allows(&instruction_type) {
+                            timings.event_extraction += stage_start.elapsed();
+                            continue;
+                        }
+                    }
+
+                    let instruction_type_id = instruction_type_id(&instruction_type);
+                    if let Err(e) = storage
+                        .register_instruction_type(protocol_id, instruction_type_id, &instruction_type)
+                        .await
+                    {
+                        tracing::warn!("Failed to register instruction type {}: {:?}", instruction_type, e);
+                    }
 
                     // Insert successful transaction (transaction already verified as successful on-chain above)
                     // Note: Multiple instructions per transaction will create multiple rows with same signature
                     // This is intentional for instruction-level analytics, but means signatures are not unique
                     let tx_record = Transaction {
                         signature: signature.clone(),
+                        instruction_index: position as u16,
                         slot: tx.slot,
                         block_time,
                         program_id: program_id_str.clone(),
-                        protocol_name: parser_name.to_string(),
-                        instruction_type,
+                        protocol_name: interner.str(parser_name),
+                        protocol_id,
+                        instruction_type: interner.str(&instruction_type),
+                        instruction_type_id,
+                        parser: interner.str(parser),
                         success: 1, // Transaction was successful on-chain
                         fee,
                         compute_units,
                         accounts_count: ix.accounts.len() as u16,
+                        data_len: ix.data.len() as u32,
+                        writable_accounts_count: ix
+                            .accounts
+                            .iter()
+                            .filter(|idx| roles.get(**idx as usize).is_some_and(|r| r.writable))
+                            .count() as u16,
+                        signer_accounts_count: ix
+                            .accounts
+                            .iter()
+                            .filter(|idx| roles.get(**idx as usize).is_some_and(|r| r.signer))
+                            .count() as u16,
+                        epoch,
+                        leader: leader.clone(),
+                        named_accounts: if *named_accounts {
+                            named_accounts_json(&parsed_instruction)
+                        } else {
+                            String::new()
+                        },
+                        resolution_source: interner.str(resolution_source.as_str()),
+                        fee_payer: fee_payer.clone(),
+                        signers: signers.clone(),
                     };
+                    group.transactions.push(tx_record);
+                    stored_protocols.insert(*parser_name);
+                    counted_rank = counted_rank.or(rank);
+                    timings.event_extraction += stage_start.elapsed();
 
-                    if let Err(e) = storage.insert_transaction(tx_record).await {
-                        tracing::error!("Failed to insert transaction: {:?}", e);
+                    // Track lookup table contents for alt_updates and the resolver cache
+                    if let ParsedInstruction::AddressLookupTable(alt_ix) = &parsed_instruction {
+                        if let Some(table) = ix.accounts.first() {
+                            let table = all_accounts.get(*table as usize).copied();
+                            let authority = ix.accounts.get(1)
+                                .and_then(|idx| all_accounts.get(*idx as usize))
+                                .map(address_to_string)
+                                .unwrap_or_default();
+                            if let Some(table) = table {
+                                alt_cache.apply(table, alt_ix);
+                                let (new_addresses, recent_slot) = match alt_ix {
+                                    AltInstruction::ExtendLookupTable { new_addresses } => {
+                                        (new_addresses.iter().map(|a| a.to_string()).collect(), 0)
+                                    }
+                                    AltInstruction::CreateLookupTable { recent_slot, .. } => (Vec::new(), *recent_slot),
+                                    _ => (Vec::new(), 0),
+                                };
+                                group.alt_updates.push(AltUpdate {
+                                    signature: signature.clone(),
+                                    slot: tx.slot,
+                                    block_time,
+                                    table_address: address_to_string(&table),
+                                    action: alt_ix.action().to_string(),
+                                    authority,
+                                    new_addresses,
+                                    recent_slot,
+                                });
+                            }
+                        }
                     }
                     
-                    _instruction_index += 1;
+                    // LP positions and limit order/DCA lifecycle
+                    let stage_start = Instant::now();
+                    push_instruction_events(&mut group, &parsed_instruction, &signature, tx.slot, block_time, parser_name);
+                    timings.event_extraction += stage_start.elapsed();
+                    
+                    // Launchpad creator, deployer and first-buyer attribution
+                    let stage_start = Instant::now();
+                    if let Some(creation) = token_creation(&parsed_instruction) {
+                        let mint = creation.mint.to_string();
+                        first_buyers.track(&mint, tx.slot);
+                        launched_mints.insert(mint.clone(), *parser_name);
+                        for (role, wallet) in [("creator", creation.creator), ("deployer", creation.deployer)] {
+                            group.token_creators.push(TokenCreator {
+                                signature: signature.clone(),
+                                slot: tx.slot,
+                                tx_index: tx.transaction_slot_index as u32,
+                                block_time,
+                                protocol_name: parser_name.to_string(),
+                                launchpad: launchpad(parser_name, creation.config),
+                                mint: mint.clone(),
+                                role: role.to_string(),
+                                wallet: wallet.to_string(),
+                                token_amount: 0,
+                                sol_amount: 0,
+                                name: creation.name.clone(),
+                                symbol: creation.symbol.clone(),
+                                uri: creation.uri.clone(),
+                            });
+                        }
+                    }
+                    if let Some(buy) = token_buy(&parsed_instruction) {
+                        let mint = buy.mint.to_string();
+                        let buyer = buy.buyer.to_string();
+                        if first_buyers.record(&mint, &buyer, tx.slot, tx.transaction_slot_index as u32) {
+                            group.token_creators.push(TokenCreator {
+                                signature: signature.clone(),
+                                slot: tx.slot,
+                                tx_index: tx.transaction_slot_index as u32,
+                                block_time,
+                                protocol_name: parser_name.to_string(),
+                                launchpad: launchpad(parser_name, buy.config),
+                                mint,
+                                role: "first_buyer".to_string(),
+                                wallet: buyer,
+                                token_amount: buy.token_amount,
+                                sol_amount: buy.sol_amount,
+                                name: String::new(),
+                                symbol: String::new(),
+                                uri: String::new(),
+                            });
+                        }
+                    }
+                    timings.event_extraction += stage_start.elapsed();
+
+                    // Compressed NFT lifecycle, keyed by DAS asset id
+                    #[cfg(feature = "bubblegum")]
+                    {
+                        let stage_start = Instant::now();
+                        if let Some(event) = compressed_asset_event(&parsed_instruction) {
+                            // Mints don't carry the nonce; take it from the Bubblegum noop CPI event
+                            let leaf = match event.nonce {
+                                Some(nonce) => Some((cnft::asset_id(&event.merkle_tree, nonce), nonce)),
+                                None => tx
+                                    .transaction_status_meta
+                                    .inner_instructions
+                                    .iter()
+                                    .flatten()
+                                    .filter(|inner| inner.index as usize == position)
+                                    .flat_map(|inner| &inner.instructions)
+                                    .filter(|inner| {
+                                        all_accounts
+                                            .get(inner.instruction.program_id_index as usize)
+                                            .is_some_and(|p| address_to_string(p) == cnft::NOOP_PROGRAM_ID)
+                                    })
+                                    .find_map(|inner| decode_leaf_schema_event(&inner.instruction.data))
+                                    .map(|leaf| (leaf.asset_id, leaf.nonce)),
+                            };
+                            match leaf {
+                                Some((asset_id, leaf_index)) => {
+                                    group.compressed_assets.push(CompressedAsset {
+                                        signature: signature.clone(),
+                                        slot: tx.slot,
+                                        block_time,
+                                        asset_id: asset_id.to_string(),
+                                        merkle_tree: event.merkle_tree.to_string(),
+                                        leaf_index,
+                                        action: event.action.to_string(),
+                                        owner: event.owner.to_string(),
+                                        counterparty: event.counterparty.map(|c| c.to_string()).unwrap_or_default(),
+                                    });
+                                }
+                                None => tracing::warn!(
+                                    "No leaf schema event for Bubblegum {} in {}, skipping asset row",
+                                    event.action, signature
+                                ),
+                            }
+                        }
+                        timings.event_extraction += stage_start.elapsed();
+                    }
 
                     // Note: transaction_payloads table removed to save storage space
                     // (was 1.32 GiB with no compression benefit, Debug strings aren't queryable)
@@ -138,30 +585,598 @@ pub async fn process_transaction(
                     // Note: If transaction has multiple instructions, some may succeed (transactions table)
                     // and some may fail (failed_transactions table), causing same signature in both tables
                     // This is intentional for instruction-level tracking
-                    let failed_tx = FailedTransaction {
-                        signature: signature.clone(),
-                        slot: tx.slot,
-                        block_time,
-                        program_id: program_id_str.clone(),
-                        protocol_name: parser_name.to_string(),
-                        raw_data,
-                        error_message: format!("{:?}", e),
-                        log_messages: log_messages_str.clone(),
-                    };
+                    let mut error_message = format!("{:?}", e);
+                    let error_class = ErrorClass::classify(&error_message);
 
-                    if let Err(e) = storage.insert_failed(failed_tx).await {
-                        tracing::error!("Failed to insert failed transaction: {:?}", e);
+                    // A recurring discriminator the IDL doesn't know usually means it needs updating
+                    if error_class == ErrorClass::DiscriminatorMismatch {
+                        if let Some(detection) =
+                            unknown_discriminators.record(&program_id_str, &instruction_update.data, tx.slot, &signature)
+                        {
+                            tracing::warn!(
+                                protocol = *parser_name,
+                                program_id = &*detection.program_id,
+                                discriminator = %detection.discriminator,
+                                failures = detection.failures,
+                                first_slot = detection.first_slot,
+                                "Unknown instruction discriminator, the IDL may need updating"
+                            );
+                            let row = UnknownDiscriminator {
+                                program_id: detection.program_id.to_string(),
+                                protocol_name: parser_name.to_string(),
+                                discriminator: detection.discriminator,
+                                failures: detection.failures,
+                                first_slot: detection.first_slot,
+                                first_signature: detection.first_signature,
+                                detected_at: 0,
+                            };
+                            if let Err(e) = storage.insert_unknown_discriminator(row).await {
+                                tracing::error!("Failed to record unknown discriminator: {:?}", e);
+                            }
+                        }
+                    }
+
+                    // Counted above either way; [failed_rows] caps and samples the stored rows
+                    if failed_rows.admit(parser_name, tx.signature.as_ref()) {
+                        let (data, data_truncated) = payload_limits.data(&ix.data);
+                        let error_truncated = payload_limits.text(&mut error_message);
+                        group.failed.push(FailedTransaction {
+                            signature: signature.clone(),
+                            instruction_index: position as u16,
+                            slot: tx.slot,
+                            block_time,
+                            program_id: program_id_str.clone(),
+                            protocol_name: interner.str(parser_name),
+                            protocol_id,
+                            raw_data: raw_data_encoding.encode(data),
+                            raw_data_encoding: interner.str(raw_data_encoding.as_str()),
+                            accounts: instruction_update.accounts.iter().map(|a| a.to_string()).collect(),
+                            fee,
+                            compute_units,
+                            error_class: error_class.as_str().to_string(),
+                            error_message,
+                            log_messages: log_messages_str.clone(),
+                            truncated: (data_truncated || error_truncated || log_messages_truncated) as u8,
+                        });
                     }
-                    
-                    _instruction_index += 1;
                 }
             }
         }
     }
 
+    // Fee tier, ticks and liquidity of CLMM swaps, from the events they logged
+    if !group.swaps.is_empty() {
+        fill_clmm_state(&mut group.swaps, &log_messages);
+    }
+
+    // Whole-token amounts, with decimals from this transaction's token balances or earlier ones
+    if !(group.swaps.is_empty() && group.trades.is_empty() && group.liquidity_events.is_empty()) {
+        let meta = &tx.transaction_status_meta;
+        decimals.observe(
+            meta.pre_token_balances
+                .iter()
+                .flatten()
+                .chain(meta.post_token_balances.iter().flatten())
+                .map(|balance| (balance.mint.as_str(), balance.ui_token_amount.decimals)),
+        );
+        fill_ui_amounts(&mut group, decimals);
+    }
+
+    // Invocation tree from the logs, for debugging the failures above
+    if *log_invocations && !group.failed.is_empty() {
+        group.log_invocations = parse_invocations(&log_messages)
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut invocation)| {
+                let logs_truncated = payload_limits.lines(&mut invocation.logs);
+                let data_truncated = payload_limits.base64_payloads(&mut invocation.data);
+                LogInvocation {
+                    signature: signature.clone(),
+                    slot: tx.slot,
+                    block_time,
+                    invocation_index: index as u16,
+                    parent_index: invocation.parent.map_or(-1, |parent| parent as i32),
+                    instruction_index: invocation.instruction_index,
+                    program_id: invocation.program_id,
+                    depth: invocation.depth,
+                    success: invocation.success as u8,
+                    error: invocation.error,
+                    compute_units: invocation.compute_units,
+                    compute_budget: invocation.compute_budget,
+                    logs: invocation.logs,
+                    data: invocation.data,
+                    return_data: invocation.return_data,
+                    truncated: (logs_truncated || data_truncated) as u8,
+                }
+            })
+            .collect();
+    }
+
+    // Token account lifecycle (top-level and CPI), for ATA churn and WSOL wrapping
+    let stage_start = Instant::now();
+    let lifecycle_filter = protocols.get(ACCOUNT_LIFECYCLE_PROTOCOL);
+    let top_level = instructions
+        .iter()
+        .map(|ix| (ix.program_id_index, ix.accounts.as_slice(), ix.data.as_slice(), 0u8));
+    let inner = tx
+        .transaction_status_meta
+        .inner_instructions
+        .iter()
+        .flatten()
+        .flat_map(|inner| &inner.instructions)
+        .map(|inner| {
+            let ix = &inner.instruction;
+            (ix.program_id_index, ix.accounts.as_slice(), ix.data.as_slice(), 1u8)
+        });
+    for (program_idx, account_idxs, data, is_inner) in top_level.chain(inner) {
+        let Some(program_id) = all_accounts.get(program_idx as usize) else { continue };
+        let Some(program) = lifecycle_programs.get(program_id.to_bytes().as_slice()) else { continue };
+        let accounts: Vec<_> = account_idxs
+            .iter()
+            .filter_map(|idx| all_accounts.get(*idx as usize))
+            .map(|a| a.to_bytes().into())
+            .collect();
+        if let Some(mint) = decode_initialize_mint(*program, data, &accounts) {
+            initialized_mints.insert(mint.to_string());
+        }
+        let Some(event) = decode_account_lifecycle(*program, data, &accounts) else { continue };
+        if lifecycle_filter.is_some_and(|f| !f.allows(event.action.as_str()) || !f.samples(tx.signature.as_ref())) {
+            continue;
+        }
+        let key = |k: Option<yellowstone_vixen_core::Pubkey>| k.map(|k| k.to_string()).unwrap_or_default();
+        group.lifecycle.push(AccountLifecycle {
+            signature: signature.clone(),
+            slot: tx.slot,
+            block_time,
+            action: event.action.as_str().to_string(),
+            // For ATA creates, record the token program the account belongs to
+            program_id: match event.token_program {
+                Some(token_program) => interner.str(&token_program.to_string()),
+                None => interner.address(program_id),
+            },
+            account: event.account.to_string(),
+            owner: key(event.owner),
+            mint: key(event.mint),
+            destination: key(event.destination),
+            is_inner,
+        });
+    }
+    // A launch's inner InitializeMint is the same mint, attributed to the launchpad
+    for mint in initialized_mints {
+        new_mints.record(block_time, launched_mints.remove(&mint).unwrap_or(SPL_TOKEN_MINT_SOURCE));
+    }
+    for source in launched_mints.into_values() {
+        new_mints.record(block_time, source);
+    }
+    if let (Some(first_seen), Some(first_row)) = (first_seen, group.transactions.first()) {
+        if let Some(wallet) = all_accounts.get(0).filter(|wallet| first_seen.observe(wallet, tx.slot)) {
+            let row = WalletFirstSeen {
+                wallet: address_to_string(wallet),
+                slot: tx.slot,
+                signature: signature.clone(),
+                block_time,
+                protocol_name: first_row.protocol_name.to_string(),
+            };
+            if let Err(e) = storage.insert_wallet_first_seen(row).await {
+                tracing::warn!("Failed to buffer wallet_first_seen row: {:?}", e);
+            }
+        }
+    }
+    if let Some(pools) = pools {
+        for row in group.swaps.iter().filter_map(|swap| pools.observe(swap)) {
+            if let Err(e) = storage.insert_pool(row).await {
+                tracing::warn!("Failed to buffer pools row: {:?}", e);
+            }
+        }
+    }
+    // The fee payer stands in for the user
+    let user = all_accounts.get(0).map(|a| a.to_bytes()).unwrap_or_default();
+    if let Some(summary) = hourly_summary {
+        for &protocol in &stored_protocols {
+            summary.record(block_time, protocol, fee, compute_units, &user);
+        }
+    }
+    if let Some(daily_users) = daily_users {
+        for &protocol in &stored_protocols {
+            daily_users.record(block_time, protocol, &user);
+        }
+    }
+    if let Some(cooccurrence) = cooccurrence {
+        cooccurrence.record(block_time, &stored_protocols);
+    }
+    timings.event_extraction += stage_start.elapsed();
+
+    if let Some(programs) = transfer_programs {
+        let stage_start = Instant::now();
+        push_transfer_edges(&mut group, &tx, &all_accounts, programs, &signature, block_time);
+        timings.event_extraction += stage_start.elapsed();
+    }
+
+    let stage_start = Instant::now();
+    if let Err(e) = storage.insert_group(group).await {
+        tracing::error!("Failed to insert rows for {}: {:?}", signature, e);
+    }
+    timings.buffer_enqueue += stage_start.elapsed();
+
+    stage_metrics.record(&timings);
     Ok(())
 }
 
+/// slot_time_oracle row for a produced block with a known time
+pub fn slot_time_row(block: &BlockData) -> Option<SlotTime> {
+    match block {
+        BlockData::Block {
+            slot,
+            block_time: Some(block_time),
+            block_height,
+            ..
+        } => Some(SlotTime {
+            slot: *slot,
+            block_time: (*block_time).max(0) as u64,
+            block_height: block_height.unwrap_or(0),
+        }),
+        _ => None,
+    }
+}
+
+/// slot_status row for a block (or skipped slot) reported by the firehose
+pub fn slot_status_row(block: &BlockData) -> SlotStatus {
+    match block {
+        BlockData::Block {
+            slot,
+            block_time,
+            block_height,
+            executed_transaction_count,
+            ..
+        } => SlotStatus {
+            slot: *slot,
+            status: "produced".to_string(),
+            block_time: block_time.map_or(0, |t| t.max(0) as u64),
+            block_height: block_height.unwrap_or(0),
+            executed_transaction_count: *executed_transaction_count,
+            recorded_at: 0,
+        },
+        BlockData::PossibleLeaderSkipped { slot } => SlotStatus {
+            slot: *slot,
+            status: "skipped".to_string(),
+            block_time: 0,
+            block_height: 0,
+            executed_transaction_count: 0,
+            recorded_at: 0,
+        },
+    }
+}
+
+/// `{"pool_state": "<address>", ...}` for transactions.named_accounts
+///
+/// Keys are the IDL's snake_case account names, so analysts can read roles
+/// with `JSONExtractString(named_accounts, 'pool_state')` instead of by position.
+pub fn named_accounts_json(parsed: &ParsedInstruction) -> String {
+    let accounts: BTreeMap<&str, String> = parsed
+        .named_accounts()
+        .into_iter()
+        .map(|(name, address)| (name, address.to_string()))
+        .collect();
+    serde_json::to_string(&accounts).unwrap_or_default()
+}
+
+/// trades/token_creators `launchpad`: the partner config on shared launchpads
+/// (Meteora DBC), otherwise the parser, which is the launchpad itself
+fn launchpad(parser_name: &str, config: Option<yellowstone_vixen_core::Pubkey>) -> String {
+    config.map_or_else(|| parser_name.to_string(), |config| config.to_string())
+}
+
+/// Derived rows that only depend on the decoded instruction (liquidity
+/// positions, order events, swaps, trades, pool liquidity events), shared
+/// with failed-row reprocessing
+pub fn push_instruction_events(
+    group: &mut WriteGroup,
+    parsed: &ParsedInstruction,
+    signature: &str,
+    slot: u64,
+    block_time: u64,
+    protocol_name: &str,
+) {
+    let key = |k: Option<yellowstone_vixen_core::Pubkey>| k.map(|k| k.to_string()).unwrap_or_default();
+
+    // LP position lifecycle for CLMM/DLMM protocols
+    if let Some(event) = position_event(parsed) {
+        group.positions.push(LiquidityPosition {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            action: event.action.as_str().to_string(),
+            position: event.position.to_string(),
+            position_mint: key(event.position_mint),
+            pool: key(event.pool),
+            vault_a: key(event.vault_a),
+            vault_b: key(event.vault_b),
+            owner: event.owner.to_string(),
+            tick_lower: event.tick_lower,
+            tick_upper: event.tick_upper,
+            liquidity: event.liquidity,
+            amount_a: event.amount_a,
+            amount_b: event.amount_b,
+        });
+    }
+
+    // Jupiter limit order and DCA lifecycle
+    if let Some(event) = order_event(parsed) {
+        group.orders.push(OrderEvent {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            action: event.action.as_str().to_string(),
+            order_account: event.order.to_string(),
+            maker: key(event.maker),
+            taker: key(event.taker),
+            input_mint: key(event.input_mint),
+            output_mint: key(event.output_mint),
+            in_amount: event.in_amount,
+            out_amount: event.out_amount,
+        });
+    }
+
+    if let Some(event) = swap_event(parsed) {
+        group.swaps.push(Swap {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            pool: key(event.pool),
+            user: event.user.to_string(),
+            mint_in: key(event.mint_in),
+            mint_out: key(event.mint_out),
+            amount_in: event.amount_in,
+            amount_out: event.amount_out,
+            amount_in_ui: None,
+            amount_out_ui: None,
+            exact_in: event.exact_in as u8,
+            fee_rate: None,
+            tick_before: None,
+            tick_after: None,
+            liquidity: None,
+        });
+    }
+
+    if let Some(event) = trade_event(parsed) {
+        group.trades.push(Trade {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            launchpad: launchpad(protocol_name, event.config),
+            venue: event.venue.as_str().to_string(),
+            side: event.side.as_str().to_string(),
+            mint: event.mint.to_string(),
+            bonding_curve: event.bonding_curve.to_string(),
+            user: event.user.to_string(),
+            token_amount: event.token_amount,
+            sol_amount: event.sol_amount,
+            token_amount_ui: None,
+            sol_amount_ui: None,
+            exact_tokens: event.exact_tokens as u8,
+        });
+    }
+
+    if let Some(event) = liquidity_event(parsed) {
+        group.liquidity_events.push(LiquidityEvent {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            action: event.action.as_str().to_string(),
+            pool: event.pool.to_string(),
+            user: event.user.to_string(),
+            mint_a: event.mint_a.to_string(),
+            mint_b: event.mint_b.to_string(),
+            lp_amount: event.lp_amount,
+            amount_a: event.amount_a,
+            amount_b: event.amount_b,
+            amount_a_ui: None,
+            amount_b_ui: None,
+        });
+    }
+
+    // Tensor and Magic Eden NFT sales
+    if let Some(event) = nft_trade_event(parsed) {
+        group.nft_trades.push(NftTrade {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            mint: event.mint.to_string(),
+            buyer: event.buyer.to_string(),
+            seller: event.seller.to_string(),
+            price: event.price,
+            exact_price: event.exact_price as u8,
+            marketplace_fee: event.marketplace_fee.unwrap_or(0),
+        });
+    }
+
+    // Squads and SPL Governance proposal lifecycle
+    if let Some(event) = governance_event(parsed) {
+        group.governance_events.push(GovernanceEvent {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            action: event.action.as_str().to_string(),
+            governance: event.governance.to_string(),
+            proposal: event.proposal.to_string(),
+            actor: key(event.actor),
+            vote: event.vote.map(|v| v.as_str().to_string()).unwrap_or_default(),
+        });
+    }
+
+    // Wormhole and deBridge cross-chain transfers
+    if let Some(event) = bridge_event(parsed) {
+        group.bridge_events.push(BridgeEvent {
+            signature: signature.to_string(),
+            slot,
+            block_time,
+            protocol_name: protocol_name.to_string(),
+            action: event.action.as_str().to_string(),
+            token: key(event.token),
+            amount: event.amount,
+            source_chain: event.source_chain,
+            target_chain: event.target_chain,
+            sender: key(event.sender),
+            recipient: event.recipient,
+        });
+    }
+}
+
+/// Match each CLMM swap event in the logs to the next swap row of its pool, in
+/// execution order (see `solixdb_decoder::clmm`)
+fn fill_clmm_state(swaps: &mut [Swap], log_messages: &[String]) {
+    let mut states: HashMap<String, VecDeque<ClmmSwapState>> = HashMap::new();
+    for line in log_messages {
+        let Some(data) = line.strip_prefix("Program data: ") else { continue };
+        let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data) else { continue };
+        if let Some(state) = clmm_swap_state(&bytes) {
+            states.entry(state.pool.to_string()).or_default().push_back(state);
+        }
+    }
+    if states.is_empty() {
+        return;
+    }
+    for swap in swaps {
+        let Some(state) = states.get_mut(&swap.pool).and_then(VecDeque::pop_front) else { continue };
+        swap.fee_rate = state.fee_rate;
+        swap.tick_before = state.tick_before;
+        swap.tick_after = state.tick_after;
+        swap.liquidity = state.liquidity;
+    }
+}
+
+/// `*_ui` amounts of the swaps, trades and liquidity_events rows
+pub fn fill_ui_amounts(group: &mut WriteGroup, decimals: &TokenDecimals) {
+    for swap in &mut group.swaps {
+        swap.amount_in_ui = decimals.ui_amount(&swap.mint_in, swap.amount_in);
+        swap.amount_out_ui = decimals.ui_amount(&swap.mint_out, swap.amount_out);
+    }
+    for trade in &mut group.trades {
+        trade.token_amount_ui = decimals.ui_amount(&trade.mint, trade.token_amount);
+        trade.sol_amount_ui = Some(ui_amount(trade.sol_amount, SOL_DECIMALS));
+    }
+    for event in &mut group.liquidity_events {
+        event.amount_a_ui = decimals.ui_amount(&event.mint_a, event.amount_a);
+        event.amount_b_ui = decimals.ui_amount(&event.mint_b, event.amount_b);
+    }
+}
+
+/// transfer_edges rows: decoded SOL/token transfers, then balance changes
+/// they don't explain, paired per mint
+fn push_transfer_edges(
+    group: &mut WriteGroup,
+    tx: &TransactionData,
+    all_accounts: &AccountKeys,
+    programs: &HashMap<Vec<u8>, TransferProgram>,
+    signature: &str,
+    block_time: u64,
+) {
+    let meta = &tx.transaction_status_meta;
+    let token_balances = || meta.pre_token_balances.iter().flatten().chain(meta.post_token_balances.iter().flatten());
+
+    // Token account -> (owner, mint); old balances have no owner, so fall back to the account
+    let mut token_accounts: HashMap<String, (String, String)> = HashMap::new();
+    for balance in token_balances() {
+        let Some(account) = all_accounts.get(balance.account_index as usize) else { continue };
+        let account = address_to_string(account);
+        let owner = if balance.owner.is_empty() { account.clone() } else { balance.owner.clone() };
+        token_accounts.insert(account, (owner, balance.mint.clone()));
+    }
+
+    // Net change per (wallet, mint) not explained by a decoded transfer; "" is SOL
+    let mut residual: HashMap<(String, String), i128> = HashMap::new();
+    for (i, (pre, post)) in meta.pre_balances.iter().zip(&meta.post_balances).enumerate() {
+        let Some(account) = all_accounts.get(i) else { continue };
+        let mut delta = *post as i128 - *pre as i128;
+        if i == 0 {
+            // The fee payer's fee goes to the validator, not to another account
+            delta += meta.fee as i128;
+        }
+        *residual.entry((address_to_string(account), String::new())).or_default() += delta;
+    }
+    for (balances, sign) in [(&meta.pre_token_balances, -1i128), (&meta.post_token_balances, 1)] {
+        for balance in balances.iter().flatten() {
+            let Some(account) = all_accounts.get(balance.account_index as usize) else { continue };
+            let Some((owner, mint)) = token_accounts.get(&address_to_string(account)) else { continue };
+            let amount: i128 = balance.ui_token_amount.amount.parse().unwrap_or(0);
+            *residual.entry((owner.clone(), mint.clone())).or_default() += sign * amount;
+        }
+    }
+
+    let instructions = match &tx.transaction.message {
+        VersionedMessage::Legacy(msg) => &msg.instructions,
+        VersionedMessage::V0(msg) => &msg.instructions,
+    };
+    let inner = meta.inner_instructions.iter().flatten().flat_map(|inner| &inner.instructions).map(|inner| &inner.instruction);
+    let calls = instructions
+        .iter()
+        .map(|ix| (ix.program_id_index, ix.accounts.as_slice(), ix.data.as_slice()))
+        .chain(inner.map(|ix| (ix.program_id_index, ix.accounts.as_slice(), ix.data.as_slice())));
+    let edge = |from: String, to: String, mint: String, amount: u64, source: &str| TransferEdge {
+        signature: signature.to_string(),
+        slot: tx.slot,
+        block_time,
+        from_account: from,
+        to_account: to,
+        mint,
+        amount,
+        source: source.to_string(),
+    };
+
+    for (program_idx, account_idxs, data) in calls {
+        let Some(program_id) = all_accounts.get(program_idx as usize) else { continue };
+        let Some(program) = programs.get(program_id.to_bytes().as_slice()) else { continue };
+        let accounts: Vec<_> = account_idxs
+            .iter()
+            .filter_map(|idx| all_accounts.get(*idx as usize))
+            .map(|a| a.to_bytes().into())
+            .collect();
+        let Some(transfer) = decode_transfer(*program, data, &accounts) else { continue };
+        let (source, destination) = (transfer.source.to_string(), transfer.destination.to_string());
+        let (from, to, mint) = match program {
+            TransferProgram::System => (source, destination, String::new()),
+            TransferProgram::Token => {
+                let from = token_accounts.get(&source);
+                let to = token_accounts.get(&destination);
+                let Some(mint) = transfer
+                    .mint
+                    .map(|m| m.to_string())
+                    .or_else(|| from.or(to).map(|(_, mint)| mint.clone()))
+                else {
+                    continue;
+                };
+                let owner = |entry: Option<&(String, String)>, account: String| entry.map_or(account, |(o, _)| o.clone());
+                (owner(from, source), owner(to, destination), mint)
+            }
+        };
+        if from == to || transfer.amount == 0 {
+            continue;
+        }
+        *residual.entry((from.clone(), mint.clone())).or_default() += transfer.amount as i128;
+        *residual.entry((to.clone(), mint.clone())).or_default() -= transfer.amount as i128;
+        group.transfer_edges.push(edge(from, to, mint, transfer.amount, "transfer"));
+    }
+
+    let mut by_mint: BTreeMap<String, Vec<(String, i128)>> = BTreeMap::new();
+    for ((account, mint), delta) in residual {
+        if delta != 0 {
+            by_mint.entry(mint).or_default().push((account, delta));
+        }
+    }
+    for (mint, deltas) in by_mint {
+        for (from, to, amount) in pair_deltas(&deltas) {
+            group.transfer_edges.push(edge(from, to, mint.clone(), amount, "balance_delta"));
+        }
+    }
+}
+
 pub fn print_summary(
     start_time: Instant,
     start_timestamp: SystemTime,
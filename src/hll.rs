@@ -0,0 +1,116 @@
+//! HyperLogLog distinct counter
+//!
+//! Estimates the number of distinct items in 2^PRECISION one-byte registers
+//! (4 KiB), with a standard error of about 1.6%. Items are hashed with
+//! FNV-1a and a 64-bit finalizer, both fixed, so estimates are reproducible
+//! across processes and runs.
+//!
+//! Sketches are mergeable: the union of two sets is the register-wise max of
+//! their sketches, so stored registers can be combined later (in ClickHouse,
+//! with `maxForEach`) to count distinct items over any span of sketches.
+
+const PRECISION: u32 = 12;
+const REGISTERS: usize = 1 << PRECISION;
+
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    registers: Box<[u8]>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTERS].into_boxed_slice(),
+        }
+    }
+
+    pub fn insert(&mut self, item: &[u8]) {
+        let hash = hash64(item);
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // Rank of the first set bit in the remaining bits (1-based), capped by their width
+        let rank = ((hash << PRECISION).leading_zeros() + 1).min(64 - PRECISION + 1) as u8;
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank);
+    }
+
+    /// Raw registers, for storage
+    pub fn registers(&self) -> &[u8] {
+        &self.registers
+    }
+
+    /// Estimated number of distinct items inserted
+    pub fn estimate(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|r| 2f64.powi(-(*r as i32))).sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        // Linear counting is more accurate while many registers are still empty
+        if raw <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+/// FNV-1a, then the splitmix64 finalizer to spread FNV's weak high bits
+fn hash64(item: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in item {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sketch(items: std::ops::Range<u64>) -> HyperLogLog {
+        let mut hll = HyperLogLog::new();
+        for item in items {
+            hll.insert(&item.to_le_bytes());
+        }
+        hll
+    }
+
+    #[test]
+    fn test_estimate_error() {
+        for count in [1_000u64, 100_000] {
+            let estimate = sketch(0..count).estimate() as f64;
+            let error = (estimate - count as f64).abs() / count as f64;
+            // About three standard errors
+            assert!(error < 0.05, "{} items estimated as {}", count, estimate);
+        }
+        assert_eq!(HyperLogLog::new().estimate(), 0);
+    }
+
+    #[test]
+    fn test_repeated_items_count_once() {
+        let mut hll = sketch(0..1_000);
+        for item in 0..1_000u64 {
+            hll.insert(&item.to_le_bytes());
+        }
+        assert_eq!(hll.registers(), sketch(0..1_000).registers());
+    }
+
+    #[test]
+    fn test_merge_equals_union() {
+        // Overlapping sets, merged register-wise as maxForEach does in ClickHouse
+        let (a, b) = (sketch(0..60_000), sketch(40_000..100_000));
+        let merged: Vec<u8> = a.registers().iter().zip(b.registers()).map(|(x, y)| *x.max(y)).collect();
+        assert_eq!(merged, sketch(0..100_000).registers());
+    }
+}
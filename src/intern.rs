@@ -0,0 +1,54 @@
+//! Shared strings for repeated row values
+//!
+//! Program ids, protocol/parser names and instruction types repeat on almost
+//! every row. Interning hands out clones of one `Arc<str>` per distinct value,
+//! and for addresses also skips the base58 encode after the first sighting.
+//! Each cache stops growing at `MAX_ENTRIES`; values past that are still
+//! returned, just not cached.
+
+use crate::alt::address_to_string;
+use solana_address::Address;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+const MAX_ENTRIES: usize = 1_000_000;
+
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: RwLock<HashSet<Arc<str>>>,
+    addresses: RwLock<HashMap<Address, Arc<str>>>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn str(&self, value: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.read().unwrap().get(value) {
+            return Arc::clone(interned);
+        }
+        let mut strings = self.strings.write().unwrap();
+        if let Some(interned) = strings.get(value) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(value);
+        if strings.len() < MAX_ENTRIES {
+            strings.insert(Arc::clone(&interned));
+        }
+        interned
+    }
+
+    /// Base58 form of an address
+    pub fn address(&self, address: &Address) -> Arc<str> {
+        if let Some(interned) = self.addresses.read().unwrap().get(address) {
+            return Arc::clone(interned);
+        }
+        let interned: Arc<str> = Arc::from(address_to_string(address));
+        let mut addresses = self.addresses.write().unwrap();
+        if addresses.len() < MAX_ENTRIES {
+            addresses.insert(*address, Arc::clone(&interned));
+        }
+        interned
+    }
+}
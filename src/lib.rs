@@ -0,0 +1,66 @@
+//! SolixDB indexer
+//!
+//! The binary (`src/main.rs`) wires these modules to the firehose; they are
+//! exposed as a library so integration tests can drive the parsing and storage
+//! path directly.
+
+pub mod account_snapshots;
+pub mod alt;
+pub mod autoscale;
+pub mod bandwidth;
+pub mod buffer_age;
+pub mod checkpoint;
+pub mod cli;
+pub mod config;
+pub mod cooccurrence;
+pub mod creators;
+pub mod cursor_export;
+pub mod daily_users;
+pub mod decimals;
+pub mod discriminators;
+pub mod endpoints;
+pub mod epoch;
+pub mod failed_rows;
+pub mod filters;
+pub mod firehose_errors;
+pub mod first_seen;
+pub mod fixtures;
+#[cfg(feature = "flight")]
+pub mod flight;
+pub mod heartbeat;
+pub mod helpers;
+pub mod hll;
+pub mod intern;
+pub mod log_invocations;
+pub mod logging;
+pub mod maintenance;
+#[cfg(feature = "test-util")]
+pub mod memory;
+pub mod mints;
+pub mod mirrors;
+pub mod multi_parser;
+pub mod network;
+pub mod parse_pool;
+pub mod payload_limits;
+pub mod pnl;
+pub mod pools;
+pub mod priority;
+pub mod raw_data;
+pub mod reindex;
+pub mod report;
+pub mod reprocess;
+pub mod rpc;
+pub mod scheduler;
+pub mod slot_time;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod stage_metrics;
+pub mod storage;
+pub mod summary;
+pub mod table_health;
+pub mod tvl;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod tx_error;
+pub mod warehouse;
+pub mod watermark;
@@ -0,0 +1,112 @@
+//! Program invocation tree from a transaction's log messages (log_invocations)
+//!
+//! The runtime logs every invocation as a bracketed block:
+//!
+//! ```text
+//! Program <id> invoke [1]
+//! Program log: Instruction: Route
+//! Program <cpi id> invoke [2]
+//! Program data: <base64>
+//! Program <cpi id> consumed 1200 of 180000 compute units
+//! Program <cpi id> success
+//! Program <id> consumed 24000 of 200000 compute units
+//! Program <id> failed: custom program error: 0x1771
+//! ```
+//!
+//! Each `invoke` opens an invocation nested under the one still open, and the
+//! matching `success`/`failed` line closes it. `Program log:`, `Program data:`
+//! and `Program return:` lines belong to the innermost open invocation. Logs
+//! cut off by the runtime's size limit (`Log truncated`) leave the open
+//! invocations unfinished: not successful, with `log truncated` as the error.
+
+/// Error of invocations still open when the log was truncated
+const TRUNCATED: &str = "log truncated";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Invocation {
+    pub program_id: String,
+    /// 1 for top-level instructions
+    pub depth: u8,
+    /// Index of the calling invocation (None for top-level instructions)
+    pub parent: Option<usize>,
+    /// Top-level instruction the invocation runs under
+    pub instruction_index: u16,
+    pub success: bool,
+    /// `failed:` message, empty on success
+    pub error: String,
+    pub compute_units: u64,
+    /// Compute units left to the invocation when it started
+    pub compute_budget: u64,
+    /// `Program log:` messages
+    pub logs: Vec<String>,
+    /// `Program data:` payloads (base64, usually Anchor events)
+    pub data: Vec<String>,
+    /// `Program return:` data (base64), empty if none
+    pub return_data: String,
+}
+
+/// Invocations in log order (parents before their children)
+pub fn parse_invocations(log_messages: &[String]) -> Vec<Invocation> {
+    let mut invocations: Vec<Invocation> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    let mut top_level = 0u16;
+
+    for line in log_messages {
+        if line == "Log truncated" {
+            break;
+        }
+        let Some(rest) = line.strip_prefix("Program ") else { continue };
+        let current = open.last().copied();
+        if let Some(message) = rest.strip_prefix("log: ") {
+            if let Some(index) = current {
+                invocations[index].logs.push(message.to_string());
+            }
+        } else if let Some(data) = rest.strip_prefix("data: ") {
+            if let Some(index) = current {
+                invocations[index].data.push(data.to_string());
+            }
+        } else if let Some(returned) = rest.strip_prefix("return: ") {
+            if let (Some(index), Some((_, data))) = (current, returned.split_once(' ')) {
+                invocations[index].return_data = data.to_string();
+            }
+        } else if let Some((program_id, event)) = rest.split_once(' ') {
+            if let Some(depth) = event.strip_prefix("invoke [").and_then(|d| d.strip_suffix(']')) {
+                let instruction_index = match current {
+                    Some(parent) => invocations[parent].instruction_index,
+                    None => {
+                        top_level += 1;
+                        top_level - 1
+                    }
+                };
+                open.push(invocations.len());
+                invocations.push(Invocation {
+                    program_id: program_id.to_string(),
+                    depth: depth.parse().unwrap_or(open.len() as u8),
+                    parent: current,
+                    instruction_index,
+                    ..Default::default()
+                });
+            } else if let Some(units) = event.strip_prefix("consumed ") {
+                // "<used> of <budget> compute units"
+                let mut numbers = units.split(' ').filter_map(|n| n.parse::<u64>().ok());
+                if let Some(index) = current {
+                    invocations[index].compute_units = numbers.next().unwrap_or(0);
+                    invocations[index].compute_budget = numbers.next().unwrap_or(0);
+                }
+            } else if event == "success" {
+                if let Some(index) = open.pop() {
+                    invocations[index].success = true;
+                }
+            } else if let Some(error) = event.strip_prefix("failed: ") {
+                if let Some(index) = open.pop() {
+                    invocations[index].error = error.to_string();
+                }
+            }
+        }
+    }
+
+    for index in open {
+        invocations[index].error = TRUNCATED.to_string();
+    }
+    invocations
+}
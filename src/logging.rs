@@ -0,0 +1,121 @@
+//! Log output (`[logging]`)
+//!
+//! Logs go to stdout as readable text (`pretty`, the default) or one JSON
+//! object per line (`json`) for log shippers, filtered by `level` (an
+//! `EnvFilter` directive such as `info` or `warn,solixdb_indexer=debug`).
+//! With `dir` set, the same lines are also written to a rolling file there,
+//! through a background writer so slow disks don't stall processing.
+
+use crate::config::LoggingConfig;
+use serde::{Deserialize, Serialize};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::RollingFileAppender;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "pretty" => Some(Self::Pretty),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// How often the log file rolls over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    #[default]
+    Daily,
+    Hourly,
+    Never,
+}
+
+impl LogRotation {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "daily" => Some(Self::Daily),
+            "hourly" => Some(Self::Hourly),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+}
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Install the global subscriber, with console lines on stderr instead of
+/// stdout when `stderr` is set (stdout carries data). The returned guard
+/// flushes the log file when dropped, so keep it alive until the process exits.
+pub fn init(config: &LoggingConfig, stderr: bool) -> Result<Option<WorkerGuard>, String> {
+    let filter = EnvFilter::try_new(&config.level)
+        .map_err(|e| format!("logging.level '{}' is not a valid filter: {}", config.level, e))?;
+
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+    // With the dashboard up, console lines are held back and warnings/errors shown in it instead
+    #[cfg(feature = "tui")]
+    {
+        let capture = crate::tui::log_capture();
+        let console = console_layer(config.format, stderr)
+            .with_filter(tracing_subscriber::filter::filter_fn(move |_| !capture.active()));
+        layers.push(console.boxed());
+        layers.push(crate::tui::log_capture().boxed());
+    }
+    #[cfg(not(feature = "tui"))]
+    layers.push(console_layer(config.format, stderr));
+
+    let guard = match &config.dir {
+        Some(dir) => {
+            let appender = match config.rotation {
+                LogRotation::Daily => tracing_appender::rolling::daily(dir, &config.file_prefix),
+                LogRotation::Hourly => tracing_appender::rolling::hourly(dir, &config.file_prefix),
+                LogRotation::Never => tracing_appender::rolling::never(dir, &config.file_prefix),
+            };
+            let (writer, guard) = tracing_appender::non_blocking::<RollingFileAppender>(appender);
+            layers.push(format_layer(config.format, writer, false));
+            Some(guard)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(layers.with_filter(filter))
+        .try_init()
+        .map_err(|e| format!("Failed to install the log subscriber: {}", e))?;
+    Ok(guard)
+}
+
+fn console_layer(format: LogFormat, stderr: bool) -> BoxedLayer {
+    if stderr {
+        format_layer(format, std::io::stderr, true)
+    } else {
+        format_layer(format, std::io::stdout, true)
+    }
+}
+
+fn format_layer<W>(format: LogFormat, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_thread_ids(false)
+        .with_level(true)
+        .with_ansi(ansi)
+        .with_writer(writer);
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        LogFormat::Json => layer.json().boxed(),
+    }
+}
@@ -1,61 +1,394 @@
-mod config;
-mod helpers;
-mod multi_parser;
-mod storage;
-
-use config::Config;
+use clap::Parser;
 use futures_util::FutureExt;
-use helpers::print_summary;
 use jetstreamer_firehose::firehose::*;
-use multi_parser::build_parser_map;
+use solixdb_decoder::registry::PROTOCOL_IDS;
+use solixdb_indexer::account_snapshots::AccountSnapshotter;
+use solixdb_indexer::alt::{self, AltCache, AltFetcher, AltInstruction};
+use solixdb_indexer::autoscale::Autoscaler;
+use solixdb_indexer::bandwidth::BandwidthLimiter;
+use solixdb_indexer::buffer_age;
+use solixdb_indexer::checkpoint;
+use solixdb_indexer::cli::Cli;
+use solixdb_indexer::config::Config;
+use solixdb_indexer::cooccurrence::Cooccurrence;
+use solixdb_indexer::creators::FirstBuyers;
+use solixdb_indexer::cursor_export::{Cursor, CursorExport};
+use solixdb_indexer::daily_users::DailyUsers;
+use solixdb_indexer::decimals::TokenDecimals;
+use solixdb_indexer::discriminators::UnknownDiscriminators;
+use solixdb_indexer::epoch::LeaderSchedule;
+use solixdb_indexer::failed_rows::FailedRowLimiter;
+use solixdb_indexer::filters::AccountFilter;
+use solixdb_indexer::firehose_errors::FirehoseErrors;
+use solixdb_indexer::first_seen::FirstSeen;
+use solixdb_indexer::fixtures::FixtureCapture;
+use solixdb_indexer::heartbeat::{default_instance_id, Heartbeat};
+use solixdb_indexer::helpers::{self, print_summary, ProcessingContext, ACCOUNT_LIFECYCLE_PROTOCOL};
+use solixdb_indexer::intern::Interner;
+use solixdb_indexer::maintenance::{self, MaintenanceTask};
+use solixdb_indexer::mints::MintCounter;
+use solixdb_indexer::mirrors::{self, ThroughputWatch};
+use solixdb_indexer::multi_parser::{build_parser_map, lifecycle_programs, transfer_programs};
+use solixdb_indexer::network::Network;
+use solixdb_indexer::parse_pool::ParsePool;
+use solixdb_indexer::payload_limits::PayloadLimits;
+use solixdb_indexer::pools::{PoolRegistry, PoolResolver};
+use solixdb_indexer::priority::ProtocolPriority;
+use solixdb_indexer::reindex;
+use solixdb_indexer::report::{self, RunReport};
+use solixdb_indexer::reprocess::reprocess_failed;
+use solixdb_indexer::rpc::RpcClient;
+use solixdb_indexer::scheduler::run_work_stealing;
+use solixdb_indexer::slot_time::SlotTimeOracle;
+use solixdb_indexer::stage_metrics::{write_textfile, StageMetrics};
+use solixdb_indexer::storage::{BackfillSettings, ClickHouseStorage, IndexedRange, RangeCheckpoint, StorageOptions};
+use solixdb_indexer::summary::HourlySummary;
+use solixdb_indexer::tvl::TvlSnapshotter;
+use solixdb_indexer::warehouse;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::{Instant, SystemTime};
-use storage::ClickHouseStorage;
 use tokio::signal;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_level(true)
-        .init();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    if cli.tui && !cfg!(feature = "tui") {
+        return Err("--tui requires building with `--features tui`".into());
+    }
+    if !(0.0..=1.0).contains(&cli.sample_rate) {
+        return Err(format!("--sample-rate must be between 0 and 1, got {}", cli.sample_rate).into());
+    }
+
+    // Load configuration (config file + env vars); logging is configured by it,
+    // so nothing is logged before this point
+    let mut config = Config::load()?;
+    let log_guard = solixdb_indexer::logging::init(&config.logging, cli.export_after.is_some() || cli.dump_schema)?;
 
-    // Load configuration (config file + env vars)
-    let config = Config::load()?;
+    // --reindex-into: write to tables under the new prefix, comparing against the configured ones after
+    let baseline_prefix = match &cli.reindex_into {
+        Some(prefix) if *prefix == config.clickhouse.table_prefix => {
+            return Err(format!("--reindex-into {} is the configured table_prefix", prefix).into());
+        }
+        Some(prefix) if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+            return Err(format!("--reindex-into may only contain letters, digits and '_', got '{}'", prefix).into());
+        }
+        Some(prefix) => Some(std::mem::replace(&mut config.clickhouse.table_prefix, prefix.clone())),
+        None => None,
+    };
     
     // Log loaded configuration
     tracing::info!("Loaded configuration:");
     tracing::info!("  Slots: {} to {}", config.slots.start, config.slots.end);
     tracing::info!("  ClickHouse URL: {}", config.clickhouse.url);
     tracing::info!("  Clear on start: {}", config.clickhouse.clear_on_start);
-    tracing::info!("  Threads: {}", config.processing.threads);
-    
+    tracing::info!("  Partition by: {:?}", config.clickhouse.partition_by);
+    if config.autoscale.enabled {
+        tracing::info!(
+            "  Threads: auto ({}..={}, starting at {}, re-evaluated every {} slots)",
+            config.autoscale.min_threads,
+            config.autoscale.max_threads,
+            config.processing.threads,
+            config.autoscale.chunk_slots
+        );
+    } else {
+        tracing::info!("  Threads: {}", config.processing.threads);
+    }
+    tracing::info!("  Timezone (date/hour): {}", config.processing.timezone);
+    if !config.filters.include_accounts.is_empty() || !config.filters.exclude_accounts.is_empty() {
+        tracing::info!(
+            "  Account filters: {} included, {} excluded",
+            config.filters.include_accounts.len(),
+            config.filters.exclude_accounts.len()
+        );
+    }
+    tracing::info!(
+        "  Archive: {} ({})",
+        config.firehose.compact_index_base_url,
+        config.firehose.network
+    );
+    if config.network.name != "solana" {
+        tracing::info!(
+            "  Network: {} ({} program id overrides)",
+            config.network.name,
+            config.network.program_ids.len()
+        );
+    }
+
+    // Pick the fastest reachable archive mirror before its URL is exported
+    if !config.firehose.mirrors.is_empty() && !cli.dump_schema {
+        let probes = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?
+            .block_on(mirrors::probe_all(&config.firehose.mirrors));
+        for probe in &probes {
+            match &probe.latency {
+                Ok(latency) => tracing::info!("  Mirror {}: {:?}", probe.url, latency),
+                Err(e) => tracing::warn!("  Mirror {} unreachable: {}", probe.url, e),
+            }
+        }
+        // After a stall the run resumes here, with the mirrors that stalled tried last
+        let stalled = mirrors::stalled_mirrors();
+        let fastest = mirrors::fastest(&probes, &stalled).ok_or("No [firehose] mirrors are reachable")?;
+        tracing::info!("  Archive mirror: {}", fastest.url);
+        config.firehose.compact_index_base_url = fastest.url.clone();
+    }
+
+    // jetstreamer reads its settings from the environment. Export them before the
+    // runtime starts, while this is the only thread that could be reading it.
+    for (key, value) in config.firehose.jetstreamer_env() {
+        std::env::set_var(key, value);
+    }
+
+    // A run stopped by min_mb_per_sec continues with --resume from another mirror
+    let configured_mirrors = config.firehose.mirrors.clone();
+    let stalled_mirror = Arc::new(OnceLock::new());
+    let result = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(cli, config, Arc::clone(&stalled_mirror)));
+    if let (Err(e), Some(mirror)) = (&result, stalled_mirror.get()) {
+        let mut stalled = mirrors::stalled_mirrors();
+        stalled.push(mirror.clone());
+        if !configured_mirrors.iter().any(|mirror| !stalled.contains(mirror)) {
+            tracing::error!("No [firehose] mirror is left that hasn't stalled during this run, not resuming");
+            return result;
+        }
+        tracing::warn!("{}; resuming with --resume on another mirror", e);
+        drop(log_guard);
+        return Err(format!("Failed to resume on another mirror: {}", mirrors::resume_elsewhere(&stalled)).into());
+    }
+    result
+}
+
+async fn run(cli: Cli, config: Config, stalled_mirror: Arc<OnceLock<String>>) -> Result<(), Box<dyn std::error::Error>> {
     let slot_start = config.slots.start;
     let slot_end = config.slots.end;
     let threads = config.processing.threads;
+    let network = Network::from_config(&config.network)?;
 
-    unsafe {
-        std::env::set_var("JETSTREAMER_NETWORK", "mainnet");
-        std::env::set_var("JETSTREAMER_COMPACT_INDEX_BASE_URL", "https://files.old-faithful.net");
-        std::env::set_var("JETSTREAMER_NETWORK_CAPACITY_MB", "100000");
+    // Export mode: serve existing tables without creating, clearing or indexing
+    if let Some(addr) = cli.serve_flight {
+        #[cfg(feature = "flight")]
+        {
+            return solixdb_indexer::flight::FlightExport::new(&config.clickhouse.url, &config.clickhouse.table_prefix)
+                .serve(addr)
+                .await
+                .map_err(|e| format!("{}", e).into());
+        }
+        #[cfg(not(feature = "flight"))]
+        {
+            return Err(format!(
+                "--serve-flight {} requires building with `--features flight`",
+                addr
+            )
+            .into());
+        }
     }
 
+    // Incremental export: rows go to stdout, nothing is created or cleared
+    if let Some(table) = &cli.export_after {
+        let after = match (&cli.cursor, &cli.cursor_file) {
+            (Some(cursor), _) => cursor.clone(),
+            (None, Some(path)) if path.exists() => std::fs::read_to_string(path)?.parse::<Cursor>()?,
+            (None, _) => Cursor::default(),
+        };
+        let export = CursorExport::new(&config.clickhouse.url, &config.clickhouse.table_prefix);
+        let next = export
+            .export(table, &after, cli.export_limit, &mut std::io::stdout().lock())
+            .await
+            .map_err(|e| format!("{}", e))?;
+        match next {
+            Some(next) => {
+                tracing::info!("Exported {} after {}; next cursor: {}", table, after, next);
+                if let Some(path) = &cli.cursor_file {
+                    std::fs::write(path, format!("{}\n", next))?;
+                }
+            }
+            None => tracing::info!("No {} rows after {}", table, after),
+        }
+        return Ok(());
+    }
+
+    let account_filter = AccountFilter::new(&config.filters)?;
+
     // Initialize ClickHouse storage
+    let storage_options = StorageOptions {
+        timezone: config.processing.timezone.clone(),
+        write_groups: config.clickhouse.write_groups,
+        partition_by: config.clickhouse.partition_by,
+        slot_bucket: config.clickhouse.partition_slot_bucket,
+        slots_per_epoch: network.slots_per_epoch,
+        legacy_protocol_events: config.clickhouse.legacy_protocol_events,
+        slot_status: config.slot_status.enabled,
+        cloud: config.clickhouse.cloud,
+        table_prefix: config.clickhouse.table_prefix.clone(),
+        strict_schema: config.clickhouse.strict_schema,
+        failover_urls: config.clickhouse.failover_urls.clone(),
+        primary_check_interval: std::time::Duration::from_secs(config.clickhouse.primary_check_secs),
+        clear_backup: config.clickhouse.clear_backup,
+        clear_max_rows: config.clickhouse.clear_max_rows,
+        commit_watermarks: config.clickhouse.commit_watermarks,
+        flush_buckets: config.clickhouse.flush_buckets,
+        backfill: config.backfill.enabled.then(|| BackfillSettings {
+            max_insert_block_size: config.backfill.max_insert_block_size,
+            min_insert_block_size_rows: config.backfill.min_insert_block_size_rows,
+            stop_merges: config.backfill.stop_merges,
+            optimize_final: config.backfill.optimize_final,
+        }),
+    };
+
+    // Schema dump: print what storage setup would run, without connecting
+    if cli.dump_schema {
+        let statements = ClickHouseStorage::dump_schema(storage_options).await.map_err(|e| format!("{}", e))?;
+        for statement in statements {
+            println!("{};\n", statement);
+        }
+        return Ok(());
+    }
+
     let storage = if config.clickhouse.clear_on_start {
+        if !(cli.yes_i_mean_it || config.clickhouse.clear_confirmed) {
+            return Err("[clickhouse] clear_on_start drops every indexer table; confirm with --yes-i-mean-it \
+                 or CLEAR_DB_CONFIRM=yes-i-mean-it"
+                .into());
+        }
         tracing::info!("Clearing database and recreating tables...");
-        Arc::new(ClickHouseStorage::new_with_clear(&config.clickhouse.url).await
+        Arc::new(ClickHouseStorage::new_with_clear(&config.clickhouse.url, storage_options).await
             .map_err(|e| format!("{}", e))?)
     } else {
-        Arc::new(ClickHouseStorage::new(&config.clickhouse.url).await
+        Arc::new(ClickHouseStorage::new(&config.clickhouse.url, storage_options).await
             .map_err(|e| format!("{}", e))?)
     };
 
+    // Real block times for slot -> block_time, preloaded from earlier runs over the range
+    let slot_times = if config.slot_time_oracle.enabled {
+        let oracle = Arc::new(SlotTimeOracle::new(network.clone()));
+        match oracle.preload(&storage, slot_start, slot_end).await {
+            Ok(anchors) => tracing::info!("Preloaded {} block time anchors from slot_time_oracle", anchors),
+            Err(e) => tracing::warn!("Failed to preload slot_time_oracle: {}", e),
+        }
+        Some(oracle)
+    } else {
+        None
+    };
+
+    if cli.check_consistency {
+        let reports = storage
+            .find_orphaned_rows(slot_start, slot_end)
+            .await
+            .map_err(|e| format!("{}", e))?;
+        if reports.is_empty() {
+            tracing::info!("No orphaned rows in slots {}..{}", slot_start, slot_end);
+            return Ok(());
+        }
+        for report in &reports {
+            tracing::warn!(
+                "{}: {} signatures without a transactions row (e.g. {})",
+                report.table,
+                report.signatures,
+                report.sample.join(", ")
+            );
+        }
+        return Err(format!(
+            "Found orphaned rows in {} table(s) for slots {}..{} (a flush was interrupted)",
+            reports.len(),
+            slot_start,
+            slot_end
+        )
+        .into());
+    }
+
+    if cli.wallet_sessions {
+        let sessions = storage
+            .build_wallet_sessions(slot_start, slot_end, config.wallet_sessions.gap_secs)
+            .await
+            .map_err(|e| format!("{}", e))?;
+        tracing::info!(
+            "Wrote {} wallet sessions for slots {}..{} ({}s inactivity gap)",
+            sessions,
+            slot_start,
+            slot_end,
+            config.wallet_sessions.gap_secs
+        );
+        return Ok(());
+    }
+
+    if cli.wallet_pnl {
+        let positions = storage
+            .build_wallet_pnl(slot_start, slot_end)
+            .await
+            .map_err(|e| format!("{}", e))?;
+        tracing::info!("Wrote {} wallet_pnl rows for slots {}..{}", positions, slot_start, slot_end);
+        return Ok(());
+    }
+
+    if let Some(warehouse) = cli.export_warehouse {
+        let (files, manifest) = warehouse::export(&storage, &config.export, warehouse, slot_start, slot_end)
+            .await
+            .map_err(|e| format!("{}", e))?;
+        tracing::info!(
+            "Exported {} tables ({} rows) for slots {}..{}; load script: {}",
+            files.len(),
+            files.iter().map(|f| f.rows).sum::<u64>(),
+            slot_start,
+            slot_end,
+            manifest.display()
+        );
+        return Ok(());
+    }
+
+    if cli.migrate_raw_data {
+        let encoding = config.processing.raw_data_encoding;
+        let migrated = storage
+            .migrate_raw_data(slot_start, slot_end, encoding)
+            .await
+            .map_err(|e| format!("{}", e))?;
+        tracing::info!(
+            "Rewrote raw_data of {} failed_transactions rows in slots {}..{} as {}",
+            migrated,
+            slot_start,
+            slot_end,
+            encoding.as_str()
+        );
+        return Ok(());
+    }
+
+    if let Some(protocol) = &cli.reprocess_failed {
+        let parser_map = network.parser_map(build_parser_map())?;
+        if !parser_map.values().any(|candidates| candidates.first() == Some(&protocol.as_str())) {
+            return Err(format!("--reprocess-failed: unknown protocol '{}'", protocol).into());
+        }
+        let summary = reprocess_failed(
+            &storage,
+            &parser_map,
+            &config.protocols,
+            protocol,
+            slot_start,
+            slot_end,
+            slot_times.as_deref(),
+        )
+        .await
+        .map_err(|e| format!("{}", e))?;
+        tracing::info!(
+            "Reprocessed {} failed {} rows in slots {}..{}: {} recovered, {} recovered but filtered, {} still failing",
+            summary.candidates,
+            protocol,
+            slot_start,
+            slot_end,
+            summary.recovered,
+            summary.filtered,
+            summary.still_failing
+        );
+        return Ok(());
+    }
+
     // Graceful shutdown signal handler
     let shutdown_flag = Arc::new(AtomicBool::new(false));
     let shutdown_flag_clone = Arc::clone(&shutdown_flag);
+    // Set on shutdown or at the end of the run, to hand the terminal back from --tui
+    let dashboard_stop = Arc::new(AtomicBool::new(false));
+    let dashboard_stop_clone = Arc::clone(&dashboard_stop);
     let storage_clone = Arc::clone(&storage);
     
     tokio::spawn(async move {
@@ -74,6 +407,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         
         shutdown_flag_clone.store(true, Ordering::Relaxed);
+        dashboard_stop_clone.store(true, Ordering::Relaxed);
         
         // Flush all pending data
         tracing::info!("Flushing all pending batches before shutdown...");
@@ -83,37 +417,432 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("Graceful shutdown complete");
     });
 
-    // Build parser map
-    let parser_map = build_parser_map();
+    // Build parser map (with the network's program ids)
+    let parser_map = network.parser_map(build_parser_map())?;
+    storage
+        .register_protocols(PROTOCOL_IDS)
+        .await
+        .map_err(|e| format!("Failed to write protocol_registry: {}", e))?;
+    storage
+        .register_programs(&parser_map)
+        .await
+        .map_err(|e| format!("Failed to write program_registry: {}", e))?;
     
     // Metrics per program - dynamically create based on parser map
     let mut metrics: HashMap<String, (Arc<AtomicU64>, Arc<AtomicU64>)> = HashMap::new();
-    for (_, parser_name) in &parser_map {
+    for parser_name in parser_map.values().flatten() {
         metrics.insert(
             parser_name.to_string(),
             (Arc::new(AtomicU64::new(0)), Arc::new(AtomicU64::new(0))),
         );
     }
 
+    // Duplicate-run guard: refuse to reindex a range already claimed with the same parsers
+    let mut parser_names: Vec<&str> = parser_map.values().flatten().copied().collect();
+    parser_names.sort_unstable();
+    parser_names.dedup();
+    let parser_set = parser_names.join(",");
+    let overlapping = storage
+        .find_overlapping_ranges(slot_start, slot_end, &parser_set)
+        .await
+        .map_err(|e| format!("{}", e))?;
+    if !overlapping.is_empty() && !cli.resume {
+        for range in &overlapping {
+            tracing::warn!(
+                "Slots {}..{} already claimed by run {} ({})",
+                range.slot_start, range.slot_end, range.run_id, range.status
+            );
+        }
+        if !cli.force {
+            return Err(format!(
+                "Slot range {}..{} overlaps {} previous run(s) with the same parser set; \
+                 rerun with --force to index it again (rows will be duplicated)",
+                slot_start,
+                slot_end,
+                overlapping.len()
+            )
+            .into());
+        }
+        tracing::warn!("--force given, indexing overlapping range anyway");
+    }
+    if cli.resume {
+        // Complete runs' checkpoints may have been deleted by [maintenance.vacuum_checkpoints]
+        for range in overlapping.iter().filter(|r| r.status == "complete") {
+            tracing::warn!(
+                "--resume over slots {}..{} of complete run {}: chunks without checkpoints are reindexed (rows will be duplicated)",
+                range.slot_start, range.slot_end, range.run_id
+            );
+        }
+    }
+
+    // The range is processed in chunks when autoscaling or checkpointing; a resume
+    // skips the chunks a previous run already checkpointed
+    if cli.resume && !config.checkpoint.enabled {
+        return Err("--resume requires [checkpoint] enabled (or CHECKPOINT=true)".into());
+    }
+    let completed_chunks = if cli.resume {
+        storage
+            .load_checkpoints(slot_start, slot_end, &parser_set)
+            .await
+            .map_err(|e| format!("{}", e))?
+    } else {
+        Vec::new()
+    };
+    let chunk_slots = if config.autoscale.enabled {
+        config.autoscale.chunk_slots
+    } else if config.checkpoint.enabled {
+        config.checkpoint.chunk_slots
+    } else {
+        slot_end - slot_start
+    };
+    let chunks = checkpoint::pending_chunks(slot_start..slot_end, chunk_slots, &completed_chunks);
+    if cli.resume {
+        let pending: u64 = chunks.iter().map(|c| c.end - c.start).sum();
+        tracing::info!(
+            "Resuming: {} of {} slots left in {} chunks",
+            pending,
+            slot_end - slot_start,
+            chunks.len()
+        );
+    }
+    if chunks.is_empty() {
+        tracing::info!("Slots {}..{} are already checkpointed, nothing to do", slot_start, slot_end);
+        return Ok(());
+    }
+
+    let unix_now = || {
+        SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+    };
+    let mut claim = IndexedRange {
+        run_id: format!("{}-{}", unix_now().as_nanos(), std::process::id()),
+        slot_start,
+        slot_end,
+        parser_set,
+        status: "running".to_string(),
+        started_at: unix_now().as_secs(),
+        updated_at: unix_now().as_secs(),
+    };
+    storage.record_range(&claim).await.map_err(|e| format!("{}", e))?;
+
+    let heartbeat = Heartbeat::new(
+        Arc::clone(&storage),
+        config.heartbeat.instance_id.clone().unwrap_or_else(default_instance_id),
+        claim.run_id.clone(),
+        slot_start,
+        slot_end,
+    );
+    if config.heartbeat.interval_secs > 0 {
+        tokio::spawn(heartbeat.clone().run(std::time::Duration::from_secs(config.heartbeat.interval_secs)));
+    }
+
+    for (task, interval) in maintenance::tasks(&config.maintenance) {
+        // Stopped merges would make every OPTIMIZE fail until the run ends
+        let merges_stopped = config.backfill.enabled && config.backfill.stop_merges;
+        if merges_stopped && matches!(task, MaintenanceTask::OptimizePartitions { .. }) {
+            tracing::info!("Maintenance: optimize_partitions skipped while backfill merges are stopped");
+            continue;
+        }
+        tracing::info!("Maintenance: {} every {}s", task.name(), interval.as_secs());
+        tokio::spawn(maintenance::run(Arc::clone(&storage), task, interval));
+    }
+
+    // Warn about [protocols.*] sections that don't match any parser
+    for name in config.protocols.keys() {
+        if !metrics.contains_key(name) && name != ACCOUNT_LIFECYCLE_PROTOCOL {
+            tracing::warn!("Unknown protocol in config: [protocols.{}] (no parser with that name)", name);
+        }
+    }
+
+    // Seed the lookup table resolver from previously indexed alt_updates
+    let alt_cache = AltCache::new();
+    match storage.load_alt_updates().await {
+        Ok(rows) => {
+            for (table, action, new_addresses) in rows {
+                let Some(table) = alt::parse_address(&table) else { continue };
+                let instruction = match action.as_str() {
+                    "create" => AltInstruction::CreateLookupTable { recent_slot: 0, bump_seed: 0 },
+                    "close" => AltInstruction::CloseLookupTable,
+                    _ => AltInstruction::ExtendLookupTable {
+                        new_addresses: new_addresses
+                            .iter()
+                            .filter_map(|a| alt::parse_address(a))
+                            .map(|a| a.to_bytes().into())
+                            .collect(),
+                    },
+                };
+                alt_cache.apply(table, &instruction);
+            }
+            tracing::info!("Loaded {} address lookup tables from alt_updates", alt_cache.len());
+        }
+        Err(e) => tracing::warn!("Failed to load ALT state from alt_updates: {}", e),
+    }
+
+    let rpc = config.rpc.url.as_deref().map(|url| Arc::new(RpcClient::new(url)));
+    let leader_schedule = match &rpc {
+        Some(rpc) if config.enrichment.leader_schedule => {
+            tracing::info!("Leader schedule enrichment enabled via {}", rpc.url());
+            Some(LeaderSchedule::new(Arc::clone(rpc), network.slots_per_epoch))
+        }
+        _ => None,
+    };
+    let alt_fetcher = match &rpc {
+        Some(rpc) if config.enrichment.lookup_tables => {
+            tracing::info!("Fetching unindexed lookup tables via {}", rpc.url());
+            Some(AltFetcher::new(Arc::clone(rpc)))
+        }
+        _ => None,
+    };
+
+    if let Some(rpc) = rpc.as_ref().filter(|_| config.tvl.enabled) {
+        tracing::info!("TVL snapshots enabled every {} slots", config.tvl.interval_slots);
+        let snapshotter = TvlSnapshotter::new(Arc::clone(rpc), Arc::clone(&storage), config.tvl.interval_slots);
+        tokio::spawn(snapshotter.run());
+    }
+
+    if let Some(rpc) = rpc.as_ref().filter(|_| config.account_snapshots.enabled) {
+        tracing::info!(
+            "Account snapshots of {} programs enabled every {} slots",
+            config.account_snapshots.programs.len(),
+            config.account_snapshots.interval_slots
+        );
+        let snapshotter = AccountSnapshotter::new(
+            Arc::clone(rpc),
+            Arc::clone(&storage),
+            config.account_snapshots.programs.clone(),
+            config.account_snapshots.interval_slots,
+        );
+        tokio::spawn(snapshotter.run());
+    }
+
+    let pools = config.pools.enabled.then(|| Arc::new(PoolRegistry::new(config.pools.resolve_via_rpc)));
+    if let (Some(rpc), Some(pools)) = (&rpc, pools.as_ref().filter(|_| config.pools.resolve_via_rpc)) {
+        tracing::info!("Resolving pool mints missing from swap instructions via {}", rpc.url());
+        let resolver = PoolResolver::new(Arc::clone(rpc), Arc::clone(&storage), Arc::clone(pools));
+        tokio::spawn(resolver.run());
+    }
+
+    let fixture_capture = match &cli.capture_fixtures {
+        Some(dir) => {
+            tracing::info!(
+                "Capturing parser fixtures to {} (sample rate {})",
+                dir.display(),
+                cli.sample_rate
+            );
+            Some(FixtureCapture::new(dir.clone(), cli.sample_rate).map_err(|e| format!("{}", e))?)
+        }
+        None => None,
+    };
+
+    let parse_pool = ParsePool::new(config.processing.parse_threads, config.processing.parse_queue)?;
+    tracing::info!(
+        "Parsing on {} threads (queue of {} instructions)",
+        parse_pool.threads(),
+        config.processing.parse_queue
+    );
+    let ctx = Arc::new(ProcessingContext {
+        network,
+        slot_times: slot_times.clone(),
+        parser_map,
+        lifecycle_programs: lifecycle_programs(),
+        transfer_programs: config.transfer_edges.enabled.then(transfer_programs),
+        metrics,
+        protocols: config.protocols.clone(),
+        protocol_priority: ProtocolPriority::new(
+            &config.processing.protocol_priority,
+            config.processing.count_once_per_tx,
+        ),
+        account_filter,
+        named_accounts: config.processing.named_accounts,
+        log_invocations: config.log_invocations.enabled,
+        raw_data_encoding: config.processing.raw_data_encoding,
+        interner: Interner::new(),
+        alt_cache,
+        alt_fetcher,
+        leader_schedule,
+        fixture_capture,
+        first_buyers: FirstBuyers::new(),
+        new_mints: MintCounter::new(),
+        decimals: TokenDecimals::new(),
+        hourly_summary: config.hourly_summary.enabled.then(|| HourlySummary::new(slot_start)),
+        daily_users: config.daily_users.enabled.then(|| DailyUsers::new(slot_start)),
+        cooccurrence: config.protocol_cooccurrence.enabled.then(|| Cooccurrence::new(slot_start)),
+        first_seen: config.wallet_first_seen.enabled.then(FirstSeen::new),
+        pools,
+        unknown_discriminators: UnknownDiscriminators::new(config.stale_idl.min_failures),
+        failed_rows: FailedRowLimiter::new(
+            config.failed_rows.max_rows,
+            config.failed_rows.max_rows_per_protocol,
+            config.failed_rows.sample_rate,
+        ),
+        payload_limits: PayloadLimits::new(
+            config.payload_limits.max_text_bytes,
+            config.payload_limits.max_data_bytes,
+        ),
+        parse_pool: Some(parse_pool),
+        stage_metrics: StageMetrics::new(),
+        storage: storage.clone(),
+    });
+
+    // Export stage histograms, the flushed watermark and per-table insert health for Prometheus
+    // (node_exporter textfile collector)
+    let render_metrics = |ctx: &ProcessingContext, storage: &ClickHouseStorage| {
+        ctx.stage_metrics.render_prometheus()
+            + &storage.watermark().render_prometheus()
+            + &storage.table_health().render_prometheus()
+            + &storage.endpoints().render_prometheus()
+            + &ctx.new_mints.render_prometheus()
+    };
+    if let Some(path) = config.metrics.textfile.clone() {
+        let ctx = Arc::clone(&ctx);
+        let storage = Arc::clone(&storage);
+        tracing::info!("Writing stage metrics to {}", path);
+        tokio::spawn(async move {
+            let path = std::path::PathBuf::from(path);
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+            loop {
+                interval.tick().await;
+                if let Err(e) = write_textfile(&path, &render_metrics(&ctx, &storage)) {
+                    tracing::warn!("Failed to write metrics textfile {}: {}", path.display(), e);
+                }
+            }
+        });
+    }
+
+    // SIGUSR1 logs the watermark, buffered rows and each table's insert health
+    {
+        let storage = Arc::clone(&storage);
+        tokio::spawn(async move {
+            let mut sigusr1 = signal::unix::signal(signal::unix::SignalKind::user_defined1())
+                .expect("Failed to register SIGUSR1 handler");
+            while sigusr1.recv().await.is_some() {
+                tracing::info!(
+                    "Status: watermark slot {}, {} rows buffered",
+                    storage.watermark().slot(),
+                    storage.buffered_rows().await
+                );
+                let endpoints = storage.endpoints();
+                tracing::info!(
+                    "  ClickHouse endpoint: {} ({} failovers)",
+                    endpoints.name(endpoints.active()),
+                    endpoints.failovers()
+                );
+                for line in storage.table_health().report() {
+                    tracing::info!("  {}", line);
+                }
+            }
+        });
+    }
+
+    // Buffers holding a row past buffer_age_alert_secs are flushed, and logged if it stays there
+    if config.clickhouse.buffer_age_alert_secs > 0 {
+        tokio::spawn(buffer_age::run(
+            Arc::clone(&storage),
+            std::time::Duration::from_secs(config.clickhouse.buffer_age_alert_secs),
+        ));
+    }
+
+    // New mint counts go to new_mints_hourly as deltas every minute (and at the end of the run),
+    // along with the hours of protocol_hourly_summary and days of protocol_daily_users and
+    // protocol_cooccurrence that changed. Mints past their first-buyer window are dropped then too.
+    {
+        let ctx = Arc::clone(&ctx);
+        let storage = Arc::clone(&storage);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                ctx.first_buyers.expire(storage.watermark().handed_off());
+                ctx.new_mints.flush(&*ctx.storage).await;
+                if let Some(summary) = &ctx.hourly_summary {
+                    summary.flush(&*ctx.storage).await;
+                }
+                if let Some(daily_users) = &ctx.daily_users {
+                    daily_users.flush(&*ctx.storage).await;
+                }
+                if let Some(cooccurrence) = &ctx.cooccurrence {
+                    cooccurrence.flush(&*ctx.storage).await;
+                }
+            }
+        });
+    }
+
+    // Every firehose error is recorded in firehose_errors; on_firehose_error decides when to give up
+    let firehose_errors = Arc::new(FirehoseErrors::new(
+        config.processing.on_firehose_error,
+        config.processing.firehose_error_retries,
+    ));
+
+    // [firehose] max_mb_per_sec pauses the handlers; running past max_total_mb stops the run
+    let bandwidth = BandwidthLimiter::new(config.firehose.max_mb_per_sec, config.firehose.max_total_mb);
+    if let Some(bandwidth) = &bandwidth {
+        let firehose_errors = Arc::clone(&firehose_errors);
+        tokio::spawn(Arc::clone(bandwidth).run(move |reason| firehose_errors.abort(reason)));
+    }
+    // ...and so does staying under min_mb_per_sec, after which main resumes on another mirror
+    let firehose_active = Arc::new(AtomicBool::new(false));
+    if let Some(watch) = ThroughputWatch::new(config.firehose.min_mb_per_sec, config.firehose.stall_secs) {
+        let firehose_errors = Arc::clone(&firehose_errors);
+        let mirror = config.firehose.compact_index_base_url.clone();
+        let on_stall = move |reason| {
+            // Only a stall that stops the run moves it to another mirror
+            if firehose_errors.aborted().is_none() {
+                let _ = stalled_mirror.set(mirror);
+            }
+            firehose_errors.abort(reason)
+        };
+        let (active, ctx) = (Arc::clone(&firehose_active), Arc::clone(&ctx));
+        tokio::spawn(watch.run(config.firehose.compact_index_base_url.clone(), active, ctx, on_stall));
+    }
+
     let transaction_handler = {
-        let parser_map = parser_map.clone();
-        let metrics = metrics.clone();
+        let ctx = Arc::clone(&ctx);
         let storage = Arc::clone(&storage);
+        let firehose_errors = Arc::clone(&firehose_errors);
+        let bandwidth = bandwidth.clone();
         
-        move |_thread_id: usize, tx: TransactionData| {
-            let parser_map = parser_map.clone();
-            let metrics = metrics.clone();
+        move |thread_id: usize, tx: TransactionData| {
+            let ctx = Arc::clone(&ctx);
             let storage = Arc::clone(&storage);
+            let firehose_errors = Arc::clone(&firehose_errors);
+            let bandwidth = bandwidth.clone();
             
             async move {
-                helpers::process_transaction(tx, &parser_map, &metrics, &storage).await
+                if let Some(bandwidth) = &bandwidth {
+                    bandwidth.throttle().await;
+                }
+                if let Some(reason) = firehose_errors.aborted() {
+                    return Err(reason.into());
+                }
+                storage.watermark().thread_at(thread_id, tx.slot);
+                helpers::process_transaction(tx, &ctx).await
             }
             .boxed()
         }
     };
 
-    let block_handler = move |_thread_id: usize, _block: BlockData| {
-        async move { Ok::<(), Box<dyn std::error::Error + Send + Sync>>(()) }.boxed()
+    let block_handler = {
+        let storage = Arc::clone(&storage);
+        let slot_status = config.slot_status.enabled;
+        let slot_times = slot_times.clone();
+
+        move |_thread_id: usize, block: BlockData| {
+            let storage = Arc::clone(&storage);
+            let slot_times = slot_times.clone();
+
+            async move {
+                if slot_status {
+                    storage.insert_slot_status(helpers::slot_status_row(&block)).await?;
+                }
+                if let (Some(slot_times), Some(row)) = (&slot_times, helpers::slot_time_row(&block)) {
+                    slot_times.record(row.slot, row.block_time);
+                    storage.insert_slot_time(row).await?;
+                }
+                Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+            }
+            .boxed()
+        }
     };
 
     let entry_handler = move |_thread_id: usize, _entry: EntryData| {
@@ -124,12 +853,34 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         async move { Ok::<(), Box<dyn std::error::Error + Send + Sync>>(()) }.boxed()
     };
 
-    let error_handler = move |_thread_id: usize, error_ctx: FirehoseErrorContext| {
-        async move {
-            eprintln!("Firehose error at slot {}: {}", error_ctx.slot, error_ctx.error_message);
-            Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+    let error_handler = {
+        let storage = Arc::clone(&storage);
+        let firehose_errors = Arc::clone(&firehose_errors);
+
+        move |thread_id: usize, error_ctx: FirehoseErrorContext| {
+            let storage = Arc::clone(&storage);
+            let firehose_errors = Arc::clone(&firehose_errors);
+
+            async move {
+                let row = firehose_errors.record(thread_id, error_ctx.slot, &error_ctx.error_message);
+                tracing::warn!(
+                    "Firehose error at slot {} ({}, attempt {}, {}): {}",
+                    row.slot,
+                    row.error_class,
+                    row.attempt,
+                    row.action,
+                    row.error_message
+                );
+                if let Err(e) = storage.insert_firehose_error(row).await {
+                    tracing::warn!("Failed to record firehose error: {:?}", e);
+                }
+                match firehose_errors.aborted() {
+                    Some(reason) => Err(reason.into()),
+                    None => Ok::<(), Box<dyn std::error::Error + Send + Sync>>(()),
+                }
+            }
+            .boxed()
         }
-        .boxed()
     };
 
     let stats_handler = move |_thread_id: usize, _stats: Stats| {
@@ -139,21 +890,151 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let start_time = Instant::now();
     let start_timestamp = std::time::SystemTime::now();
 
-    let firehose_result = firehose(
-        threads as u64,
-        slot_start..slot_end,
-        Some(block_handler),
-        Some(transaction_handler),
-        Some(entry_handler),
-        Some(rewards_handler),
-        Some(error_handler),
-        Some(StatsTracking {
-            on_stats: stats_handler,
-            tracking_interval_slots: 1000,
-        }),
-        None,
-    )
-    .await;
+    let run_range = |threads: usize, range: std::ops::Range<u64>| {
+        storage.watermark().begin_range(range.start, threads);
+        let range_end = range.end;
+        let storage = Arc::clone(&storage);
+        let run = if config.scheduler.work_stealing {
+            // One single-threaded firehose call per chunk; the worker id stands in for the thread id
+            run_work_stealing(range, threads, config.scheduler.chunk_slots, |worker, chunk| {
+                let handler = transaction_handler.clone();
+                firehose(
+                    1,
+                    chunk,
+                    Some(block_handler.clone()),
+                    Some(move |_thread_id: usize, tx: TransactionData| handler(worker, tx)),
+                    Some(entry_handler.clone()),
+                    Some(rewards_handler.clone()),
+                    Some(error_handler.clone()),
+                    Some(StatsTracking {
+                        on_stats: stats_handler.clone(),
+                        tracking_interval_slots: 1000,
+                    }),
+                    None,
+                )
+            })
+            .boxed_local()
+        } else {
+            firehose(
+                threads as u64,
+                range,
+                Some(block_handler.clone()),
+                Some(transaction_handler.clone()),
+                Some(entry_handler.clone()),
+                Some(rewards_handler.clone()),
+                Some(error_handler.clone()),
+                Some(StatsTracking {
+                    on_stats: stats_handler.clone(),
+                    tracking_interval_slots: 1000,
+                }),
+                None,
+            )
+            .boxed_local()
+        };
+        async move {
+            let result = run.await;
+            if result.is_ok() {
+                storage.watermark().end_range(range_end);
+            }
+            result
+        }
+    };
+
+    storage.begin_backfill().await.map_err(|e| format!("{}", e))?;
+
+    #[cfg(feature = "tui")]
+    let dashboard = cli.tui.then(|| {
+        let dashboard = solixdb_indexer::tui::Dashboard::new(Arc::clone(&ctx), Arc::clone(&storage), slot_start, slot_end);
+        tokio::spawn(dashboard.run(Arc::clone(&dashboard_stop)))
+    });
+
+    // Thread count is fixed per firehose call, so autoscaling rescales between chunks
+    let mut scaler = config.autoscale.enabled.then(|| Autoscaler::new(&config.autoscale, threads));
+    let chunk_count = chunks.len();
+    let mut chunks = chunks.into_iter().peekable();
+    let (firehose_result, completed) = loop {
+        let chunk = chunks.next().expect("at least one pending chunk");
+        let threads = scaler.as_ref().map_or(threads, Autoscaler::threads);
+        if chunk_count > 1 || scaler.is_some() {
+            tracing::info!("Processing slots {}..{} with {} threads", chunk.start, chunk.end, threads);
+        }
+        let sample = scaler.as_ref().map(|scaler| scaler.begin(&ctx.stage_metrics));
+        firehose_active.store(true, Ordering::Relaxed);
+        let result = run_range(threads, chunk.clone()).await;
+        firehose_active.store(false, Ordering::Relaxed);
+        let aborted = firehose_errors.aborted().is_some();
+        if result.is_ok() && !aborted && config.checkpoint.enabled {
+            // Only checkpoint once the chunk's rows are written
+            let checkpoint = RangeCheckpoint {
+                parser_set: claim.parser_set.clone(),
+                slot_start: chunk.start,
+                slot_end: chunk.end,
+                run_id: claim.run_id.clone(),
+                completed_at: 0,
+            };
+            match storage.flush_all().await {
+                Ok(()) => {
+                    if let Err(e) = storage.record_checkpoint(checkpoint).await {
+                        tracing::warn!("Failed to checkpoint slots {}..{}: {:?}", chunk.start, chunk.end, e);
+                    }
+                }
+                Err(e) => tracing::warn!("Not checkpointing slots {}..{}, flush failed: {:?}", chunk.start, chunk.end, e),
+            }
+        }
+        let Some(next) = chunks.peek() else {
+            break (result, true);
+        };
+        if result.is_err() || aborted {
+            break (result, false);
+        }
+        if shutdown_flag.load(Ordering::Relaxed) {
+            tracing::info!("Shutdown requested, stopping before slot {}", next.start);
+            break (result, false);
+        }
+        if let (Some(scaler), Some(sample)) = (scaler.as_mut(), sample) {
+            scaler.end(sample, &ctx.stage_metrics);
+        }
+    };
+
+    dashboard_stop.store(true, Ordering::Relaxed);
+    #[cfg(feature = "tui")]
+    if let Some(dashboard) = dashboard {
+        match dashboard.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!("Dashboard failed: {}", e),
+            Err(e) => tracing::warn!("Dashboard task failed: {}", e),
+        }
+    }
+
+    let aborted = firehose_errors.aborted();
+    claim.status = if firehose_result.is_ok() && completed && aborted.is_none() { "complete" } else { "failed" }.to_string();
+    claim.updated_at = unix_now().as_secs();
+    if let Err(e) = storage.record_range(&claim).await {
+        tracing::error!("Failed to update indexed_ranges claim {}: {:?}", claim.run_id, e);
+    }
+    if config.heartbeat.interval_secs > 0 {
+        if let Err(e) = heartbeat.beat(&claim.status).await {
+            tracing::warn!("Failed to write final heartbeat: {:?}", e);
+        }
+    }
+
+    if let Some(bandwidth) = &bandwidth {
+        tracing::info!("{}", bandwidth.summary());
+    }
+
+    // on_firehose_error gave up (or the download budget ran out): fail the run even if the
+    // firehose call itself returned
+    if let (Ok(_), Some(reason)) = (&firehose_result, aborted) {
+        tracing::info!("Flushing pending batches before exit...");
+        if let Err(e) = storage.flush_all().await {
+            tracing::error!("Failed to flush batches on abort: {:?}", e);
+        }
+        if let Err(e) = storage.end_backfill(false).await {
+            tracing::error!("Failed to restart merges: {:?}", e);
+        }
+        tracing::info!("Data is complete below slot {}", storage.watermark().slot());
+        return Err(format!("Aborted on {}", reason).into());
+    }
 
     match firehose_result {
         Ok(_) => {
@@ -165,6 +1046,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if let Err(e) = storage.flush_all().await {
                 tracing::error!("Failed to flush batches: {:?}", e);
             }
+            ctx.new_mints.flush(storage.as_ref()).await;
+            if let Some(summary) = &ctx.hourly_summary {
+                summary.flush(storage.as_ref()).await;
+            }
+            if let Some(daily_users) = &ctx.daily_users {
+                daily_users.flush(storage.as_ref()).await;
+            }
+            if let Some(cooccurrence) = &ctx.cooccurrence {
+                cooccurrence.flush(storage.as_ref()).await;
+            }
+            // --optimize-written merges the run's partitions itself, after measuring them
+            if let Err(e) = storage.end_backfill(completed && !cli.optimize_written).await {
+                tracing::error!("Failed to finish backfill: {:?}", e);
+            }
 
             print_summary(
                 start_time,
@@ -173,14 +1068,91 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 end_timestamp,
                 slot_start,
                 slot_end,
-                &metrics,
+                &ctx.metrics,
                 threads,
     );
+            ctx.failed_rows.print_summary();
+            ctx.stage_metrics.print_summary();
+            if let Some(path) = &config.metrics.textfile {
+                if let Err(e) = write_textfile(std::path::Path::new(path), &render_metrics(&ctx, &storage)) {
+                    tracing::warn!("Failed to write metrics textfile {}: {}", path, e);
+                }
+            }
 
             // Print storage stats
-            if let Err(e) = storage.get_storage_stats().await {
+            let storage_sizes = storage.get_storage_stats().await.unwrap_or_else(|e| {
                 tracing::error!("Failed to get storage stats: {:?}", e);
-    }
+                Vec::new()
+            });
+
+            let optimize = if cli.optimize_written && completed {
+                match report::optimize_written(&storage, slot_start, slot_end).await {
+                    Ok(optimized) => {
+                        optimized.print();
+                        Some(optimized)
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to optimize written partitions: {:?}", e);
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            let reindex = match &baseline_prefix {
+                Some(baseline_prefix) if completed => {
+                    match reindex::compare(&storage, baseline_prefix, slot_start, slot_end).await {
+                        Ok(reindex) => {
+                            reindex.print();
+                            Some(reindex)
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to compare reindexed tables: {:?}", e);
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            };
+
+            println!("\n=== Validation ===");
+            let problems = match storage.validate_run(slot_start, slot_end).await {
+                Ok(problems) => problems,
+                Err(e) => vec![format!("validation queries failed: {}", e)],
+            };
+            if problems.is_empty() {
+                println!("OK: row counts match, all rows in range, no duplicate instructions");
+            }
+            for problem in &problems {
+                println!("MISMATCH {}", problem);
+            }
+
+            if let Some(path) = &cli.report_json {
+                let run_report = RunReport {
+                    slot_start,
+                    slot_end,
+                    seconds: end_time.duration_since(start_time).as_secs_f64(),
+                    protocols: ctx
+                        .metrics
+                        .iter()
+                        .map(|(name, (parsed, failed))| {
+                            (name.clone(), (parsed.load(Ordering::Relaxed), failed.load(Ordering::Relaxed)))
+                        })
+                        .collect(),
+                    storage: storage_sizes,
+                    optimize,
+                    reindex,
+                    validation_problems: problems.clone(),
+                };
+                match run_report.write(path) {
+                    Ok(()) => tracing::info!("Wrote run report to {}", path.display()),
+                    Err(e) => tracing::warn!("Failed to write run report {}: {}", path.display(), e),
+                }
+            }
+            if cli.strict && !problems.is_empty() {
+                return Err(format!("{} validation check(s) failed (--strict)", problems.len()).into());
+            }
 
             Ok(())
         }
@@ -191,6 +1163,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if let Err(flush_err) = storage.flush_all().await {
                 tracing::error!("Failed to flush batches on error: {:?}", flush_err);
             }
+            if let Err(backfill_err) = storage.end_backfill(false).await {
+                tracing::error!("Failed to restart merges: {:?}", backfill_err);
+            }
+            tracing::info!("Data is complete below slot {}", storage.watermark().slot());
             Err(format!("Error at slot {}: {:?}", slot, e).into())
         }
     }
@@ -0,0 +1,94 @@
+//! Periodic self-maintenance tasks (`[maintenance]`)
+//!
+//! A long-running indexer leaves housekeeping behind that ClickHouse doesn't
+//! schedule for it: partitions that accumulate small parts faster than
+//! background merges keep up, and resume checkpoints nobody will use again.
+//! Each enabled task runs on its own interval for the life of the process. A
+//! failed run is logged and tried again at the next interval.
+
+use crate::config::MaintenanceConfig;
+use crate::storage::ClickHouseStorage;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceTask {
+    /// OPTIMIZE every partition of the per-transaction tables with more than `max_parts` active parts
+    OptimizePartitions { max_parts: u64 },
+    /// Delete range_checkpoints rows of complete runs older than `retention_secs`
+    VacuumCheckpoints { retention_secs: u64 },
+}
+
+impl MaintenanceTask {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OptimizePartitions { .. } => "optimize_partitions",
+            Self::VacuumCheckpoints { .. } => "vacuum_checkpoints",
+        }
+    }
+
+    /// Run the task once
+    pub async fn run_once(&self, storage: &ClickHouseStorage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match *self {
+            Self::OptimizePartitions { max_parts } => {
+                for (table, partition_id, parts) in storage.fragmented_partitions(max_parts).await? {
+                    let started = std::time::Instant::now();
+                    storage.optimize_partition(&table, &partition_id, false).await?;
+                    tracing::info!(
+                        "Maintenance: optimized {} partition {} ({} parts) in {:.1}s",
+                        table,
+                        partition_id,
+                        parts,
+                        started.elapsed().as_secs_f64()
+                    );
+                }
+            }
+            Self::VacuumCheckpoints { retention_secs } => {
+                let now = SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                storage.vacuum_checkpoints(now.saturating_sub(retention_secs)).await?;
+                tracing::info!("Maintenance: vacuumed range_checkpoints");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tasks enabled in `config`, with their intervals
+pub fn tasks(config: &MaintenanceConfig) -> Vec<(MaintenanceTask, Duration)> {
+    let optimize = &config.optimize_partitions;
+    let vacuum = &config.vacuum_checkpoints;
+    [
+        (
+            MaintenanceTask::OptimizePartitions {
+                max_parts: optimize.max_parts,
+            },
+            optimize.interval_secs,
+        ),
+        (
+            MaintenanceTask::VacuumCheckpoints {
+                retention_secs: vacuum.retention_days.saturating_mul(SECONDS_PER_DAY),
+            },
+            vacuum.interval_secs,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, interval_secs)| *interval_secs > 0)
+    .map(|(task, interval_secs)| (task, Duration::from_secs(interval_secs)))
+    .collect()
+}
+
+/// Run `task` every `interval`, first after one interval, until the task is dropped
+pub async fn run(storage: Arc<ClickHouseStorage>, task: MaintenanceTask, interval: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = task.run_once(&storage).await {
+            tracing::warn!("Maintenance task {} failed: {:?}", task.name(), e);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! In-memory storage for tests (`test-util` feature)
+//!
+//! `MemoryStorage` implements [`Storage`] by appending rows to in-memory
+//! tables, so `process_transaction` can be driven without ClickHouse and the
+//! produced rows asserted on directly:
+//!
+//! ```ignore
+//! let storage = Arc::new(MemoryStorage::new());
+//! let ctx = processing_context(storage.clone());
+//! process_transaction(tx, &ctx).await?;
+//! assert_eq!(storage.transactions()[0].instruction_type.as_ref(), "Sell");
+//! ```
+//!
+//! Rows are stored exactly as handed to storage: nothing is stamped with
+//! insert times and `protocol_events` is only filled if the group carried it.
+
+use crate::storage::{
+    BridgeEvent, FailedTransaction, GovernanceEvent, LiquidityEvent, LogInvocation, NewMintsHourly,
+    NftTrade, Pool, ProtocolCooccurrence, ProtocolDailyUsers, ProtocolHourlySummary, Storage, Swap, Trade, Transaction,
+    UnknownDiscriminator, WalletFirstSeen, WriteGroup,
+};
+use async_trait::async_trait;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug, Default)]
+struct Tables {
+    groups: WriteGroup,
+    /// (protocol_id, instruction_type_id, name)
+    instruction_types: Vec<(u16, u32, String)>,
+    unknown_discriminators: Vec<UnknownDiscriminator>,
+    wallet_first_seen: Vec<WalletFirstSeen>,
+    pools: Vec<Pool>,
+    new_mints: Vec<NewMintsHourly>,
+    hourly_summary: Vec<ProtocolHourlySummary>,
+    daily_users: Vec<ProtocolDailyUsers>,
+    cooccurrence: Vec<ProtocolCooccurrence>,
+    flushes: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    tables: Mutex<Tables>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `f` over every per-transaction row inserted so far
+    pub fn with_rows<R>(&self, f: impl FnOnce(&WriteGroup) -> R) -> R {
+        f(&self.tables().groups)
+    }
+
+    /// Rows inserted into a per-transaction table, by ClickHouse table name
+    pub fn row_count(&self, table: &str) -> usize {
+        self.with_rows(|rows| {
+            rows.table_counts()
+                .iter()
+                .find(|(name, _)| *name == table)
+                .map_or(0, |(_, count)| *count)
+        })
+    }
+
+    pub fn transactions(&self) -> Vec<Transaction> {
+        self.with_rows(|rows| rows.transactions.clone())
+    }
+
+    /// transactions rows for one signature, in instruction order
+    pub fn transactions_for(&self, signature: &str) -> Vec<Transaction> {
+        let mut rows: Vec<_> = self
+            .transactions()
+            .into_iter()
+            .filter(|row| row.signature == signature)
+            .collect();
+        rows.sort_by_key(|row| row.instruction_index);
+        rows
+    }
+
+    pub fn failed(&self) -> Vec<FailedTransaction> {
+        self.with_rows(|rows| rows.failed.clone())
+    }
+
+    pub fn swaps(&self) -> Vec<Swap> {
+        self.with_rows(|rows| rows.swaps.clone())
+    }
+
+    pub fn trades(&self) -> Vec<Trade> {
+        self.with_rows(|rows| rows.trades.clone())
+    }
+
+    pub fn liquidity_events(&self) -> Vec<LiquidityEvent> {
+        self.with_rows(|rows| rows.liquidity_events.clone())
+    }
+
+    pub fn nft_trades(&self) -> Vec<NftTrade> {
+        self.with_rows(|rows| rows.nft_trades.clone())
+    }
+
+    pub fn governance_events(&self) -> Vec<GovernanceEvent> {
+        self.with_rows(|rows| rows.governance_events.clone())
+    }
+
+    pub fn bridge_events(&self) -> Vec<BridgeEvent> {
+        self.with_rows(|rows| rows.bridge_events.clone())
+    }
+
+    pub fn log_invocations(&self) -> Vec<LogInvocation> {
+        self.with_rows(|rows| rows.log_invocations.clone())
+    }
+
+    /// Registered instruction types as (protocol_id, instruction_type_id, name)
+    pub fn instruction_types(&self) -> Vec<(u16, u32, String)> {
+        self.tables().instruction_types.clone()
+    }
+
+    pub fn unknown_discriminators(&self) -> Vec<UnknownDiscriminator> {
+        self.tables().unknown_discriminators.clone()
+    }
+
+    pub fn wallet_first_seen(&self) -> Vec<WalletFirstSeen> {
+        self.tables().wallet_first_seen.clone()
+    }
+
+    pub fn pools(&self) -> Vec<Pool> {
+        self.tables().pools.clone()
+    }
+
+    pub fn new_mints(&self) -> Vec<NewMintsHourly> {
+        self.tables().new_mints.clone()
+    }
+
+    pub fn hourly_summary(&self) -> Vec<ProtocolHourlySummary> {
+        self.tables().hourly_summary.clone()
+    }
+
+    pub fn daily_users(&self) -> Vec<ProtocolDailyUsers> {
+        self.tables().daily_users.clone()
+    }
+
+    pub fn cooccurrence(&self) -> Vec<ProtocolCooccurrence> {
+        self.tables().cooccurrence.clone()
+    }
+
+    /// Number of `flush_all` calls
+    pub fn flushes(&self) -> usize {
+        self.tables().flushes
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn insert_group(&self, group: WriteGroup) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().groups.extend(group);
+        Ok(())
+    }
+
+    async fn register_instruction_type(
+        &self,
+        protocol_id: u16,
+        instruction_type_id: u32,
+        name: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tables = self.tables();
+        let entry = (protocol_id, instruction_type_id, name.to_string());
+        if !tables.instruction_types.contains(&entry) {
+            tables.instruction_types.push(entry);
+        }
+        Ok(())
+    }
+
+    async fn insert_unknown_discriminator(&self, row: UnknownDiscriminator) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().unknown_discriminators.push(row);
+        Ok(())
+    }
+
+    async fn insert_wallet_first_seen(&self, row: WalletFirstSeen) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().wallet_first_seen.push(row);
+        Ok(())
+    }
+
+    async fn insert_pool(&self, row: Pool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().pools.push(row);
+        Ok(())
+    }
+
+    async fn insert_new_mints(&self, rows: &[NewMintsHourly]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().new_mints.extend_from_slice(rows);
+        Ok(())
+    }
+
+    async fn insert_hourly_summary(&self, rows: &[ProtocolHourlySummary]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().hourly_summary.extend_from_slice(rows);
+        Ok(())
+    }
+
+    async fn insert_daily_users(&self, rows: &[ProtocolDailyUsers]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().daily_users.extend_from_slice(rows);
+        Ok(())
+    }
+
+    async fn insert_cooccurrence(&self, rows: &[ProtocolCooccurrence]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().cooccurrence.extend_from_slice(rows);
+        Ok(())
+    }
+
+    async fn flush_all(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tables().flushes += 1;
+        Ok(())
+    }
+}
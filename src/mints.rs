@@ -0,0 +1,85 @@
+//! New token mint rate (new_mints_hourly)
+//!
+//! Counts mints initialized per hour of block time, by source: the launchpad
+//! whose create instruction launched the mint (`pump_fun`), else `spl_token`
+//! for a plain `InitializeMint`. A mint can only be initialized once, so
+//! counting is per transaction (a pump.fun create and its inner
+//! `InitializeMint` are one mint) and needs no global set. Counts are drained
+//! to new_mints_hourly as deltas, which SummingMergeTree adds up, and the
+//! running totals are exported as a Prometheus counter.
+
+use crate::storage::{NewMintsHourly, Storage};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Mutex;
+
+const SECONDS_PER_HOUR: u64 = 3_600;
+
+#[derive(Debug, Default)]
+pub struct MintCounter {
+    /// Counts not yet written, by (hour start, source)
+    pending: Mutex<HashMap<(u64, String), u64>>,
+    /// Running totals by source, for metrics
+    totals: Mutex<HashMap<&'static str, u64>>,
+}
+
+impl MintCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A mint was initialized at `block_time`
+    pub fn record(&self, block_time: u64, source: &'static str) {
+        let hour = block_time - block_time % SECONDS_PER_HOUR;
+        *self.pending.lock().unwrap().entry((hour, source.to_string())).or_default() += 1;
+        *self.totals.lock().unwrap().entry(source).or_default() += 1;
+    }
+
+    /// Take the counts recorded since the last drain
+    pub fn drain(&self) -> Vec<NewMintsHourly> {
+        let mut pending = self.pending.lock().unwrap();
+        pending
+            .drain()
+            .map(|((hour, source), new_mints)| NewMintsHourly {
+                hour,
+                source,
+                new_mints,
+            })
+            .collect()
+    }
+
+    /// Put back counts whose write failed
+    fn restore(&self, rows: Vec<NewMintsHourly>) {
+        let mut pending = self.pending.lock().unwrap();
+        for row in rows {
+            *pending.entry((row.hour, row.source)).or_default() += row.new_mints;
+        }
+    }
+
+    /// Write pending counts to new_mints_hourly, keeping them for the next
+    /// flush if the insert fails
+    pub async fn flush(&self, storage: &dyn Storage) {
+        let rows = self.drain();
+        if rows.is_empty() {
+            return;
+        }
+        if let Err(e) = storage.insert_new_mints(&rows).await {
+            tracing::warn!("Failed to write new_mints_hourly: {:?}", e);
+            self.restore(rows);
+        }
+    }
+
+    /// Render in Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP solixdb_new_mints_total Token mints initialized, by source\n");
+        out.push_str("# TYPE solixdb_new_mints_total counter\n");
+        let totals = self.totals.lock().unwrap();
+        let mut sources: Vec<_> = totals.iter().collect();
+        sources.sort();
+        for (source, count) in sources {
+            let _ = writeln!(out, "solixdb_new_mints_total{{source=\"{}\"}} {}", source, count);
+        }
+        out
+    }
+}
@@ -0,0 +1,279 @@
+//! Archive mirror selection and throughput watch (`[firehose] mirrors`, `min_mb_per_sec`)
+//!
+//! With several Old Faithful mirrors configured (regional copies of the
+//! archive, say), each is probed at startup with a few `HEAD` requests, and
+//! the reachable one with the lowest latency becomes the run's
+//! `compact_index_base_url`. Local copies (`file://`) win outright.
+//!
+//! jetstreamer takes the archive URL from the environment, which a running
+//! process can't safely change, so a run doesn't switch mirrors in place.
+//! Instead, with `min_mb_per_sec` set, a run whose download rate stays under
+//! it for `stall_secs` stops the way an exhausted download budget does, then
+//! replaces itself with a `--resume` run of the same command line
+//! ([`resume_elsewhere`]) that probes the mirrors again, trying the ones that
+//! stalled last. Like the bandwidth limits, the rate is measured on the
+//! host's network interfaces (Linux only), and only while a firehose call is
+//! running and not waiting on storage.
+
+use crate::autoscale::{stage_time, STORAGE_BOUND};
+use crate::bandwidth::received_bytes;
+use crate::helpers::ProcessingContext;
+use futures_util::future::join_all;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// `HEAD` requests per mirror; the fastest counts, so connection setup doesn't decide
+const PROBES: usize = 3;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Mirrors that stalled earlier in this run, comma-separated, passed on to the `--resume` run
+pub const STALLED_MIRRORS_ENV: &str = "SOLIXDB_STALLED_MIRRORS";
+
+#[derive(Debug, Clone)]
+pub struct MirrorProbe {
+    pub url: String,
+    /// Best round trip, or why the mirror is unreachable
+    pub latency: Result<Duration, String>,
+}
+
+/// Probe every mirror concurrently
+pub async fn probe_all(urls: &[String]) -> Vec<MirrorProbe> {
+    let http = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    join_all(urls.iter().map(|url| {
+        let http = http.clone();
+        async move {
+            MirrorProbe {
+                url: url.clone(),
+                latency: probe(&http, url).await,
+            }
+        }
+    }))
+    .await
+}
+
+async fn probe(http: &reqwest::Client, url: &str) -> Result<Duration, String> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return match std::path::Path::new(path).is_dir() {
+            true => Ok(Duration::ZERO),
+            false => Err(format!("{} is not a directory", path)),
+        };
+    }
+    let mut best: Option<Duration> = None;
+    let mut last_error = String::new();
+    for _ in 0..PROBES {
+        let start = Instant::now();
+        match http.head(format!("{}/", url)).send().await {
+            // Any answer short of a server error means the mirror is up
+            Ok(response) if !response.status().is_server_error() => {
+                let elapsed = start.elapsed();
+                best = Some(best.map_or(elapsed, |best| best.min(elapsed)));
+            }
+            Ok(response) => last_error = format!("HTTP {}", response.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    best.ok_or(last_error)
+}
+
+/// The reachable mirror with the lowest latency (the first listed on ties),
+/// preferring any that isn't in `stalled`
+pub fn fastest<'a>(probes: &'a [MirrorProbe], stalled: &[String]) -> Option<&'a MirrorProbe> {
+    probes
+        .iter()
+        .filter(|probe| probe.latency.is_ok())
+        .min_by_key(|probe| (stalled.contains(&probe.url), probe.latency.clone().unwrap_or(Duration::MAX)))
+}
+
+/// Mirrors that stalled earlier in this run (see [`STALLED_MIRRORS_ENV`])
+pub fn stalled_mirrors() -> Vec<String> {
+    std::env::var(STALLED_MIRRORS_ENV)
+        .map(|val| val.split(',').filter(|s| !s.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Replace this process with a `--resume` run of the same command line that
+/// probes the mirrors again, trying `stalled` last. Only returns on failure.
+#[cfg(unix)]
+pub fn resume_elsewhere(stalled: &[String]) -> std::io::Error {
+    use std::os::unix::process::CommandExt;
+
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return e,
+    };
+    let mut args: Vec<std::ffi::OsString> = std::env::args_os().skip(1).collect();
+    if !args.iter().any(|arg| arg == "--resume") {
+        args.push("--resume".into());
+    }
+    std::process::Command::new(exe)
+        .args(args)
+        .env(STALLED_MIRRORS_ENV, stalled.join(","))
+        .exec()
+}
+
+#[cfg(not(unix))]
+pub fn resume_elsewhere(_stalled: &[String]) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Unsupported, "automatic mirror failover needs a Unix host")
+}
+
+#[derive(Debug)]
+pub struct ThroughputWatch {
+    /// Bytes per second
+    min_rate: f64,
+    stall: Duration,
+}
+
+impl ThroughputWatch {
+    /// None unless a minimum is set and `/proc/net/dev` is readable
+    pub fn new(min_mb_per_sec: u64, stall_secs: u64) -> Option<Self> {
+        if min_mb_per_sec == 0 {
+            return None;
+        }
+        if received_bytes().is_none() {
+            tracing::warn!("/proc/net/dev is not readable; firehose min_mb_per_sec is ignored");
+            return None;
+        }
+        Some(Self {
+            min_rate: min_mb_per_sec as f64 * MB,
+            stall: Duration::from_secs(stall_secs.max(1)),
+        })
+    }
+
+    /// Sample the interfaces until the task is dropped, calling `on_stall`
+    /// once the rate over the last `stall_secs` is under the minimum
+    ///
+    /// Only time inside a firehose call (`active` set) counts, and a sample
+    /// interval the pipeline spent mostly in buffer enqueue restarts the
+    /// window: ClickHouse backpressure slows the downloads too, which is no
+    /// reason to leave the mirror.
+    pub async fn run(
+        self,
+        mirror: String,
+        active: Arc<AtomicBool>,
+        ctx: Arc<ProcessingContext>,
+        on_stall: impl FnOnce(String),
+    ) {
+        // (bytes, start) of the current window
+        let mut window: Option<(u64, Instant)> = None;
+        let mut last_stage_time = stage_time(&ctx.stage_metrics);
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            let stage_now = stage_time(&ctx.stage_metrics);
+            let total = stage_now.0.saturating_sub(last_stage_time.0);
+            let enqueue = stage_now.1.saturating_sub(last_stage_time.1);
+            last_stage_time = stage_now;
+            let Some(now) = received_bytes() else { continue };
+            let storage_bound = !total.is_zero() && enqueue.as_secs_f64() / total.as_secs_f64() > STORAGE_BOUND;
+            if !active.load(Ordering::Relaxed) || storage_bound {
+                window = None;
+                continue;
+            }
+            let Some((window_bytes, window_start)) = window else {
+                window = Some((now, Instant::now()));
+                continue;
+            };
+            let elapsed = window_start.elapsed();
+            if elapsed < self.stall {
+                continue;
+            }
+            let rate = now.saturating_sub(window_bytes) as f64 / elapsed.as_secs_f64();
+            if rate < self.min_rate {
+                on_stall(format!(
+                    "archive throughput from {} collapsed to {:.2} MB/s over {}s (minimum {:.0} MB/s)",
+                    mirror,
+                    rate / MB,
+                    elapsed.as_secs(),
+                    self.min_rate / MB
+                ));
+                return;
+            }
+            window = Some((now, Instant::now()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    fn reachable(url: &str, millis: u64) -> MirrorProbe {
+        MirrorProbe {
+            url: url.to_string(),
+            latency: Ok(Duration::from_millis(millis)),
+        }
+    }
+
+    fn unreachable(url: &str) -> MirrorProbe {
+        MirrorProbe {
+            url: url.to_string(),
+            latency: Err("connection refused".to_string()),
+        }
+    }
+
+    /// Serve `PROBES` requests with `status` on a local port, closing each connection
+    fn serve(status: &'static str) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(PROBES) {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_fastest_prefers_low_latency_then_listing_order() {
+        let probes = [unreachable("a"), reachable("b", 40), reachable("c", 20), reachable("d", 20)];
+        assert_eq!(fastest(&probes, &[]).unwrap().url, "c");
+        assert!(fastest(&[unreachable("a")], &[]).is_none());
+    }
+
+    #[test]
+    fn test_fastest_tries_stalled_mirrors_last() {
+        let probes = [reachable("a", 10), reachable("b", 80), unreachable("c")];
+        assert_eq!(fastest(&probes, &["a".to_string()]).unwrap().url, "b");
+        // A stalled mirror still beats none
+        assert_eq!(fastest(&probes, &["a".to_string(), "b".to_string()]).unwrap().url, "a");
+    }
+
+    #[tokio::test]
+    async fn test_probe_local_directories() {
+        let http = reqwest::Client::new();
+        let dir = std::env::temp_dir();
+        assert_eq!(probe(&http, &format!("file://{}", dir.display())).await, Ok(Duration::ZERO));
+        let missing = dir.join("solixdb-no-such-mirror");
+        assert!(probe(&http, &format!("file://{}", missing.display())).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_probe_http_mirrors() {
+        let http = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build().unwrap();
+        // Any answer short of a server error counts, a 404 for the bare path included
+        assert!(probe(&http, &serve("404 Not Found")).await.is_ok());
+        let error = probe(&http, &serve("503 Service Unavailable")).await.unwrap_err();
+        assert!(error.contains("503"), "{}", error);
+
+        // Nothing listens on a port just released
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        assert!(probe(&http, &format!("http://127.0.0.1:{}", port)).await.is_err());
+    }
+}
@@ -1,130 +1,109 @@
 use solana_address::Address;
 use solana_message::VersionedMessage;
-use std::collections::HashMap;
-use yellowstone_vixen_core::instruction::InstructionUpdate;
-use yellowstone_vixen_core::Parser;
-use yellowstone_vixen_proc_macro::include_vixen_parser;
 
-include_vixen_parser!("idls/jupiter_v6.json");
-include_vixen_parser!("idls/jupiter_v4.json");
-include_vixen_parser!("idls/pumpfun_swaps.json");
-include_vixen_parser!("idls/pump_fun.json");
-include_vixen_parser!("idls/raydium_amm_v3.json");
-include_vixen_parser!("idls/raydium_cpmm.json");
-include_vixen_parser!("idls/orca_whirlpool.json");
+// Instruction decoding lives in the solixdb-decoder crate (no storage deps, WASM-friendly)
+pub use solixdb_decoder::lifecycle::lifecycle_programs;
+pub use solixdb_decoder::transfers::transfer_programs;
+pub use solixdb_decoder::{try_parse, try_parse_candidates, ErrorClass, ParsedInstruction, ParserMap};
 
-pub fn build_full_account_list(
-    message: &VersionedMessage,
-    loaded_writable: &[Address],
-    loaded_readonly: &[Address],
-) -> Vec<Address> {
-    let mut all_accounts = Vec::new();
-    match message {
-        VersionedMessage::Legacy(msg) => {
-            all_accounts.extend(msg.account_keys.clone());
-        }
-        VersionedMessage::V0(msg) => {
-            all_accounts.extend(msg.account_keys.clone());
-            all_accounts.extend(loaded_writable.iter().cloned());
-            all_accounts.extend(loaded_readonly.iter().cloned());
+/// A transaction's full account list: static keys, then loaded writable and
+/// loaded readonly addresses, borrowed from the message and meta
+///
+/// Indexes match instruction account indexes. Nothing is copied, so building
+/// one per transaction costs no allocation.
+#[derive(Debug, Clone, Copy)]
+pub struct AccountKeys<'a> {
+    static_keys: &'a [Address],
+    loaded_writable: &'a [Address],
+    loaded_readonly: &'a [Address],
+}
+
+impl<'a> AccountKeys<'a> {
+    pub fn get(&self, index: usize) -> Option<&'a Address> {
+        let index = match index.checked_sub(self.static_keys.len()) {
+            None => return self.static_keys.get(index),
+            Some(index) => index,
+        };
+        match index.checked_sub(self.loaded_writable.len()) {
+            None => self.loaded_writable.get(index),
+            Some(index) => self.loaded_readonly.get(index),
         }
     }
-    all_accounts
+
+    pub fn len(&self) -> usize {
+        self.static_keys.len() + self.loaded_writable.len() + self.loaded_readonly.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a Address> {
+        self.static_keys
+            .iter()
+            .chain(self.loaded_writable)
+            .chain(self.loaded_readonly)
+    }
 }
 
-pub async fn try_parse(
-    update: &InstructionUpdate,
-    parser_name: &str,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    match parser_name {
-        "jupiter_v6" => {
-            jupiter_v6::InstructionParser.parse(update).await
-                .map(|inst| format!("{:?}", inst))
-                .map_err(|e| format!("{:?}", e).into())
-        }
-        "jupiter_v4" => {
-            jupiter_v4::InstructionParser.parse(update).await
-                .map(|inst| format!("{:?}", inst))
-                .map_err(|e| format!("{:?}", e).into())
-        }
-        "pump_amm" => {
-            pump_amm::InstructionParser.parse(update).await
-                .map(|inst| format!("{:?}", inst))
-                .map_err(|e| format!("{:?}", e).into())
-        }
-        "pump_fun" => {
-            pump_fun::InstructionParser.parse(update).await
-                .map(|inst| format!("{:?}", inst))
-                .map_err(|e| format!("{:?}", e).into())
-        }
-        "raydium_amm_v3" => {
-            amm_v3::InstructionParser.parse(update).await
-                .map(|inst| format!("{:?}", inst))
-                .map_err(|e| format!("{:?}", e).into())
-        }
-        "raydium_cp_swap" => {
-            raydium_cp_swap::InstructionParser.parse(update).await
-                .map(|inst| format!("{:?}", inst))
-                .map_err(|e| format!("{:?}", e).into())
-        }
-        "whirlpool" => {
-            whirlpool::InstructionParser.parse(update).await
-                .map(|inst| format!("{:?}", inst))
-                .map_err(|e| format!("{:?}", e).into())
-        }
-        _ => Err(format!("Unknown parser: {}", parser_name).into()),
+/// Full account list of a transaction; loaded addresses only apply to V0 messages
+pub fn build_full_account_list<'a>(
+    message: &'a VersionedMessage,
+    loaded_writable: &'a [Address],
+    loaded_readonly: &'a [Address],
+) -> AccountKeys<'a> {
+    match message {
+        VersionedMessage::Legacy(msg) => AccountKeys {
+            static_keys: &msg.account_keys,
+            loaded_writable: &[],
+            loaded_readonly: &[],
+        },
+        VersionedMessage::V0(msg) => AccountKeys {
+            static_keys: &msg.account_keys,
+            loaded_writable,
+            loaded_readonly,
+        },
     }
 }
 
-/// Extract instruction type name from parsed instruction string
-/// Format: "InstructionName { ... }" -> "InstructionName"
-pub fn extract_instruction_type(parsed: &str) -> String {
-    parsed
-        .split('{')
-        .next()
-        .unwrap_or(parsed)
-        .trim()
-        .to_string()
+/// Signer and writable flags for one entry of the full account list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountRole {
+    pub signer: bool,
+    pub writable: bool,
+}
+
+/// Account roles for every index of [`build_full_account_list`], from the message header
+///
+/// Writability is as requested by the message; the runtime may still demote
+/// program ids and reserved accounts to read-only.
+pub fn account_roles(
+    message: &VersionedMessage,
+    loaded_writable: usize,
+    loaded_readonly: usize,
+) -> Vec<AccountRole> {
+    let (header, static_len) = match message {
+        VersionedMessage::Legacy(msg) => (&msg.header, msg.account_keys.len()),
+        VersionedMessage::V0(msg) => (&msg.header, msg.account_keys.len()),
+    };
+    let signers = header.num_required_signatures as usize;
+    let writable_signers = signers.saturating_sub(header.num_readonly_signed_accounts as usize);
+    let writable_unsigned_end = static_len.saturating_sub(header.num_readonly_unsigned_accounts as usize);
+
+    let mut roles: Vec<AccountRole> = (0..static_len)
+        .map(|i| AccountRole {
+            signer: i < signers,
+            writable: if i < signers { i < writable_signers } else { i < writable_unsigned_end },
+        })
+        .collect();
+    if matches!(message, VersionedMessage::V0(_)) {
+        let writable = AccountRole { signer: false, writable: true };
+        roles.resize(static_len + loaded_writable, writable);
+        roles.resize(static_len + loaded_writable + loaded_readonly, AccountRole::default());
+    }
+    roles
 }
 
-pub fn build_parser_map() -> HashMap<Vec<u8>, &'static str> {
-    let mut map = HashMap::new();
-    
-    // 1. Jupiter v6
-    map.insert(
-        bs58::decode("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").into_vec().unwrap(),
-        "jupiter_v6",
-    );
-    // 2. Jupiter v4
-    map.insert(
-        bs58::decode("JUP4Fb2cqiRUcaTHdrPC8h2gNsA2ETXiPDD33WcGuJB").into_vec().unwrap(),
-        "jupiter_v4",
-    );
-    // 3. Pump Amm
-    map.insert(
-        bs58::decode("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA").into_vec().unwrap(),
-        "pump_amm",
-    );
-    // 4. Pump fun
-    map.insert(
-        bs58::decode("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P").into_vec().unwrap(),
-        "pump_fun",
-    );
-    // 5. Raydium AMM V3
-    map.insert(
-        bs58::decode("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK").into_vec().unwrap(),
-        "raydium_amm_v3",
-    );
-    // 6. Raydium CP Swap
-    map.insert(
-        bs58::decode("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C").into_vec().unwrap(),
-        "raydium_cp_swap",
-    );
-    // 7. Whirlpool
-    map.insert(
-        bs58::decode("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc").into_vec().unwrap(),
-        "whirlpool",
-    );
-    
-    map
+pub fn build_parser_map() -> ParserMap {
+    solixdb_decoder::parser_map()
 }
@@ -0,0 +1,114 @@
+//! Network-specific constants (`[network]`)
+//!
+//! Everything that ties the pipeline to Solana mainnet lives here: the genesis
+//! timestamp and slot duration block times are estimated from, the epoch
+//! length, and the program ids each parser is registered under. Other SVM chains (Eclipse, SOON, ...)
+//! run the same programs, often redeployed at different addresses, so they are
+//! indexed with the same parsers and tables by overriding those.
+//!
+//! The data source is configured separately in `[firehose]`: point
+//! `compact_index_base_url` at an Old Faithful-format archive of the chain.
+
+use crate::config::NetworkConfig;
+use crate::epoch::SLOTS_PER_EPOCH;
+use solixdb_decoder::ParserMap;
+
+/// Solana mainnet genesis (2020-09-23 00:00:00 UTC)
+const SOLANA_GENESIS_TIMESTAMP: u64 = 1600646400;
+
+const SOLANA: &str = "solana";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Network {
+    pub name: String,
+    /// Unix time of slot 0
+    pub genesis_timestamp: u64,
+    /// Nominal slot duration (actual block times vary)
+    pub slot_duration_secs: f64,
+    /// Fixed epoch length, for `epoch` columns, leader schedules and epoch partitions
+    pub slots_per_epoch: u64,
+    /// Parser name -> program id (base58) replacing the parser's Solana program
+    /// id; an empty id drops the parser
+    pub program_ids: Vec<(String, String)>,
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self::solana()
+    }
+}
+
+impl Network {
+    pub fn solana() -> Self {
+        Self {
+            name: SOLANA.to_string(),
+            genesis_timestamp: SOLANA_GENESIS_TIMESTAMP,
+            slot_duration_secs: 0.4,
+            slots_per_epoch: SLOTS_PER_EPOCH,
+            program_ids: Vec::new(),
+        }
+    }
+
+    /// Network described by `[network]`; chains other than Solana must set
+    /// their genesis timestamp
+    pub fn from_config(config: &NetworkConfig) -> Result<Self, String> {
+        let genesis_timestamp = match (config.name.as_str(), config.genesis_timestamp) {
+            (_, Some(timestamp)) => timestamp,
+            (SOLANA, None) => SOLANA_GENESIS_TIMESTAMP,
+            (name, None) => return Err(format!("network '{}' requires [network] genesis_timestamp", name)),
+        };
+        if config.slot_duration_ms == 0 {
+            return Err("network.slot_duration_ms must be greater than 0".to_string());
+        }
+        if config.slots_per_epoch == 0 {
+            return Err("network.slots_per_epoch must be greater than 0".to_string());
+        }
+        let mut program_ids: Vec<_> = config
+            .program_ids
+            .iter()
+            .map(|(parser, program_id)| (parser.clone(), program_id.clone()))
+            .collect();
+        program_ids.sort();
+        for (parser, program_id) in &program_ids {
+            if !program_id.is_empty() && decode_program_id(program_id).is_none() {
+                return Err(format!("network.program_ids.{}: invalid program id '{}'", parser, program_id));
+            }
+        }
+        Ok(Self {
+            name: config.name.clone(),
+            genesis_timestamp,
+            slot_duration_secs: config.slot_duration_ms as f64 / 1000.0,
+            slots_per_epoch: config.slots_per_epoch,
+            program_ids,
+        })
+    }
+
+    /// Estimated block time of `slot`
+    pub fn block_time(&self, slot: u64) -> u64 {
+        self.genesis_timestamp + (slot as f64 * self.slot_duration_secs) as u64
+    }
+
+    /// `map` (the Solana program ids) with this network's program ids applied
+    pub fn parser_map(&self, mut map: ParserMap) -> Result<ParserMap, String> {
+        for (parser, program_id) in &self.program_ids {
+            let parser = map
+                .values()
+                .flatten()
+                .find(|name| **name == parser.as_str())
+                .copied()
+                .ok_or_else(|| format!("network.program_ids: unknown parser '{}'", parser))?;
+            for candidates in map.values_mut() {
+                candidates.retain(|name| *name != parser);
+            }
+            map.retain(|_, candidates| !candidates.is_empty());
+            if let Some(bytes) = decode_program_id(program_id) {
+                map.entry(bytes).or_default().push(parser);
+            }
+        }
+        Ok(map)
+    }
+}
+
+fn decode_program_id(program_id: &str) -> Option<Vec<u8>> {
+    bs58::decode(program_id).into_vec().ok().filter(|bytes| bytes.len() == 32)
+}
@@ -0,0 +1,68 @@
+//! Dedicated thread pool for instruction decoding (`[processing] parse_threads`)
+//!
+//! Borsh/IDL decoding is CPU-bound, and run inline it holds a tokio worker for
+//! the whole decode, so under load ClickHouse inserts, heartbeats and the
+//! firehose's own I/O queue up behind parsing. The pool runs decoding on its
+//! own threads instead; the transaction task only awaits the result.
+//!
+//! At most `queue` instructions are queued or decoding at once. Beyond that,
+//! transaction tasks wait for a slot before handing more work over, which
+//! pushes back on the firehose instead of growing the queue without bound.
+
+use crate::multi_parser::{try_parse_candidates, ParsedInstruction};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::{oneshot, Semaphore};
+use yellowstone_vixen_core::instruction::InstructionUpdate;
+
+pub type ParseResult = Result<(&'static str, ParsedInstruction), Box<dyn std::error::Error + Send + Sync>>;
+
+pub struct ParsePool {
+    pool: rayon::ThreadPool,
+    /// Permits for queued plus in-progress decodes
+    queue: Arc<Semaphore>,
+    /// Drives the parsers' futures on pool threads (they never wait on I/O)
+    runtime: Handle,
+}
+
+impl ParsePool {
+    /// A pool of `threads` decoding threads (0 = one per core) with room for
+    /// `queue` instructions; must be called within the tokio runtime
+    pub fn new(threads: usize, queue: usize) -> Result<Self, String> {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|i| format!("parse-{}", i))
+            .build()
+            .map_err(|e| format!("Failed to start parse pool: {}", e))?;
+        Ok(Self {
+            pool,
+            queue: Arc::new(Semaphore::new(queue.max(1))),
+            runtime: Handle::current(),
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// Decode `update` with the first of `candidates` that accepts it, on the
+    /// pool; the update is handed back for the caller's later use
+    pub async fn parse(&self, update: InstructionUpdate, candidates: Vec<&'static str>) -> (InstructionUpdate, ParseResult) {
+        let permit = Arc::clone(&self.queue)
+            .acquire_owned()
+            .await
+            .expect("parse pool queue is never closed");
+        let (sender, receiver) = oneshot::channel();
+        let runtime = self.runtime.clone();
+        self.pool.spawn(move || {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                runtime.block_on(try_parse_candidates(&update, &candidates))
+            }))
+            .unwrap_or_else(|_| Err("parser panicked".into()));
+            drop(permit);
+            let _ = sender.send((update, result));
+        });
+        // rayon runs every spawned job, and the job always sends
+        receiver.await.expect("parse pool job dropped")
+    }
+}
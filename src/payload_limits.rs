@@ -0,0 +1,103 @@
+//! Size caps on stored logs and instruction data (`[payload_limits]`)
+//!
+//! A failed instruction's row carries the transaction's whole log and the
+//! instruction bytes, and log_invocations stores each invocation's logs and
+//! `Program data:` payloads, so a few outsized transactions can make rows
+//! large enough to slow inserts or hit ClickHouse's size limits. Text fields
+//! beyond `max_text_bytes` keep their prefix and end in `TRUNCATION_MARKER`;
+//! data beyond `max_data_bytes` keeps its leading bytes (cut before encoding,
+//! so the prefix still decodes). Rows with anything cut have `truncated = 1`.
+
+/// Appended to text cut at `max_text_bytes` (counted within the limit)
+pub const TRUNCATION_MARKER: &str = "...[truncated]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadLimits {
+    /// Bytes per text field (0 = unlimited)
+    max_text_bytes: usize,
+    /// Bytes per data field (0 = unlimited)
+    max_data_bytes: usize,
+}
+
+impl PayloadLimits {
+    pub fn new(max_text_bytes: usize, max_data_bytes: usize) -> Self {
+        Self {
+            max_text_bytes,
+            max_data_bytes,
+        }
+    }
+
+    /// Nothing is cut, for tests and library use
+    pub fn unlimited() -> Self {
+        Self::new(0, 0)
+    }
+
+    /// Cut `text` to `max_text_bytes`, marker included; true if it was cut
+    pub fn text(&self, text: &mut String) -> bool {
+        if self.max_text_bytes == 0 || text.len() <= self.max_text_bytes {
+            return false;
+        }
+        let mut end = self.max_text_bytes.saturating_sub(TRUNCATION_MARKER.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str(TRUNCATION_MARKER);
+        true
+    }
+
+    /// Cut log lines to `max_text_bytes` in total, the last one kept ending
+    /// in the marker; true if any were cut
+    pub fn lines(&self, lines: &mut Vec<String>) -> bool {
+        let Some((index, room)) = over_limit(lines, self.max_text_bytes) else {
+            return false;
+        };
+        lines.truncate(index + 1);
+        let line = &mut lines[index];
+        // Too little room for the marker after a prefix: the marker replaces the line
+        if room < TRUNCATION_MARKER.len() {
+            *line = TRUNCATION_MARKER.to_string();
+        } else {
+            Self::new(room, 0).text(line);
+        }
+        true
+    }
+
+    /// Leading `max_data_bytes` of `data`, and whether it was cut
+    pub fn data<'a>(&self, data: &'a [u8]) -> (&'a [u8], bool) {
+        match self.max_data_bytes {
+            0 => (data, false),
+            max if data.len() > max => (&data[..max], true),
+            _ => (data, false),
+        }
+    }
+
+    /// Cut base64 payloads to `max_data_bytes` of text in total, keeping
+    /// whole 4-character groups so the last one still decodes; true if any were cut
+    pub fn base64_payloads(&self, payloads: &mut Vec<String>) -> bool {
+        let Some((index, room)) = over_limit(payloads, self.max_data_bytes) else {
+            return false;
+        };
+        payloads.truncate(index + 1);
+        payloads[index].truncate(room - room % 4);
+        if payloads[index].is_empty() {
+            payloads.pop();
+        }
+        true
+    }
+}
+
+/// First item that takes `items` past `max` bytes in total, with the bytes left for it
+fn over_limit(items: &[String], max: usize) -> Option<(usize, usize)> {
+    if max == 0 {
+        return None;
+    }
+    let mut used = 0usize;
+    for (index, item) in items.iter().enumerate() {
+        if used + item.len() > max {
+            return Some((index, max - used));
+        }
+        used += item.len();
+    }
+    None
+}
@@ -0,0 +1,105 @@
+//! Realized PnL per wallet and mint (`--wallet-pnl`, wallet_pnl table)
+//!
+//! Every swap or bonding curve trade with exactly one SOL (or wrapped SOL) or
+//! USDC leg is a buy or sell of the other mint, priced in that quote mint.
+//! Buys open cost basis lots; sells close the oldest lots first (FIFO), and
+//! the sale's proceeds minus the closed lots' cost is the realized PnL.
+//!
+//! Only what happened inside the slot range is known: tokens sold beyond the
+//! lots bought in range (bought earlier, airdropped, transferred in) have no
+//! cost basis and are counted in `unmatched_tokens_sold` instead of PnL.
+//! Amounts are taken as recorded, so exact-out swaps contribute their bound.
+
+use crate::storage::{PnlLeg, WalletPnl};
+use std::collections::VecDeque;
+
+/// Cost basis lot: tokens bought together and what they cost
+#[derive(Debug, Clone, Copy)]
+struct Lot {
+    tokens: u64,
+    cost: u64,
+}
+
+/// Realized PnL per (wallet, mint, quote_mint) from `legs`, which must be
+/// ordered by wallet, mint, quote_mint and then execution order
+pub fn realized_pnl(legs: &[PnlLeg], slot_start: u64, slot_end: u64, computed_at: u64) -> Vec<WalletPnl> {
+    let mut results = Vec::new();
+    let mut start = 0;
+    while start < legs.len() {
+        let key = |leg: &PnlLeg| (leg.wallet.as_str(), leg.mint.as_str(), leg.quote_mint.as_str());
+        let end = start
+            + legs[start..]
+                .iter()
+                .take_while(|leg| key(leg) == key(&legs[start]))
+                .count();
+        results.push(position(&legs[start..end], slot_start, slot_end, computed_at));
+        start = end;
+    }
+    results
+}
+
+/// FIFO over one wallet's legs in one mint and quote mint
+fn position(legs: &[PnlLeg], slot_start: u64, slot_end: u64, computed_at: u64) -> WalletPnl {
+    let first = &legs[0];
+    let mut pnl = WalletPnl {
+        wallet: first.wallet.clone(),
+        mint: first.mint.clone(),
+        quote_mint: first.quote_mint.clone(),
+        slot_start,
+        slot_end,
+        first_trade: first.block_time,
+        last_trade: legs[legs.len() - 1].block_time,
+        computed_at,
+        ..Default::default()
+    };
+    let mut lots: VecDeque<Lot> = VecDeque::new();
+
+    for leg in legs {
+        if leg.buy == 1 {
+            pnl.buys += 1;
+            pnl.tokens_bought += leg.token_amount;
+            pnl.cost += leg.quote_amount;
+            if leg.token_amount > 0 {
+                lots.push_back(Lot {
+                    tokens: leg.token_amount,
+                    cost: leg.quote_amount,
+                });
+            }
+            continue;
+        }
+
+        pnl.sells += 1;
+        pnl.tokens_sold += leg.token_amount;
+        pnl.proceeds += leg.quote_amount;
+        let mut remaining = leg.token_amount;
+        let mut basis = 0u64;
+        while remaining > 0 {
+            let Some(lot) = lots.front_mut() else { break };
+            let take = remaining.min(lot.tokens);
+            let cost = share(lot.cost, take, lot.tokens);
+            basis += cost;
+            lot.tokens -= take;
+            lot.cost -= cost;
+            remaining -= take;
+            if lot.tokens == 0 {
+                lots.pop_front();
+            }
+        }
+        let matched = leg.token_amount - remaining;
+        pnl.unmatched_tokens_sold += remaining;
+        pnl.cost_basis_sold += basis;
+        pnl.realized_pnl += share(leg.quote_amount, matched, leg.token_amount) as i64 - basis as i64;
+    }
+
+    pnl.open_tokens = lots.iter().map(|lot| lot.tokens).sum();
+    pnl.open_cost = lots.iter().map(|lot| lot.cost).sum();
+    pnl
+}
+
+/// `amount * part / whole` without overflow
+fn share(amount: u64, part: u64, whole: u64) -> u64 {
+    if whole == 0 {
+        return 0;
+    }
+    (amount as u128 * part as u128 / whole as u128) as u64
+}
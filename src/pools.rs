@@ -0,0 +1,197 @@
+//! Pool registry (pools)
+//!
+//! Writes a `pools` row the first time a run sees a pool account in a swap,
+//! with the pool's two mints so swaps, liquidity events and TVL snapshots can
+//! be joined to pools without outside reference data. Most swap instructions
+//! name both mints among their accounts; for the ones that only pass token
+//! vaults (Raydium AMM v4, Orca Whirlpools, ...) the pool is written without
+//! mints and, with `resolve_via_rpc`, queued for [`PoolResolver`], which
+//! looks up the token accounts the pool owns.
+//!
+//! `mint_a`/`mint_b` are the two mints in lexicographic order, not the
+//! pool's own A/B order. The table's version ranks rows with mints above
+//! rows without, then lower first slots, so `FINAL` gives each pool's
+//! earliest sighting with its mints resolved whichever run found them.
+
+use crate::rpc::RpcClient;
+use crate::storage::{ClickHouseStorage, Pool, Swap};
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const TOKEN_PROGRAMS: [&str; 2] = [
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    "TokenzQdBNbLqP5VEhdkAS6EPFLC1PZnBkzpWFBBxCq",
+];
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Default)]
+pub struct PoolRegistry {
+    /// Pools seen this run, and whether their mints are known
+    pools: Mutex<HashMap<String, bool>>,
+    /// Pools seen without mints, waiting for [`PoolResolver`]
+    unresolved: Mutex<Vec<Pool>>,
+    resolve_via_rpc: bool,
+}
+
+impl PoolRegistry {
+    pub fn new(resolve_via_rpc: bool) -> Self {
+        Self {
+            resolve_via_rpc,
+            ..Self::default()
+        }
+    }
+
+    /// Note the pool `swap` went through, returning the row to write if it's
+    /// the first sighting (or the first one that names its mints)
+    pub fn observe(&self, swap: &Swap) -> Option<Pool> {
+        if swap.pool.is_empty() {
+            return None;
+        }
+        let resolved = !swap.mint_in.is_empty() && !swap.mint_out.is_empty();
+        let mut pools = self.pools.lock().unwrap();
+        match pools.get_mut(&swap.pool) {
+            Some(known) if *known || !resolved => return None,
+            Some(known) => *known = true,
+            None => {
+                pools.insert(swap.pool.clone(), resolved);
+            }
+        }
+        drop(pools);
+
+        let (mint_a, mint_b) = if resolved {
+            ordered(swap.mint_in.clone(), swap.mint_out.clone())
+        } else {
+            Default::default()
+        };
+        let row = Pool {
+            pool: swap.pool.clone(),
+            protocol_name: swap.protocol_name.clone(),
+            mint_a,
+            mint_b,
+            source: if resolved { "instruction" } else { "" }.to_string(),
+            slot: swap.slot,
+            signature: swap.signature.clone(),
+            block_time: swap.block_time,
+        };
+        if !resolved && self.resolve_via_rpc {
+            self.unresolved.lock().unwrap().push(row.clone());
+        }
+        Some(row)
+    }
+
+    /// Record mints resolved outside of this registry's view (by RPC)
+    fn mark_resolved(&self, pool: &str) {
+        self.pools.lock().unwrap().insert(pool.to_string(), true);
+    }
+
+    fn take_unresolved(&self) -> Vec<Pool> {
+        let pools = self.pools.lock().unwrap();
+        let mut unresolved = self.unresolved.lock().unwrap();
+        // Drop pools a later swap resolved in the meantime
+        unresolved.retain(|row| pools.get(&row.pool) == Some(&false));
+        std::mem::take(&mut *unresolved)
+    }
+}
+
+fn ordered(a: String, b: String) -> (String, String) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcContextResult<T> {
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyedAccount {
+    account: ParsedAccount,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedAccount {
+    data: ParsedData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedData {
+    parsed: ParsedTokenAccount,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedTokenAccount {
+    info: TokenAccountInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenAccountInfo {
+    mint: String,
+}
+
+/// Resolves the mints of pools swaps didn't name them for, from the token
+/// accounts the pool account owns. Pools whose vaults belong to a shared
+/// authority instead (Raydium AMM v4) stay unresolved.
+pub struct PoolResolver {
+    rpc: Arc<RpcClient>,
+    storage: Arc<ClickHouseStorage>,
+    registry: Arc<PoolRegistry>,
+}
+
+impl PoolResolver {
+    pub fn new(rpc: Arc<RpcClient>, storage: Arc<ClickHouseStorage>, registry: Arc<PoolRegistry>) -> Self {
+        Self { rpc, storage, registry }
+    }
+
+    /// Run until the task is dropped, resolving newly queued pools every poll interval
+    pub async fn run(self) {
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            match self.resolve_pending().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Resolved mints of {} pools via RPC", count),
+                Err(e) => tracing::warn!("Pool mint resolution failed: {}", e),
+            }
+        }
+    }
+
+    /// Resolve and write every queued pool, returning the number resolved
+    pub async fn resolve_pending(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut rows = Vec::new();
+        for mut row in self.registry.take_unresolved() {
+            let Some((mint_a, mint_b)) = self.owned_mints(&row.pool).await? else { continue };
+            (row.mint_a, row.mint_b) = (mint_a, mint_b);
+            row.source = "rpc".to_string();
+            self.registry.mark_resolved(&row.pool);
+            rows.push(row);
+        }
+        self.storage.insert_pools(&rows).await?;
+        Ok(rows.len())
+    }
+
+    /// The two mints of the token accounts `pool` owns (None unless exactly two)
+    async fn owned_mints(&self, pool: &str) -> Result<Option<(String, String)>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut mints = BTreeSet::new();
+        for program in TOKEN_PROGRAMS {
+            let result: Option<RpcContextResult<Vec<KeyedAccount>>> = self
+                .rpc
+                .call(
+                    "getTokenAccountsByOwner",
+                    serde_json::json!([pool, { "programId": program }, { "encoding": "jsonParsed" }]),
+                )
+                .await?;
+            if let Some(result) = result {
+                mints.extend(result.value.into_iter().map(|a| a.account.data.parsed.info.mint));
+            }
+        }
+        let mut mints = mints.into_iter();
+        Ok(match (mints.next(), mints.next(), mints.next()) {
+            (Some(a), Some(b), None) => Some((a, b)),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,51 @@
+//! Per-transaction protocol priority
+//!
+//! By default a transaction's top-level instructions are processed in order
+//! and each stores its own rows. `[processing] protocol_priority` processes
+//! the listed protocols first, highest priority first (unlisted protocols
+//! follow in instruction order). With `count_once_per_tx`, once a listed
+//! protocol has stored a row for a transaction, lower-priority listed
+//! protocols are skipped, so e.g. a Jupiter route next to a top-level Raydium
+//! leg counts as one swap instead of two.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct ProtocolPriority {
+    /// Protocol name to position in the priority list (0 = highest)
+    ranks: HashMap<String, usize>,
+    count_once_per_tx: bool,
+}
+
+impl ProtocolPriority {
+    pub fn new(order: &[String], count_once_per_tx: bool) -> Self {
+        let mut ranks = HashMap::new();
+        for (rank, protocol) in order.iter().enumerate() {
+            ranks.entry(protocol.clone()).or_insert(rank);
+        }
+        Self { ranks, count_once_per_tx }
+    }
+
+    /// Position of `protocol` in the priority list, None if unlisted
+    pub fn rank(&self, protocol: &str) -> Option<usize> {
+        self.ranks.get(protocol).copied()
+    }
+
+    /// Instruction positions in processing order, given each instruction's
+    /// protocol (None for programs without a parser)
+    pub fn order<'a>(&self, protocols: impl Iterator<Item = Option<&'a str>>) -> Vec<usize> {
+        let mut positions: Vec<(usize, usize)> = protocols
+            .map(|protocol| protocol.and_then(|p| self.rank(p)).unwrap_or(usize::MAX))
+            .enumerate()
+            .collect();
+        // Stable, so equal ranks keep instruction order
+        positions.sort_by_key(|(_, rank)| *rank);
+        positions.into_iter().map(|(position, _)| position).collect()
+    }
+
+    /// Whether an instruction of `rank` is skipped after `counted` (the rank of
+    /// the first listed protocol that stored a row for this transaction)
+    pub fn skips(&self, rank: Option<usize>, counted: Option<usize>) -> bool {
+        self.count_once_per_tx && matches!((rank, counted), (Some(rank), Some(counted)) if rank > counted)
+    }
+}
@@ -0,0 +1,66 @@
+//! Encodings of failed_transactions.raw_data (`[processing] raw_data_encoding`)
+//!
+//! Instruction bytes were stored hex-encoded, doubling them before ClickHouse's
+//! own ZSTD. `base64` stores 4 characters per 3 bytes; `zstd` compresses the
+//! bytes in the indexer before base64-encoding them, which pays off on large
+//! instruction data (route plans, bridge payloads) but adds a few bytes of
+//! frame overhead to small ones. Each row records its encoding in
+//! `raw_data_encoding` (rows from before the column existed are hex), and
+//! `--migrate-raw-data` rewrites a slot range into the configured encoding.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Compression level for `zstd` (raw_data is compressed again by the column codec)
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RawDataEncoding {
+    #[default]
+    Hex,
+    Base64,
+    /// zstd-compressed, then base64
+    Zstd,
+}
+
+impl RawDataEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Base64 => "base64",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Encoding named by a raw_data_encoding value (empty for rows written before the column)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "hex" | "" => Some(Self::Hex),
+            "base64" => Some(Self::Base64),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self, data: &[u8]) -> String {
+        match self {
+            Self::Hex => hex::encode(data),
+            Self::Base64 => STANDARD.encode(data),
+            Self::Zstd => {
+                // Compressing an in-memory buffer can't fail
+                let compressed = zstd::bulk::compress(data, ZSTD_LEVEL).expect("zstd compression");
+                STANDARD.encode(compressed)
+            }
+        }
+    }
+
+    pub fn decode(&self, raw: &str) -> Option<Vec<u8>> {
+        match self {
+            Self::Hex => hex::decode(raw).ok(),
+            Self::Base64 => STANDARD.decode(raw).ok(),
+            Self::Zstd => zstd::stream::decode_all(STANDARD.decode(raw).ok()?.as_slice()).ok(),
+        }
+    }
+}
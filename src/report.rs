@@ -0,0 +1,135 @@
+//! End-of-run report (`--report-json`) and post-run compaction (`--optimize-written`)
+//!
+//! Parts written during a backfill are small and not yet merged, so their
+//! compression ratio understates what the tables settle at. With
+//! `--optimize-written`, the partitions that hold the run's slots are merged
+//! with `OPTIMIZE TABLE ... PARTITION ID ... FINAL`, and their sizes before and
+//! after are compared. The merged partitions' bytes per row, applied to the
+//! table's total row count, estimates the table's steady-state size.
+
+use crate::reindex::ReindexReport;
+use crate::storage::{ClickHouseStorage, TableSize};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Instant;
+
+/// One table's written partitions before and after OPTIMIZE ... FINAL
+#[derive(Debug, Clone, Serialize)]
+pub struct TableCompaction {
+    pub table: String,
+    pub partitions: usize,
+    pub rows: u64,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub ratio_before: f64,
+    pub ratio_after: f64,
+    /// Whole table at the merged partitions' bytes per row
+    pub steady_state_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OptimizeReport {
+    pub seconds: f64,
+    pub tables: Vec<TableCompaction>,
+}
+
+impl OptimizeReport {
+    pub fn print(&self) {
+        println!("\n=== Compaction (OPTIMIZE ... FINAL on written partitions, {:.1}s) ===", self.seconds);
+        println!(
+            "{:<28} {:>6} {:>14} {:>11} {:>11} {:>8} {:>8} {:>12}",
+            "table", "parts", "rows", "before MB", "after MB", "ratio", "after", "steady MB"
+        );
+        for table in &self.tables {
+            println!(
+                "{:<28} {:>6} {:>14} {:>11.2} {:>11.2} {:>7.2}x {:>7.2}x {:>12.2}",
+                table.table,
+                table.partitions,
+                table.rows,
+                mb(table.bytes_before),
+                mb(table.bytes_after),
+                table.ratio_before,
+                table.ratio_after,
+                mb(table.steady_state_bytes)
+            );
+        }
+    }
+}
+
+fn mb(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+/// OPTIMIZE ... FINAL the partitions holding `slot_start..slot_end` and compare their sizes
+pub async fn optimize_written(
+    storage: &ClickHouseStorage,
+    slot_start: u64,
+    slot_end: u64,
+) -> Result<OptimizeReport, Box<dyn std::error::Error + Send + Sync>> {
+    let started = Instant::now();
+    let partitions = storage.written_partitions(slot_start, slot_end).await?;
+    let before = storage.table_sizes(Some(&partitions)).await?;
+    for (table, partition_id) in &partitions {
+        let partition_started = Instant::now();
+        storage.optimize_partition(table, partition_id, true).await?;
+        tracing::info!(
+            "Optimized {} partition {} in {:.1}s",
+            table,
+            partition_id,
+            partition_started.elapsed().as_secs_f64()
+        );
+    }
+    let after = storage.table_sizes(Some(&partitions)).await?;
+    let totals: BTreeMap<_, _> = storage
+        .table_sizes(None)
+        .await?
+        .into_iter()
+        .map(|size| (size.table.clone(), size.rows))
+        .collect();
+
+    let tables = after
+        .into_iter()
+        .map(|after| {
+            let before = before.iter().find(|b| b.table == after.table);
+            let total_rows = totals.get(&after.table).copied().unwrap_or(after.rows);
+            TableCompaction {
+                partitions: partitions.iter().filter(|(t, _)| *t == after.table).count(),
+                rows: after.rows,
+                bytes_before: before.map_or(0, |b| b.compressed_bytes),
+                bytes_after: after.compressed_bytes,
+                ratio_before: before.map_or(0.0, TableSize::ratio),
+                ratio_after: after.ratio(),
+                steady_state_bytes: (after.bytes_per_row() * total_rows as f64) as u64,
+                table: after.table,
+            }
+        })
+        .collect();
+    Ok(OptimizeReport {
+        seconds: started.elapsed().as_secs_f64(),
+        tables,
+    })
+}
+
+/// Machine-readable summary of a run, written by `--report-json`
+#[derive(Debug, Clone, Serialize)]
+pub struct RunReport {
+    pub slot_start: u64,
+    pub slot_end: u64,
+    pub seconds: f64,
+    /// (parsed, failed) instructions per parser
+    pub protocols: BTreeMap<String, (u64, u64)>,
+    pub storage: Vec<TableSize>,
+    pub optimize: Option<OptimizeReport>,
+    /// `--reindex-into` comparison against the configured tables
+    pub reindex: Option<ReindexReport>,
+    /// End-of-run validation mismatches (empty when everything checks out)
+    pub validation_problems: Vec<String>,
+}
+
+impl RunReport {
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json + "\n")
+    }
+}
@@ -0,0 +1,152 @@
+//! Reparse failed_transactions rows (`--reprocess-failed <protocol>`)
+//!
+//! After a parser fix, reruns the protocol's candidate parsers on the raw
+//! instruction data and accounts stored with each failure. Recovered
+//! instructions get their transactions row (plus liquidity position, order,
+//! swap, trade and liquidity event rows) and their failed row is deleted; the
+//! rest are left in place.
+//!
+//! Rows written before failed_transactions stored accounts can't be reparsed.
+//! Recovered transactions rows have no account role counts, leader or named
+//! accounts, and derived rows that need the whole transaction (ALT updates,
+//! compressed assets, token creators, account lifecycle) aren't produced.
+//! Failures don't keep token balances, so token UI amounts are NULL (SOL
+//! amounts are filled).
+//! Block times are re-derived from the slot time oracle when it has block
+//! times for the range, replacing the estimate stored with the failure.
+
+use crate::alt::ResolutionSource;
+use crate::config::ProtocolConfig;
+use crate::epoch::epoch_for_slot;
+use crate::decimals::TokenDecimals;
+use crate::helpers::{fill_ui_amounts, push_instruction_events};
+use crate::multi_parser::{try_parse_candidates, ParserMap};
+use crate::raw_data::RawDataEncoding;
+use crate::slot_time::SlotTimeOracle;
+use crate::storage::{ClickHouseStorage, FailedTransaction, Transaction, WriteGroup};
+use solixdb_decoder::registry::{instruction_type_id, protocol_id};
+use std::collections::HashMap;
+use std::sync::Arc;
+use yellowstone_vixen_core::instruction::InstructionUpdate;
+use yellowstone_vixen_core::Pubkey;
+
+#[derive(Debug, Default)]
+pub struct ReprocessSummary {
+    /// Failed rows with stored accounts in the slot range
+    pub candidates: usize,
+    pub recovered: usize,
+    /// Recovered, but the instruction type is filtered out by `[protocols.<name>]`
+    pub filtered: usize,
+    pub still_failing: usize,
+}
+
+/// Reparse `protocol_name`'s failed rows in [slot_start, slot_end)
+pub async fn reprocess_failed(
+    storage: &ClickHouseStorage,
+    parser_map: &ParserMap,
+    protocols: &HashMap<String, ProtocolConfig>,
+    protocol_name: &str,
+    slot_start: u64,
+    slot_end: u64,
+    slot_times: Option<&SlotTimeOracle>,
+) -> Result<ReprocessSummary, Box<dyn std::error::Error + Send + Sync>> {
+    let slot_times = slot_times.filter(|oracle| oracle.anchors() > 0);
+    let rows = storage.load_failed(protocol_name, slot_start, slot_end).await?;
+    let mut summary = ReprocessSummary {
+        candidates: rows.len(),
+        ..ReprocessSummary::default()
+    };
+    let mut recovered = Vec::new();
+    let decimals = TokenDecimals::new();
+
+    for row in rows {
+        let Some(candidates) = bs58::decode(row.program_id.as_bytes())
+            .into_vec()
+            .ok()
+            .and_then(|program_id| parser_map.get(&program_id))
+        else {
+            summary.still_failing += 1;
+            continue;
+        };
+        // raw_data may have been cut by [payload_limits]; a prefix isn't the instruction
+        let Some(update) = instruction_update(&row).filter(|_| row.truncated == 0) else {
+            summary.still_failing += 1;
+            continue;
+        };
+        let (parser, parsed) = match try_parse_candidates(&update, candidates).await {
+            Ok(result) => result,
+            Err(_) => {
+                summary.still_failing += 1;
+                continue;
+            }
+        };
+
+        let instruction_type = parsed.instruction_type();
+        recovered.push((row.signature.clone(), row.instruction_index));
+        if protocols.get(protocol_name).is_some_and(|p| !p.allows(&instruction_type)) {
+            summary.filtered += 1;
+            continue;
+        }
+        summary.recovered += 1;
+
+        let protocol_id = protocol_id(protocol_name);
+        let instruction_type_id = instruction_type_id(&instruction_type);
+        storage
+            .register_instruction_type(protocol_id, instruction_type_id, &instruction_type)
+            .await?;
+        let block_time = slot_times.map_or(row.block_time, |oracle| oracle.block_time(row.slot));
+        let mut group = WriteGroup::default();
+        push_instruction_events(&mut group, &parsed, &row.signature, row.slot, block_time, protocol_name);
+        fill_ui_amounts(&mut group, &decimals);
+        group.transactions.push(Transaction {
+            signature: row.signature.clone(),
+            instruction_index: row.instruction_index,
+            slot: row.slot,
+            block_time,
+            program_id: row.program_id.clone(),
+            protocol_name: Arc::from(protocol_name),
+            protocol_id,
+            instruction_type: Arc::from(instruction_type),
+            instruction_type_id,
+            parser: Arc::from(parser),
+            success: 1,
+            fee: row.fee,
+            compute_units: row.compute_units,
+            accounts_count: update.accounts.len() as u16,
+            data_len: update.data.len() as u32,
+            writable_accounts_count: 0,
+            signer_accounts_count: 0,
+            epoch: epoch_for_slot(row.slot, storage.slots_per_epoch()) as u32,
+            leader: String::new(),
+            named_accounts: String::new(),
+            resolution_source: Arc::from(ResolutionSource::Reprocessed.as_str()),
+            fee_payer: String::new(),
+            signers: Vec::new(),
+        });
+        storage.insert_group(group).await?;
+    }
+
+    // Only drop failures once their replacement rows are written
+    storage.flush_all().await?;
+    storage.delete_failed(&recovered).await?;
+    Ok(summary)
+}
+
+fn instruction_update(row: &FailedTransaction) -> Option<InstructionUpdate> {
+    let program: [u8; 32] = bs58::decode(row.program_id.as_bytes()).into_vec().ok()?.try_into().ok()?;
+    let accounts = row
+        .accounts
+        .iter()
+        .map(|a| {
+            let bytes: [u8; 32] = bs58::decode(a).into_vec().ok()?.try_into().ok()?;
+            Some(Pubkey::from(bytes))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(InstructionUpdate {
+        program: Pubkey::from(program),
+        data: RawDataEncoding::parse(&row.raw_data_encoding)?.decode(&row.raw_data)?,
+        accounts,
+        shared: Default::default(),
+        inner: vec![],
+    })
+}
@@ -0,0 +1,74 @@
+//! Minimal Solana JSON-RPC client
+//!
+//! Only the handful of methods used for enrichment are needed, so this is a thin
+//! wrapper over reqwest rather than a dependency on the full Solana client.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+pub struct RpcClient {
+    url: String,
+    http: reqwest::Client,
+    next_id: AtomicU64,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to build HTTP client");
+        Self {
+            url: url.to_string(),
+            http,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Call a JSON-RPC method, returning None when the node returns a null result
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<Option<T>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let response: RpcResponse<T> = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("RPC {} request failed: {}", method, e))?
+            .json()
+            .await
+            .map_err(|e| format!("RPC {} returned invalid JSON: {}", method, e))?;
+
+        if let Some(err) = response.error {
+            return Err(format!("RPC {} error {}: {}", method, err.code, err.message).into());
+        }
+        Ok(response.result)
+    }
+}
@@ -0,0 +1,61 @@
+//! Work-stealing slot scheduler
+//!
+//! jetstreamer splits a firehose call's range evenly across its threads, so on
+//! skewed ranges (a burst of dense slots) some threads finish long before the
+//! others. With `[scheduler] work_stealing = true` the range is cut into
+//! `chunk_slots` chunks on a shared queue instead, and each worker runs a
+//! single-threaded firehose call per chunk, taking the next chunk as soon as it
+//! finishes. Workers end within about one chunk's processing time of each
+//! other, at the cost of one firehose call (and its archive setup) per chunk.
+//!
+//! Chunks are handed out in slot order, so every slot below the slowest
+//! worker's current slot is done and the flushed watermark stays valid.
+
+use futures_util::future::try_join_all;
+use std::future::Future;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Shared queue of slot chunks, handed out in order
+pub struct ChunkQueue {
+    next: AtomicU64,
+    end: u64,
+    chunk_slots: u64,
+}
+
+impl ChunkQueue {
+    pub fn new(range: Range<u64>, chunk_slots: u64) -> Self {
+        Self {
+            next: AtomicU64::new(range.start),
+            end: range.end,
+            chunk_slots: chunk_slots.max(1),
+        }
+    }
+
+    /// Take the next chunk, or None once the range is exhausted
+    pub fn next_chunk(&self) -> Option<Range<u64>> {
+        let start = self.next.fetch_add(self.chunk_slots, Ordering::Relaxed);
+        (start < self.end).then(|| start..(start.saturating_add(self.chunk_slots)).min(self.end))
+    }
+}
+
+/// Run `range` on `workers` workers pulling chunks from a shared queue
+///
+/// `run_chunk(worker, chunk)` processes one chunk. The first error stops every
+/// worker (chunks in flight are abandoned) and is returned.
+pub async fn run_work_stealing<F, Fut, E>(range: Range<u64>, workers: usize, chunk_slots: u64, run_chunk: F) -> Result<(), E>
+where
+    F: Fn(usize, Range<u64>) -> Fut,
+    Fut: Future<Output = Result<(), E>>,
+{
+    let queue = ChunkQueue::new(range, chunk_slots);
+    let (queue, run_chunk) = (&queue, &run_chunk);
+    try_join_all((0..workers.max(1)).map(|worker| async move {
+        while let Some(chunk) = queue.next_chunk() {
+            run_chunk(worker, chunk).await?;
+        }
+        Ok::<(), E>(())
+    }))
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Slot -> block time from real block times (`[slot_time_oracle]`, slot_time_oracle table)
+//!
+//! Transactions don't carry their block's time, and `Network::block_time`
+//! (genesis plus 400 ms per slot) drifts from the chain by hours, since real
+//! slots run slower than nominal and skipped slots still advance time. Every
+//! block the firehose delivers has its actual time, so the indexer writes
+//! each one to slot_time_oracle and keeps anchors in memory, and block times
+//! are read from those: exact where a block of the slot was seen, otherwise
+//! interpolated between the nearest anchors on either side, or extrapolated
+//! at the nominal slot duration from the nearest one. With no anchors at all
+//! it falls back to the genesis estimate.
+//!
+//! The firehose delivers a block after its transactions, so on a first pass
+//! a transaction's time comes from nearby blocks (usually within a second);
+//! runs over slots already in slot_time_oracle preload those anchors and
+//! match the chain. Only one anchor per `ANCHOR_SPACING` slots is kept in
+//! memory, which bounds memory over long ranges at well under a second of
+//! interpolation error.
+
+use crate::network::Network;
+use crate::storage::ClickHouseStorage;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Slots per in-memory anchor bucket (about 13 seconds)
+pub const ANCHOR_SPACING: u64 = 32;
+
+/// Slots preloaded either side of a range, so its edges interpolate (about an hour)
+const PRELOAD_MARGIN_SLOTS: u64 = 9_000;
+
+#[derive(Debug)]
+pub struct SlotTimeOracle {
+    network: Network,
+    /// slot -> block time, at most one per `ANCHOR_SPACING` bucket
+    anchors: RwLock<BTreeMap<u64, u64>>,
+}
+
+impl SlotTimeOracle {
+    /// An oracle with no anchors (block times are the network's estimate until some are recorded)
+    pub fn new(network: Network) -> Self {
+        Self {
+            network,
+            anchors: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// `slot` was produced at `block_time`; kept if its bucket has no anchor yet
+    pub fn record(&self, slot: u64, block_time: u64) {
+        let bucket = slot - slot % ANCHOR_SPACING;
+        let mut anchors = self.anchors.write().unwrap_or_else(|e| e.into_inner());
+        if anchors.range(bucket..bucket + ANCHOR_SPACING).next().is_none() {
+            anchors.insert(slot, block_time);
+        }
+    }
+
+    /// Load anchors for [slot_start, slot_end), plus a margin either side,
+    /// from slot_time_oracle, returning how many
+    pub async fn preload(
+        &self,
+        storage: &ClickHouseStorage,
+        slot_start: u64,
+        slot_end: u64,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let anchors = storage
+            .load_slot_times(
+                slot_start.saturating_sub(PRELOAD_MARGIN_SLOTS),
+                slot_end.saturating_add(PRELOAD_MARGIN_SLOTS),
+                ANCHOR_SPACING,
+            )
+            .await?;
+        for &(slot, block_time) in &anchors {
+            self.record(slot, block_time);
+        }
+        Ok(anchors.len())
+    }
+
+    pub fn anchors(&self) -> usize {
+        self.anchors.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Block time of `slot`: exact for an anchor, interpolated between the
+    /// anchors around it, extrapolated from the nearest one, or the network's
+    /// estimate when there are none
+    pub fn block_time(&self, slot: u64) -> u64 {
+        let anchors = self.anchors.read().unwrap_or_else(|e| e.into_inner());
+        let before = anchors.range(..=slot).next_back();
+        let after = anchors.range(slot..).next();
+        let per_slot = self.network.slot_duration_secs;
+        match (before, after) {
+            (Some((&s0, &t0)), Some((&s1, &t1))) if s1 > s0 => {
+                let fraction = (slot - s0) as f64 / (s1 - s0) as f64;
+                t0 + (t1.saturating_sub(t0) as f64 * fraction) as u64
+            }
+            (Some((_, &t0)), Some(_)) => t0,
+            (Some((&s0, &t0)), None) => t0 + ((slot - s0) as f64 * per_slot) as u64,
+            (None, Some((&s1, &t1))) => t1.saturating_sub(((s1 - slot) as f64 * per_slot) as u64),
+            (None, None) => self.network.block_time(slot),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! SQLite storage for small embedded deployments (`sqlite` feature)
+//!
+//! `SqliteStorage` implements [`Storage`] on a single SQLite file in WAL mode,
+//! for edge devices and local development where running ClickHouse is too
+//! much. Only the core tables are kept, with the same columns as their
+//! ClickHouse counterparts:
+//!
+//! - `transactions` (`signers` as a JSON array; `date`/`hour` are generated
+//!   columns in UTC)
+//! - `protocol_events`, the combined swaps/trades/liquidity events table
+//!   (always filled, since the per-kind tables aren't kept)
+//!
+//! Rows for every other table are accepted and dropped. Each write group is
+//! committed in one SQLite transaction, so a crash loses whole transactions
+//! only. Writes run on the calling task under a mutex: fine for the low
+//! volumes this is meant for, not for a full mainnet backfill.
+//!
+//! ```ignore
+//! let storage = Arc::new(SqliteStorage::open("solixdb.sqlite")?);
+//! let ctx = ProcessingContext { storage: storage.clone(), ... };
+//! process_transaction(tx, &ctx).await?;
+//! ```
+
+use crate::storage::{
+    NewMintsHourly, Pool, ProtocolCooccurrence, ProtocolDailyUsers, ProtocolHourlySummary, Storage,
+    UnknownDiscriminator, WalletFirstSeen, WriteGroup,
+};
+use async_trait::async_trait;
+use rusqlite::{params, Connection};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT NOT NULL,
+    instruction_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER NOT NULL,
+    program_id TEXT NOT NULL,
+    protocol_name TEXT NOT NULL,
+    protocol_id INTEGER NOT NULL,
+    instruction_type TEXT NOT NULL,
+    instruction_type_id INTEGER NOT NULL,
+    parser TEXT NOT NULL,
+    success INTEGER NOT NULL,
+    fee INTEGER NOT NULL,
+    compute_units INTEGER NOT NULL,
+    accounts_count INTEGER NOT NULL,
+    data_len INTEGER NOT NULL,
+    writable_accounts_count INTEGER NOT NULL,
+    signer_accounts_count INTEGER NOT NULL,
+    epoch INTEGER NOT NULL,
+    leader TEXT NOT NULL,
+    named_accounts TEXT NOT NULL,
+    resolution_source TEXT NOT NULL,
+    fee_payer TEXT NOT NULL,
+    signers TEXT NOT NULL,
+    date TEXT GENERATED ALWAYS AS (date(block_time, 'unixepoch')) VIRTUAL,
+    hour INTEGER GENERATED ALWAYS AS (CAST(strftime('%H', block_time, 'unixepoch') AS INTEGER)) VIRTUAL
+);
+CREATE INDEX IF NOT EXISTS transactions_slot ON transactions (slot, signature);
+CREATE INDEX IF NOT EXISTS transactions_protocol ON transactions (protocol_name, slot);
+
+CREATE TABLE IF NOT EXISTS protocol_events (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    block_time INTEGER NOT NULL,
+    protocol_name TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    pool TEXT NOT NULL,
+    user TEXT NOT NULL,
+    mint_a TEXT NOT NULL,
+    mint_b TEXT NOT NULL,
+    amount_a INTEGER NOT NULL,
+    amount_b INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS protocol_events_protocol ON protocol_events (protocol_name, slot, signature);
+"#;
+
+pub struct SqliteStorage {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Open (or create) the database at `path` and its tables
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let connection = Connection::open(path)?;
+        // WAL lets readers query while the indexer writes; NORMAL sync is safe with WAL
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.pragma_update(None, "synchronous", "NORMAL")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap()
+    }
+
+    /// Row count of a table, for checks and tests
+    pub fn count(&self, table: &str) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let count: i64 = self
+            .connection()
+            .query_row(&format!("SELECT count(*) FROM {}", table), [], |row| row.get(0))?;
+        Ok(count as u64)
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn insert_group(&self, mut group: WriteGroup) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if group.is_empty() {
+            return Ok(());
+        }
+        group.fill_protocol_events();
+        let mut connection = self.connection();
+        let tx = connection.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO transactions (signature, instruction_index, slot, block_time, program_id, \
+                 protocol_name, protocol_id, instruction_type, instruction_type_id, parser, success, fee, \
+                 compute_units, accounts_count, data_len, writable_accounts_count, signer_accounts_count, \
+                 epoch, leader, named_accounts, resolution_source, fee_payer, signers) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
+            )?;
+            for row in &group.transactions {
+                insert.execute(params![
+                    row.signature,
+                    row.instruction_index,
+                    row.slot as i64,
+                    row.block_time as i64,
+                    &*row.program_id,
+                    &*row.protocol_name,
+                    row.protocol_id,
+                    &*row.instruction_type,
+                    row.instruction_type_id,
+                    &*row.parser,
+                    row.success,
+                    row.fee as i64,
+                    row.compute_units as i64,
+                    row.accounts_count,
+                    row.data_len,
+                    row.writable_accounts_count,
+                    row.signer_accounts_count,
+                    row.epoch,
+                    row.leader,
+                    row.named_accounts,
+                    &*row.resolution_source,
+                    row.fee_payer,
+                    serde_json::to_string(&row.signers)?,
+                ])?;
+            }
+            let mut insert = tx.prepare_cached(
+                "INSERT INTO protocol_events (signature, slot, block_time, protocol_name, event_type, pool, \
+                 user, mint_a, mint_b, amount_a, amount_b) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            for row in &group.protocol_events {
+                insert.execute(params![
+                    row.signature,
+                    row.slot as i64,
+                    row.block_time as i64,
+                    row.protocol_name,
+                    row.event_type,
+                    row.pool,
+                    row.user,
+                    row.mint_a,
+                    row.mint_b,
+                    // Amounts above i64::MAX wrap; SQLite integers are signed
+                    row.amount_a as i64,
+                    row.amount_b as i64,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    async fn register_instruction_type(
+        &self,
+        _protocol_id: u16,
+        _instruction_type_id: u32,
+        _name: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn insert_unknown_discriminator(&self, _row: UnknownDiscriminator) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn insert_wallet_first_seen(&self, _row: WalletFirstSeen) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn insert_pool(&self, _row: Pool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn insert_new_mints(&self, _rows: &[NewMintsHourly]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn insert_hourly_summary(&self, _rows: &[ProtocolHourlySummary]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn insert_daily_users(&self, _rows: &[ProtocolDailyUsers]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    async fn insert_cooccurrence(&self, _rows: &[ProtocolCooccurrence]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Groups are committed as they arrive; this only folds the WAL back into the database file
+    async fn flush_all(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.connection().execute_batch("PRAGMA wal_checkpoint(PASSIVE);")?;
+        Ok(())
+    }
+}
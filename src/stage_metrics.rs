@@ -0,0 +1,210 @@
+//! Per-stage pipeline timing
+//!
+//! Each transaction's time is split into account resolution, parsing, event
+//! extraction and buffer enqueue, and recorded into fixed-bucket histograms.
+//! When throughput drops, this shows whether the time goes to CPU parsing or to
+//! storage (enqueue includes any synchronous batch flush it triggers).
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bucket bounds in microseconds (an implicit +Inf bucket follows)
+const BUCKETS_US: &[u64] = &[
+    5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000,
+    1_000_000,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    AccountResolution,
+    Parse,
+    EventExtraction,
+    BufferEnqueue,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [
+        Stage::AccountResolution,
+        Stage::Parse,
+        Stage::EventExtraction,
+        Stage::BufferEnqueue,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AccountResolution => "account_resolution",
+            Self::Parse => "parse",
+            Self::EventExtraction => "event_extraction",
+            Self::BufferEnqueue => "buffer_enqueue",
+        }
+    }
+}
+
+/// Lock-free histogram with fixed microsecond buckets
+pub struct Histogram {
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_us: AtomicU64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: (0..=BUCKETS_US.len()).map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_us: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Histogram {
+    pub fn observe(&self, duration: Duration) {
+        let us = duration.as_micros() as u64;
+        let idx = BUCKETS_US.iter().position(|b| us <= *b).unwrap_or(BUCKETS_US.len());
+        self.buckets[idx].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(us, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> Duration {
+        Duration::from_micros(self.sum_us.load(Ordering::Relaxed))
+    }
+
+    /// Approximate quantile (upper bound of the bucket containing it), in microseconds
+    pub fn quantile_us(&self, q: f64) -> Option<u64> {
+        let count = self.count();
+        if count == 0 {
+            return None;
+        }
+        let target = ((count as f64) * q).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (idx, bucket) in self.buckets.iter().enumerate() {
+            seen += bucket.load(Ordering::Relaxed);
+            if seen >= target {
+                return Some(BUCKETS_US.get(idx).copied().unwrap_or(u64::MAX));
+            }
+        }
+        Some(u64::MAX)
+    }
+}
+
+/// Per-transaction stage durations, accumulated across a transaction's instructions
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StageTimings {
+    pub account_resolution: Duration,
+    pub parse: Duration,
+    pub event_extraction: Duration,
+    pub buffer_enqueue: Duration,
+}
+
+#[derive(Default)]
+pub struct StageMetrics {
+    account_resolution: Histogram,
+    parse: Histogram,
+    event_extraction: Histogram,
+    buffer_enqueue: Histogram,
+}
+
+impl StageMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn histogram(&self, stage: Stage) -> &Histogram {
+        match stage {
+            Stage::AccountResolution => &self.account_resolution,
+            Stage::Parse => &self.parse,
+            Stage::EventExtraction => &self.event_extraction,
+            Stage::BufferEnqueue => &self.buffer_enqueue,
+        }
+    }
+
+    /// Record one transaction's stage timings
+    pub fn record(&self, timings: &StageTimings) {
+        self.account_resolution.observe(timings.account_resolution);
+        self.parse.observe(timings.parse);
+        self.event_extraction.observe(timings.event_extraction);
+        self.buffer_enqueue.observe(timings.buffer_enqueue);
+    }
+
+    /// Render in Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP solixdb_stage_duration_seconds Per-transaction time spent in each pipeline stage\n");
+        out.push_str("# TYPE solixdb_stage_duration_seconds histogram\n");
+        for stage in Stage::ALL {
+            let hist = self.histogram(stage);
+            let name = stage.as_str();
+            let mut cumulative = 0;
+            for (idx, bucket) in hist.buckets.iter().enumerate() {
+                cumulative += bucket.load(Ordering::Relaxed);
+                let le = match BUCKETS_US.get(idx) {
+                    Some(us) => format!("{}", *us as f64 / 1_000_000.0),
+                    None => "+Inf".to_string(),
+                };
+                let _ = writeln!(
+                    out,
+                    "solixdb_stage_duration_seconds_bucket{{stage=\"{}\",le=\"{}\"}} {}",
+                    name, le, cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "solixdb_stage_duration_seconds_sum{{stage=\"{}\"}} {}",
+                name,
+                hist.sum().as_secs_f64()
+            );
+            let _ = writeln!(
+                out,
+                "solixdb_stage_duration_seconds_count{{stage=\"{}\"}} {}",
+                name,
+                hist.count()
+            );
+        }
+        out
+    }
+
+    pub fn print_summary(&self) {
+        println!("\n=== Stage Timing (per transaction) ===");
+        let total: Duration = Stage::ALL.iter().map(|s| self.histogram(*s).sum()).sum();
+        for stage in Stage::ALL {
+            let hist = self.histogram(stage);
+            let count = hist.count();
+            if count == 0 {
+                continue;
+            }
+            let share = if total.is_zero() {
+                0.0
+            } else {
+                hist.sum().as_secs_f64() / total.as_secs_f64() * 100.0
+            };
+            let fmt_q = |q| match hist.quantile_us(q) {
+                Some(u64::MAX) => "inf".to_string(),
+                Some(us) => format!("{}us", us),
+                None => "-".to_string(),
+            };
+            println!(
+                "{:<20} mean {:>8.1}us  p50 <= {:>9}  p99 <= {:>9}  ({:.1}% of time)",
+                stage.as_str(),
+                hist.sum().as_micros() as f64 / count as f64,
+                fmt_q(0.5),
+                fmt_q(0.99),
+                share
+            );
+        }
+    }
+}
+
+/// Write Prometheus text to a file (for node_exporter's textfile collector)
+///
+/// Writes to a temp file and renames so the collector never sees a partial file.
+pub fn write_textfile(path: &std::path::Path, text: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("prom.tmp");
+    std::fs::write(&tmp, text)?;
+    std::fs::rename(&tmp, path)
+}
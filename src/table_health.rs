@@ -0,0 +1,126 @@
+//! Per-table insert health
+//!
+//! Counts failed insert attempts and batches per table and keeps the last
+//! error message and the time of the last successful insert, so an operator
+//! can see which table is unhealthy from the metrics textfile or a SIGUSR1
+//! dump instead of grepping the logs for retry messages.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableStatus {
+    /// Insert attempts that failed (each retry counts)
+    pub failed_attempts: u64,
+    /// Batches that failed every retry and were given up on
+    pub failed_batches: u64,
+    pub last_error: Option<String>,
+    /// Unix seconds of the last failed attempt
+    pub last_error_at: Option<u64>,
+    /// Unix seconds of the last successful insert
+    pub last_success_at: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct TableHealth {
+    tables: Mutex<BTreeMap<String, TableStatus>>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+impl TableHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record_success(&self, table: &str) {
+        let mut tables = self.tables.lock().unwrap();
+        tables.entry(table.to_string()).or_default().last_success_at = Some(now());
+    }
+
+    pub(crate) fn record_failed_attempt(&self, table: &str, error: &str) {
+        let mut tables = self.tables.lock().unwrap();
+        let status = tables.entry(table.to_string()).or_default();
+        status.failed_attempts += 1;
+        status.last_error = Some(error.to_string());
+        status.last_error_at = Some(now());
+    }
+
+    pub(crate) fn record_failed_batch(&self, table: &str) {
+        let mut tables = self.tables.lock().unwrap();
+        tables.entry(table.to_string()).or_default().failed_batches += 1;
+    }
+
+    /// Status of every table inserted into so far, by name
+    pub fn snapshot(&self) -> BTreeMap<String, TableStatus> {
+        self.tables.lock().unwrap().clone()
+    }
+
+    /// Status of one table (default if nothing was inserted into it yet)
+    pub fn table(&self, table: &str) -> TableStatus {
+        self.tables.lock().unwrap().get(table).cloned().unwrap_or_default()
+    }
+
+    /// One line per table, for the SIGUSR1 dump
+    pub fn report(&self) -> Vec<String> {
+        let now = now();
+        let ago = |at: Option<u64>| match at {
+            Some(at) => format!("{}s ago", now.saturating_sub(at)),
+            None => "never".to_string(),
+        };
+        self.snapshot()
+            .into_iter()
+            .map(|(table, status)| {
+                let mut line = format!(
+                    "{}: last success {}, {} failed attempts, {} failed batches",
+                    table,
+                    ago(status.last_success_at),
+                    status.failed_attempts,
+                    status.failed_batches
+                );
+                if let Some(error) = &status.last_error {
+                    let _ = write!(line, ", last error {}: {}", ago(status.last_error_at), error);
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Render in Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let tables = self.snapshot();
+        let mut out = String::new();
+        out.push_str("# HELP solixdb_insert_failed_attempts_total Failed ClickHouse insert attempts, retries included\n");
+        out.push_str("# TYPE solixdb_insert_failed_attempts_total counter\n");
+        for (table, status) in &tables {
+            let _ = writeln!(out, "solixdb_insert_failed_attempts_total{{table=\"{}\"}} {}", table, status.failed_attempts);
+        }
+        out.push_str("# HELP solixdb_insert_failed_batches_total Batches that failed every insert retry\n");
+        out.push_str("# TYPE solixdb_insert_failed_batches_total counter\n");
+        for (table, status) in &tables {
+            let _ = writeln!(out, "solixdb_insert_failed_batches_total{{table=\"{}\"}} {}", table, status.failed_batches);
+        }
+        out.push_str("# HELP solixdb_insert_last_success_timestamp_seconds Unix time of the last successful insert\n");
+        out.push_str("# TYPE solixdb_insert_last_success_timestamp_seconds gauge\n");
+        for (table, status) in &tables {
+            if let Some(at) = status.last_success_at {
+                let _ = writeln!(out, "solixdb_insert_last_success_timestamp_seconds{{table=\"{}\"}} {}", table, at);
+            }
+        }
+        out.push_str("# HELP solixdb_insert_last_error_timestamp_seconds Unix time of the last failed insert attempt\n");
+        out.push_str("# TYPE solixdb_insert_last_error_timestamp_seconds gauge\n");
+        for (table, status) in &tables {
+            if let Some(at) = status.last_error_at {
+                let _ = writeln!(out, "solixdb_insert_last_error_timestamp_seconds{{table=\"{}\"}} {}", table, at);
+            }
+        }
+        out
+    }
+}
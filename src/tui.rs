@@ -0,0 +1,301 @@
+//! Terminal dashboard for backfills (`--tui`, requires the `tui` feature)
+//!
+//! Redraws a full-screen view every second instead of scrolling log lines:
+//! flushed slot progress, per-protocol parse rates, table buffer fill,
+//! ClickHouse insert latency and the latest warnings and errors. While the
+//! dashboard is up, log events go to [`LogCapture`] instead of the terminal;
+//! once it stops (end of run, or Ctrl-C), logging prints as usual again.
+//!
+//! The terminal isn't switched to raw mode, so Ctrl-C still reaches the
+//! indexer's signal handler for a graceful shutdown.
+
+use crate::helpers::ProcessingContext;
+use crate::storage::ClickHouseStorage;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::cursor::{Hide, Show};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Row, Table};
+use ratatui::{Frame, Terminal};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::io::Stdout;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Warnings and errors kept for the dashboard
+const RECENT_ERRORS: usize = 50;
+
+const REFRESH: Duration = Duration::from_secs(1);
+
+/// Tracing layer that keeps recent warnings and errors while the dashboard is
+/// active (see [`LogCapture::active`] to silence the regular log output meanwhile)
+#[derive(Debug, Clone, Default)]
+pub struct LogCapture {
+    active: Arc<AtomicBool>,
+    recent: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl LogCapture {
+    /// Whether the dashboard owns the terminal (regular log output should be off)
+    pub fn active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    fn recent(&self) -> Vec<String> {
+        self.recent.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+/// The process-wide capture shared by the tracing subscriber and the dashboard
+pub fn log_capture() -> LogCapture {
+    static CAPTURE: OnceLock<LogCapture> = OnceLock::new();
+    CAPTURE.get_or_init(LogCapture::default).clone()
+}
+
+impl<S: Subscriber> Layer<S> for LogCapture {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let level = *event.metadata().level();
+        if !self.active() || level > Level::WARN {
+            return;
+        }
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+        let line = format!("{} {} {}", chrono::Utc::now().format("%H:%M:%S"), level, message.0);
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() == RECENT_ERRORS {
+            recent.pop_front();
+        }
+        recent.push_back(line);
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Everything drawn in one frame
+struct Snapshot {
+    slot: u64,
+    elapsed: Duration,
+    /// (protocol, parsed/s, failed/s, parsed, failed), busiest first
+    protocols: Vec<(String, f64, f64, u64, u64)>,
+    buffers: Vec<(&'static str, usize)>,
+    batch_size: usize,
+    inserts: u64,
+    last_insert: Duration,
+    mean_insert: Duration,
+    errors: Vec<String>,
+}
+
+pub struct Dashboard {
+    ctx: Arc<ProcessingContext>,
+    storage: Arc<ClickHouseStorage>,
+    capture: LogCapture,
+    slot_start: u64,
+    slot_end: u64,
+    started: Instant,
+    /// Parsed/failed totals at the previous frame, for rates
+    previous: HashMap<String, (u64, u64)>,
+}
+
+impl Dashboard {
+    pub fn new(ctx: Arc<ProcessingContext>, storage: Arc<ClickHouseStorage>, slot_start: u64, slot_end: u64) -> Self {
+        Self {
+            ctx,
+            storage,
+            capture: log_capture(),
+            slot_start,
+            slot_end,
+            started: Instant::now(),
+            previous: HashMap::new(),
+        }
+    }
+
+    /// Draw every second until `stop` is set, then give the terminal back
+    pub async fn run(mut self, stop: Arc<AtomicBool>) -> std::io::Result<()> {
+        // No raw mode, so Ctrl-C still raises SIGINT
+        execute!(std::io::stdout(), EnterAlternateScreen, Hide)?;
+        self.capture.active.store(true, Ordering::Relaxed);
+        let result = match Terminal::new(CrosstermBackend::new(std::io::stdout())) {
+            Ok(mut terminal) => self.draw_until(&mut terminal, &stop).await,
+            Err(e) => Err(e),
+        };
+        self.capture.active.store(false, Ordering::Relaxed);
+        execute!(std::io::stdout(), Show, LeaveAlternateScreen)?;
+        result
+    }
+
+    async fn draw_until(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>, stop: &AtomicBool) -> std::io::Result<()> {
+        let mut ticker = tokio::time::interval(REFRESH);
+        let mut last_frame = Instant::now();
+        while !stop.load(Ordering::Relaxed) {
+            ticker.tick().await;
+            let snapshot = self.snapshot(last_frame.elapsed()).await;
+            last_frame = Instant::now();
+            terminal.draw(|frame| self.draw(frame, &snapshot))?;
+        }
+        Ok(())
+    }
+
+    async fn snapshot(&mut self, since_last: Duration) -> Snapshot {
+        let seconds = since_last.as_secs_f64().max(0.001);
+        let mut protocols: Vec<_> = self
+            .ctx
+            .metrics
+            .iter()
+            .map(|(name, (parsed, failed))| {
+                let totals = (parsed.load(Ordering::Relaxed), failed.load(Ordering::Relaxed));
+                let previous = self.previous.insert(name.clone(), totals).unwrap_or_default();
+                (
+                    name.clone(),
+                    totals.0.saturating_sub(previous.0) as f64 / seconds,
+                    totals.1.saturating_sub(previous.1) as f64 / seconds,
+                    totals.0,
+                    totals.1,
+                )
+            })
+            .filter(|(_, _, _, parsed, failed)| parsed + failed > 0)
+            .collect();
+        protocols.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.3.cmp(&a.3)));
+
+        let latency = self.storage.insert_latency();
+        Snapshot {
+            slot: self.storage.watermark().slot(),
+            elapsed: self.started.elapsed(),
+            protocols,
+            buffers: self.storage.buffer_levels().await,
+            batch_size: self.storage.batch_size(),
+            inserts: latency.inserts(),
+            last_insert: latency.last(),
+            mean_insert: latency.mean(),
+            errors: self.capture.recent(),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame, snapshot: &Snapshot) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(8), Constraint::Length(12)])
+            .split(frame.area());
+        let middle = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(rows[1]);
+
+        frame.render_widget(self.progress(snapshot), rows[0]);
+        frame.render_widget(protocol_table(snapshot), middle[0]);
+        let right = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(4)])
+            .split(middle[1]);
+        frame.render_widget(insert_latency(snapshot), right[0]);
+        frame.render_widget(buffer_table(snapshot), right[1]);
+        frame.render_widget(error_list(snapshot), rows[2]);
+    }
+
+    fn progress(&self, snapshot: &Snapshot) -> Gauge<'static> {
+        let total = self.slot_end.saturating_sub(self.slot_start).max(1);
+        let done = snapshot.slot.clamp(self.slot_start, self.slot_end) - self.slot_start;
+        let ratio = done as f64 / total as f64;
+        let eta = if done > 0 {
+            let remaining = snapshot.elapsed.as_secs_f64() * (total - done) as f64 / done as f64;
+            format!(", ETA {}", format_duration(Duration::from_secs_f64(remaining)))
+        } else {
+            String::new()
+        };
+        Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(" Flushed slots "))
+            .gauge_style(Style::default().fg(Color::Green))
+            .ratio(ratio)
+            .label(format!(
+                "{} / {} ({:.1}%), elapsed {}{}",
+                snapshot.slot.max(self.slot_start),
+                self.slot_end,
+                ratio * 100.0,
+                format_duration(snapshot.elapsed),
+                eta
+            ))
+    }
+}
+
+fn protocol_table(snapshot: &Snapshot) -> Table<'static> {
+    let rows = snapshot.protocols.iter().map(|(name, parsed_rate, failed_rate, parsed, failed)| {
+        let style = if *failed_rate > 0.0 { Style::default().fg(Color::Yellow) } else { Style::default() };
+        Row::new(vec![
+            name.clone(),
+            format!("{:.0}", parsed_rate),
+            format!("{:.0}", failed_rate),
+            parsed.to_string(),
+            failed.to_string(),
+        ])
+        .style(style)
+    });
+    Table::new(
+        rows,
+        [
+            Constraint::Min(16),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(12),
+            Constraint::Length(10),
+        ],
+    )
+    .header(Row::new(vec!["protocol", "parsed/s", "failed/s", "parsed", "failed"]).style(Style::default().fg(Color::Cyan)))
+    .block(Block::default().borders(Borders::ALL).title(" Protocols "))
+}
+
+fn insert_latency(snapshot: &Snapshot) -> Paragraph<'static> {
+    Paragraph::new(format!(
+        "{} inserts, last {} ms, mean {} ms",
+        snapshot.inserts,
+        snapshot.last_insert.as_millis(),
+        snapshot.mean_insert.as_millis()
+    ))
+    .block(Block::default().borders(Borders::ALL).title(" ClickHouse inserts "))
+}
+
+fn buffer_table(snapshot: &Snapshot) -> Table<'static> {
+    const BAR_WIDTH: usize = 20;
+    let batch_size = snapshot.batch_size.max(1);
+    let rows = snapshot.buffers.iter().filter(|(_, rows)| *rows > 0).map(|(table, rows)| {
+        let filled = (rows * BAR_WIDTH / batch_size).min(BAR_WIDTH);
+        Row::new(vec![
+            table.to_string(),
+            rows.to_string(),
+            format!("{}{}", "█".repeat(filled), "·".repeat(BAR_WIDTH - filled)),
+        ])
+    });
+    Table::new(rows, [Constraint::Min(18), Constraint::Length(8), Constraint::Length(BAR_WIDTH as u16)])
+        .header(Row::new(vec!["buffer", "rows", "fill"]).style(Style::default().fg(Color::Cyan)))
+        .block(Block::default().borders(Borders::ALL).title(format!(" Buffers (flush at {}) ", batch_size)))
+}
+
+fn error_list(snapshot: &Snapshot) -> List<'static> {
+    let items = snapshot.errors.iter().map(|line| {
+        let color = if line.contains(" ERROR ") { Color::Red } else { Color::Yellow };
+        ListItem::new(line.clone()).style(Style::default().fg(color))
+    });
+    List::new(items).block(Block::default().borders(Borders::ALL).title(" Recent warnings and errors "))
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
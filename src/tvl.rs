@@ -0,0 +1,183 @@
+//! Pool TVL snapshot job
+//!
+//! Every `interval_slots` (by the RPC node's current slot), reads the token
+//! vault balances of every pool seen in `liquidity_positions` and writes one
+//! `pool_tvl_snapshots` row per pool. Balances are raw token amounts; pricing
+//! is left to queries. RPC only serves current state, so snapshots describe the
+//! chain at read time, not the slot range being backfilled.
+
+use crate::rpc::RpcClient;
+use crate::storage::{ClickHouseStorage, PoolTvlSnapshot};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// getMultipleAccounts accepts at most 100 keys per call
+const MAX_ACCOUNTS_PER_CALL: usize = 100;
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize)]
+struct RpcContextResult<T> {
+    context: RpcContext,
+    value: T,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcContext {
+    slot: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedAccount {
+    data: ParsedData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedData {
+    parsed: ParsedTokenAccount,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedTokenAccount {
+    info: TokenAccountInfo,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenAccountInfo {
+    mint: String,
+    token_amount: TokenAmount,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenAmount {
+    amount: String,
+    decimals: u8,
+}
+
+/// Vault balance: (mint, amount, decimals)
+type VaultBalance = (String, u64, u8);
+
+pub struct TvlSnapshotter {
+    rpc: Arc<RpcClient>,
+    storage: Arc<ClickHouseStorage>,
+    interval_slots: u64,
+}
+
+impl TvlSnapshotter {
+    pub fn new(rpc: Arc<RpcClient>, storage: Arc<ClickHouseStorage>, interval_slots: u64) -> Self {
+        Self {
+            rpc,
+            storage,
+            interval_slots,
+        }
+    }
+
+    /// Run until the task is dropped, snapshotting whenever the chain has
+    /// advanced `interval_slots` past the previous snapshot
+    pub async fn run(self) {
+        let mut last_snapshot_slot: Option<u64> = None;
+        loop {
+            let due = |slot: u64| match last_snapshot_slot {
+                Some(last) => slot >= last + self.interval_slots,
+                None => true,
+            };
+            match self.rpc.call::<u64>("getSlot", serde_json::json!([])).await {
+                Ok(Some(slot)) if due(slot) => {
+                    match self.snapshot().await {
+                        Ok(count) => {
+                            tracing::info!("Wrote TVL snapshots for {} pools at slot {}", count, slot);
+                            last_snapshot_slot = Some(slot);
+                        }
+                        Err(e) => tracing::warn!("TVL snapshot failed: {}", e),
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("TVL job failed to get current slot: {}", e),
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Take one snapshot of all known pools, returning the number of rows written
+    pub async fn snapshot(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let pools = self.storage.load_pool_vaults().await?;
+        if pools.is_empty() {
+            return Ok(0);
+        }
+
+        let vaults: Vec<&str> = pools
+            .iter()
+            .flat_map(|(_, _, a, b)| [a.as_str(), b.as_str()])
+            .collect();
+        let mut balances: HashMap<&str, VaultBalance> = HashMap::new();
+        let mut snapshot_slot = 0;
+        for chunk in vaults.chunks(MAX_ACCOUNTS_PER_CALL) {
+            let (slot, values) = self.fetch_balances(chunk).await?;
+            snapshot_slot = snapshot_slot.max(slot);
+            for (vault, balance) in chunk.iter().zip(values) {
+                if let Some(balance) = balance {
+                    balances.insert(vault, balance);
+                }
+            }
+        }
+
+        let snapshot_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let rows: Vec<PoolTvlSnapshot> = pools
+            .iter()
+            .filter_map(|(protocol_name, pool, vault_a, vault_b)| {
+                // Skip pools whose vaults were closed or aren't token accounts
+                let (mint_a, amount_a, decimals_a) = balances.get(vault_a.as_str())?.clone();
+                let (mint_b, amount_b, decimals_b) = balances.get(vault_b.as_str())?.clone();
+                Some(PoolTvlSnapshot {
+                    snapshot_slot,
+                    snapshot_time,
+                    protocol_name: protocol_name.clone(),
+                    pool: pool.clone(),
+                    vault_a: vault_a.clone(),
+                    vault_b: vault_b.clone(),
+                    mint_a,
+                    mint_b,
+                    amount_a,
+                    amount_b,
+                    decimals_a,
+                    decimals_b,
+                })
+            })
+            .collect();
+
+        self.storage.insert_tvl_snapshots(&rows).await?;
+        Ok(rows.len())
+    }
+
+    async fn fetch_balances(
+        &self,
+        vaults: &[&str],
+    ) -> Result<(u64, Vec<Option<VaultBalance>>), Box<dyn std::error::Error + Send + Sync>> {
+        let result: Option<RpcContextResult<Vec<Option<serde_json::Value>>>> = self
+            .rpc
+            .call(
+                "getMultipleAccounts",
+                serde_json::json!([vaults, { "encoding": "jsonParsed" }]),
+            )
+            .await?;
+        let Some(result) = result else {
+            return Ok((0, vec![None; vaults.len()]));
+        };
+        let balances = result
+            .value
+            .into_iter()
+            .map(|account| {
+                let account: ParsedAccount = serde_json::from_value(account?).ok()?;
+                let info = account.data.parsed.info;
+                let amount = info.token_amount.amount.parse().ok()?;
+                Some((info.mint, amount, info.token_amount.decimals))
+            })
+            .collect();
+        Ok((result.context.slot, balances))
+    }
+}
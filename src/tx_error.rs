@@ -0,0 +1,141 @@
+//! Failing instruction of an on-chain-failed transaction, from meta.status
+//!
+//! `TransactionError::InstructionError(index, error)` names the top-level
+//! instruction that aborted the transaction. Program-defined errors are
+//! `Custom(code)`; Anchor programs number theirs from 6000 (0x1770), so
+//! `0x1771` is an IDL's second error. Other transaction errors (blockhash
+//! not found, insufficient fee funds, ...) aren't tied to an instruction.
+
+use solana_instruction_error::InstructionError;
+use solana_transaction_error::TransactionError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionFailure {
+    /// Index of the failing top-level instruction
+    pub instruction_index: u8,
+    /// InstructionError variant, e.g. `Custom` or `InvalidAccountData`
+    pub error: &'static str,
+    /// Program error code for `Custom` errors
+    pub custom_code: Option<u32>,
+}
+
+impl InstructionFailure {
+    /// `custom program error 0x1771` for program errors, else the variant name
+    pub fn code(&self) -> String {
+        match self.custom_code {
+            Some(code) => format!("custom program error {:#x}", code),
+            None => self.error.to_string(),
+        }
+    }
+}
+
+/// The failing instruction and its error, if the transaction failed in an instruction
+///
+/// Only reported in the trace log of transactions skipped for failing on-chain.
+pub fn instruction_failure(status: &Result<(), TransactionError>) -> Option<InstructionFailure> {
+    let Err(TransactionError::InstructionError(index, error)) = status else { return None };
+    Some(InstructionFailure {
+        instruction_index: *index,
+        error: variant_name(error),
+        custom_code: match error {
+            InstructionError::Custom(code) => Some(*code),
+            _ => None,
+        },
+    })
+}
+
+/// Name of an InstructionError variant, without its fields
+fn variant_name(error: &InstructionError) -> &'static str {
+    use InstructionError::*;
+    match error {
+        Custom(_) => "Custom",
+        BorshIoError { .. } => "BorshIoError",
+        GenericError => "GenericError",
+        InvalidArgument => "InvalidArgument",
+        InvalidInstructionData => "InvalidInstructionData",
+        InvalidAccountData => "InvalidAccountData",
+        AccountDataTooSmall => "AccountDataTooSmall",
+        InsufficientFunds => "InsufficientFunds",
+        IncorrectProgramId => "IncorrectProgramId",
+        MissingRequiredSignature => "MissingRequiredSignature",
+        AccountAlreadyInitialized => "AccountAlreadyInitialized",
+        UninitializedAccount => "UninitializedAccount",
+        UnbalancedInstruction => "UnbalancedInstruction",
+        ModifiedProgramId => "ModifiedProgramId",
+        ExternalAccountLamportSpend => "ExternalAccountLamportSpend",
+        ExternalAccountDataModified => "ExternalAccountDataModified",
+        ReadonlyLamportChange => "ReadonlyLamportChange",
+        ReadonlyDataModified => "ReadonlyDataModified",
+        DuplicateAccountIndex => "DuplicateAccountIndex",
+        ExecutableModified => "ExecutableModified",
+        RentEpochModified => "RentEpochModified",
+        NotEnoughAccountKeys => "NotEnoughAccountKeys",
+        AccountDataSizeChanged => "AccountDataSizeChanged",
+        AccountNotExecutable => "AccountNotExecutable",
+        AccountBorrowFailed => "AccountBorrowFailed",
+        AccountBorrowOutstanding => "AccountBorrowOutstanding",
+        DuplicateAccountOutOfSync => "DuplicateAccountOutOfSync",
+        InvalidError => "InvalidError",
+        ExecutableDataModified => "ExecutableDataModified",
+        ExecutableLamportChange => "ExecutableLamportChange",
+        ExecutableAccountNotRentExempt => "ExecutableAccountNotRentExempt",
+        UnsupportedProgramId => "UnsupportedProgramId",
+        CallDepth => "CallDepth",
+        MissingAccount => "MissingAccount",
+        ReentrancyNotAllowed => "ReentrancyNotAllowed",
+        MaxSeedLengthExceeded => "MaxSeedLengthExceeded",
+        InvalidSeeds => "InvalidSeeds",
+        InvalidRealloc => "InvalidRealloc",
+        ComputationalBudgetExceeded => "ComputationalBudgetExceeded",
+        PrivilegeEscalation => "PrivilegeEscalation",
+        ProgramEnvironmentSetupFailure => "ProgramEnvironmentSetupFailure",
+        ProgramFailedToComplete => "ProgramFailedToComplete",
+        ProgramFailedToCompile => "ProgramFailedToCompile",
+        Immutable => "Immutable",
+        IncorrectAuthority => "IncorrectAuthority",
+        AccountNotRentExempt => "AccountNotRentExempt",
+        InvalidAccountOwner => "InvalidAccountOwner",
+        ArithmeticOverflow => "ArithmeticOverflow",
+        UnsupportedSysvar => "UnsupportedSysvar",
+        IllegalOwner => "IllegalOwner",
+        MaxAccountsDataAllocationsExceeded => "MaxAccountsDataAllocationsExceeded",
+        MaxAccountsExceeded => "MaxAccountsExceeded",
+        MaxInstructionTraceLengthExceeded => "MaxInstructionTraceLengthExceeded",
+        BuiltinProgramsMustConsumeComputeUnits => "BuiltinProgramsMustConsumeComputeUnits",
+        // Variants added by later solana-instruction-error releases
+        #[allow(unreachable_patterns)]
+        _ => "InstructionError",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_program_error() {
+        let status = Err(TransactionError::InstructionError(1, InstructionError::Custom(0x1771)));
+        let failure = instruction_failure(&status).unwrap();
+        assert_eq!(
+            failure,
+            InstructionFailure {
+                instruction_index: 1,
+                error: "Custom",
+                custom_code: Some(0x1771),
+            }
+        );
+        assert_eq!(failure.code(), "custom program error 0x1771");
+    }
+
+    #[test]
+    fn test_builtin_instruction_error() {
+        let status = Err(TransactionError::InstructionError(0, InstructionError::InvalidAccountData));
+        assert_eq!(instruction_failure(&status).unwrap().code(), "InvalidAccountData");
+    }
+
+    #[test]
+    fn test_errors_outside_instructions() {
+        assert_eq!(instruction_failure(&Err(TransactionError::BlockhashNotFound)), None);
+        assert_eq!(instruction_failure(&Ok(())), None);
+    }
+}
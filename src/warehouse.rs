@@ -0,0 +1,157 @@
+//! Staged Parquet export for BigQuery and Snowflake
+//!
+//! `--export-warehouse <bigquery|snowflake>` has ClickHouse write each
+//! selected table's rows in the slot range to `[export] destination` (S3 or
+//! GCS) as one Parquet file per table, then writes a SQL load script for the
+//! warehouse to `[export] manifest_dir`. Files are named after the slot range,
+//! so exporting consecutive ranges adds files instead of replacing them.
+//!
+//! GCS is written through its S3-compatible XML API, which needs an HMAC key
+//! (not a service account JSON key) in `access_key_id`/`secret_access_key`.
+//! BigQuery only loads from GCS; Snowflake reads either through a stage.
+
+use crate::config::ExportConfig;
+use crate::storage::{ClickHouseStorage, EVENT_TABLES, TABLES};
+use std::fmt::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Warehouse {
+    Bigquery,
+    Snowflake,
+}
+
+impl Warehouse {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bigquery => "bigquery",
+            Self::Snowflake => "snowflake",
+        }
+    }
+}
+
+/// One exported table file
+#[derive(Debug, Clone)]
+pub struct ExportedFile {
+    pub table: String,
+    /// Object URI in the bucket's own scheme (`s3://` or `gs://`)
+    pub uri: String,
+    /// Path below `destination`, as seen from a stage rooted there
+    pub path: String,
+    pub rows: u64,
+}
+
+/// Export the configured tables for [slot_start, slot_end), returning the
+/// files written and the path of the load script
+pub async fn export(
+    storage: &ClickHouseStorage,
+    config: &ExportConfig,
+    warehouse: Warehouse,
+    slot_start: u64,
+    slot_end: u64,
+) -> Result<(Vec<ExportedFile>, PathBuf), Box<dyn std::error::Error + Send + Sync>> {
+    let destination = config.destination.trim_end_matches('/');
+    if destination.is_empty() {
+        return Err("--export-warehouse requires [export] destination (or EXPORT_DESTINATION)".into());
+    }
+    let endpoint = https_endpoint(destination)?;
+    if warehouse == Warehouse::Bigquery && !destination.starts_with("gs://") {
+        tracing::warn!("BigQuery only loads from GCS; {} needs a transfer before LOAD DATA", destination);
+    }
+
+    let tables: Vec<&str> = if config.tables.is_empty() {
+        EVENT_TABLES.to_vec()
+    } else {
+        config.tables.iter().map(String::as_str).collect()
+    };
+    if let Some(unknown) = tables.iter().find(|t| !TABLES.contains(t)) {
+        return Err(format!("[export] tables: unknown table '{}'", unknown).into());
+    }
+
+    let mut files = Vec::new();
+    for table in tables {
+        let path = format!("{table}/{slot_start}-{slot_end}.parquet");
+        let rows = storage
+            .export_parquet(
+                table,
+                &format!("{endpoint}/{path}"),
+                &config.access_key_id,
+                &config.secret_access_key,
+                slot_start,
+                slot_end,
+            )
+            .await
+            .map_err(|e| format!("Failed to export {}: {}", table, e))?;
+        tracing::info!("Exported {} rows of {} to {}/{}", rows, table, destination, path);
+        files.push(ExportedFile {
+            table: table.to_string(),
+            uri: format!("{destination}/{path}"),
+            path,
+            rows,
+        });
+    }
+
+    let script = match warehouse {
+        Warehouse::Bigquery => bigquery_script(&config.bigquery_dataset, &files),
+        Warehouse::Snowflake => snowflake_script(&config.snowflake_stage, destination, &files),
+    };
+    let dir = PathBuf::from(&config.manifest_dir);
+    std::fs::create_dir_all(&dir)?;
+    let manifest = dir.join(format!("{}_{}_{}.sql", warehouse.as_str(), slot_start, slot_end));
+    std::fs::write(&manifest, script)?;
+    Ok((files, manifest))
+}
+
+/// HTTPS endpoint of an `s3://` or `gs://` URL for ClickHouse's s3() function
+/// (other URLs, e.g. MinIO or R2 endpoints, are used as-is)
+fn https_endpoint(destination: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(rest) = destination.strip_prefix("s3://") {
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        Ok(format!("https://{bucket}.s3.amazonaws.com/{prefix}").trim_end_matches('/').to_string())
+    } else if let Some(rest) = destination.strip_prefix("gs://") {
+        Ok(format!("https://storage.googleapis.com/{rest}"))
+    } else if destination.starts_with("https://") || destination.starts_with("http://") {
+        Ok(destination.to_string())
+    } else {
+        Err(format!("[export] destination must be s3://, gs:// or http(s)://, got {}", destination).into())
+    }
+}
+
+/// `LOAD DATA` statements; BigQuery creates missing tables from the Parquet schema
+fn bigquery_script(dataset: &str, files: &[ExportedFile]) -> String {
+    let mut out = String::from("-- SolixDB export: run with `bq query --use_legacy_sql=false < this_file`\n");
+    for file in files {
+        let _ = writeln!(
+            out,
+            "\n-- {} rows\nLOAD DATA INTO `{}.{}`\nFROM FILES (format = 'PARQUET', uris = ['{}']);",
+            file.rows, dataset, file.table, file.uri
+        );
+    }
+    out
+}
+
+/// Stage setup plus `CREATE TABLE ... USING TEMPLATE` and `COPY INTO` per table
+fn snowflake_script(stage: &str, destination: &str, files: &[ExportedFile]) -> String {
+    let mut out = format!(
+        "-- SolixDB export: run in the target database and schema\n\
+         CREATE FILE FORMAT IF NOT EXISTS solixdb_parquet TYPE = PARQUET;\n\
+         -- Add CREDENTIALS or STORAGE_INTEGRATION for a private bucket\n\
+         CREATE STAGE IF NOT EXISTS {stage} URL = '{destination}/' FILE_FORMAT = solixdb_parquet;\n"
+    );
+    for file in files {
+        let _ = writeln!(
+            out,
+            "\n-- {rows} rows\n\
+             CREATE TABLE IF NOT EXISTS {table} USING TEMPLATE (\n    \
+             SELECT ARRAY_AGG(OBJECT_CONSTRUCT(*)) FROM TABLE(INFER_SCHEMA(\n        \
+             LOCATION => '@{stage}/{path}', FILE_FORMAT => 'solixdb_parquet'))\n\
+             );\n\
+             COPY INTO {table} FROM @{stage}/{path}\n    \
+             FILE_FORMAT = (FORMAT_NAME = 'solixdb_parquet') MATCH_BY_COLUMN_NAME = CASE_INSENSITIVE;",
+            rows = file.rows,
+            table = file.table,
+            path = file.path,
+        );
+    }
+    out
+}
@@ -0,0 +1,231 @@
+//! Flushed slot watermark
+//!
+//! Tracks the lowest slot that may still be missing rows in ClickHouse, so
+//! downstream consumers know up to which slot the data is complete. Every slot
+//! below the watermark has been processed by its firehose thread and all of its
+//! rows (across every table) have been written.
+//!
+//! Two things hold it back: firehose threads, each of which walks its share of
+//! the range in slot order, and rows handed to storage that are still buffered.
+//! A thread's share isn't known up front, so until every thread has reported a
+//! slot the watermark stays at the start of the range, and a thread that has
+//! finished its share holds it until the whole firehose call (or autoscale
+//! chunk) returns.
+//!
+//! Pending rows are also tracked per table, so each table has a watermark of
+//! its own ([`Watermark::table_slot`]): a table that is flushed often can be
+//! complete further than the slowest buffer. With `[clickhouse]
+//! commit_watermarks`, storage writes these to the commit_watermark table.
+//! Each table's buffered rows and the age of its oldest are exported too
+//! (see `buffer_age`).
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Unwritten rows of one table and slot
+#[derive(Debug)]
+struct Pending {
+    rows: u64,
+    /// When the first of them was handed to storage
+    since: Instant,
+}
+
+/// Unwritten rows of a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BufferedTable {
+    pub table: &'static str,
+    pub rows: u64,
+    /// How long the oldest has been waiting (zero without rows)
+    pub oldest: Duration,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Start of the firehose range being processed; everything below it is handed off
+    range_start: u64,
+    /// Threads the current firehose call was started with
+    threads: usize,
+    /// Slot each firehose thread is currently processing
+    thread_slots: HashMap<usize, u64>,
+    /// Rows handed to storage but not yet written, by table and slot (a table
+    /// stays listed once it has had rows)
+    pending: HashMap<&'static str, BTreeMap<u64, Pending>>,
+    /// Slot last recorded in commit_watermark, by table
+    committed: HashMap<&'static str, u64>,
+    /// Highest slot any firehose thread has reached
+    last_slot: Option<u64>,
+}
+
+impl State {
+    /// Every slot below this one was handed to storage by the firehose threads
+    fn handed_off(&self) -> u64 {
+        match self.thread_slots.values().min() {
+            Some(min) if self.threads > 0 && self.thread_slots.len() >= self.threads => (*min).max(self.range_start),
+            _ => self.range_start,
+        }
+    }
+
+    fn table_slot(&self, table: &str) -> u64 {
+        match self.pending.get(table).and_then(|slots| slots.keys().next()) {
+            Some(pending) => self.handed_off().min(*pending),
+            None => self.handed_off(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Watermark {
+    state: Mutex<State>,
+}
+
+impl Watermark {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A firehose call is starting on `[start, ..)` with `threads` threads
+    pub fn begin_range(&self, start: u64, threads: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.range_start = start;
+        state.threads = threads;
+        state.thread_slots.clear();
+    }
+
+    /// The firehose call returned having processed every slot below `end`
+    pub fn end_range(&self, end: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.range_start = end;
+        state.threads = 0;
+        state.thread_slots.clear();
+    }
+
+    /// A firehose thread moved on to `slot` (it has handed off everything below it)
+    pub fn thread_at(&self, thread_id: usize, slot: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.thread_slots.insert(thread_id, slot);
+        state.last_slot = state.last_slot.max(Some(slot));
+    }
+
+    /// Highest slot processed so far (threads run ahead of the watermark)
+    pub fn last_slot(&self) -> Option<u64> {
+        self.state.lock().unwrap().last_slot
+    }
+
+    /// Rows of `table` for `slot` were handed to storage
+    pub(crate) fn add_pending(&self, table: &'static str, slot: u64, rows: usize) {
+        let mut state = self.state.lock().unwrap();
+        let pending = state.pending.entry(table).or_default().entry(slot).or_insert_with(|| Pending {
+            rows: 0,
+            since: Instant::now(),
+        });
+        pending.rows += rows as u64;
+    }
+
+    /// Rows of `table` with these slots were written
+    pub(crate) fn release(&self, table: &str, slots: impl IntoIterator<Item = u64>) {
+        let mut written: BTreeMap<u64, u64> = BTreeMap::new();
+        for slot in slots {
+            *written.entry(slot).or_default() += 1;
+        }
+        let mut state = self.state.lock().unwrap();
+        let Some(pending) = state.pending.get_mut(table) else { return };
+        for (slot, rows) in written {
+            if let Some(count) = pending.get_mut(&slot) {
+                count.rows = count.rows.saturating_sub(rows);
+                if count.rows == 0 {
+                    pending.remove(&slot);
+                }
+            }
+        }
+    }
+
+    /// Every slot below the returned one was processed by its firehose thread
+    /// (its rows may still be buffered)
+    pub fn handed_off(&self) -> u64 {
+        self.state.lock().unwrap().handed_off()
+    }
+
+    /// Every slot below the returned one is fully written
+    pub fn slot(&self) -> u64 {
+        let state = self.state.lock().unwrap();
+        let lowest_pending = state.pending.values().filter_map(|slots| slots.keys().next()).min();
+        match lowest_pending {
+            Some(pending) => state.handed_off().min(*pending),
+            None => state.handed_off(),
+        }
+    }
+
+    /// Every row of `table` below the returned slot is written
+    pub fn table_slot(&self, table: &str) -> u64 {
+        let state = self.state.lock().unwrap();
+        state.table_slot(table)
+    }
+
+    /// Unwritten rows of every table that has had rows, by table name
+    pub fn buffered(&self) -> Vec<BufferedTable> {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let mut tables: Vec<_> = state
+            .pending
+            .iter()
+            .map(|(&table, slots)| BufferedTable {
+                table,
+                rows: slots.values().map(|pending| pending.rows).sum(),
+                oldest: slots
+                    .values()
+                    .map(|pending| now.duration_since(pending.since))
+                    .max()
+                    .unwrap_or_default(),
+            })
+            .collect();
+        tables.sort_by_key(|buffered| buffered.table);
+        tables
+    }
+
+    /// Tables whose watermark moved past the slot last recorded for them
+    pub(crate) fn uncommitted(&self) -> Vec<(&'static str, u64)> {
+        let state = self.state.lock().unwrap();
+        let mut tables: Vec<_> = state
+            .pending
+            .keys()
+            .map(|&table| (table, state.table_slot(table)))
+            .filter(|(table, slot)| state.committed.get(table).is_none_or(|committed| slot > committed))
+            .collect();
+        tables.sort();
+        tables
+    }
+
+    /// `table`'s watermark was recorded at `slot`
+    pub(crate) fn committed(&self, table: &'static str, slot: u64) {
+        let mut state = self.state.lock().unwrap();
+        let committed = state.committed.entry(table).or_default();
+        *committed = (*committed).max(slot);
+    }
+
+    /// Render in Prometheus text exposition format
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP solixdb_flushed_slot_watermark Every slot below this one is fully written to ClickHouse\n");
+        out.push_str("# TYPE solixdb_flushed_slot_watermark gauge\n");
+        let _ = writeln!(out, "solixdb_flushed_slot_watermark {}", self.slot());
+        let buffered = self.buffered();
+        out.push_str("# HELP solixdb_buffered_rows Rows handed to storage but not yet written\n");
+        out.push_str("# TYPE solixdb_buffered_rows gauge\n");
+        for table in &buffered {
+            let _ = writeln!(out, "solixdb_buffered_rows{{table=\"{}\"}} {}", table.table, table.rows);
+        }
+        out.push_str("# HELP solixdb_buffer_oldest_row_age_seconds How long the oldest unwritten row has waited\n");
+        out.push_str("# TYPE solixdb_buffer_oldest_row_age_seconds gauge\n");
+        for table in &buffered {
+            let _ = writeln!(
+                out,
+                "solixdb_buffer_oldest_row_age_seconds{{table=\"{}\"}} {:.1}",
+                table.table,
+                table.oldest.as_secs_f64()
+            );
+        }
+        out
+    }
+}
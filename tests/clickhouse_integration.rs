@@ -0,0 +1,383 @@
+//! End-to-end tests: fixture transactions -> process_transaction -> ClickHouse rows
+//!
+//! Spins up ClickHouse with testcontainers, so Docker is required:
+//! `cargo test --test clickhouse_integration -- --ignored`
+
+mod common;
+
+use clickhouse::Client;
+use solixdb_indexer::buffer_age;
+use solixdb_indexer::helpers::process_transaction;
+use solixdb_indexer::storage::{ClickHouseStorage, IndexedRange, RangeCheckpoint, StorageOptions, Trade, Transaction};
+use std::sync::Arc;
+use std::time::Duration;
+use testcontainers_modules::clickhouse::ClickHouse;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::ContainerAsync;
+
+async fn start_clickhouse() -> (ContainerAsync<ClickHouse>, String) {
+    let container = ClickHouse::default()
+        .start()
+        .await
+        .expect("Failed to start ClickHouse container");
+    let port = container
+        .get_host_port_ipv4(8123)
+        .await
+        .expect("ClickHouse HTTP port not mapped");
+    (container, format!("http://127.0.0.1:{}", port))
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_successful_parse_writes_transaction_row() {
+    let (_container, url) = start_clickhouse().await;
+    let storage = Arc::new(ClickHouseStorage::new_with_clear(&url, StorageOptions::default()).await.unwrap());
+    let ctx = common::processing_context(storage.clone());
+
+    let tx = common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000);
+    let signature = tx.signature.to_string();
+    process_transaction(tx, &ctx).await.unwrap();
+    storage.flush_all().await.unwrap();
+
+    let health = storage.table_health().table("transactions");
+    assert!(health.last_success_at.is_some());
+    assert_eq!(health.failed_attempts, 0);
+    assert_eq!(health.last_error, None);
+
+    let client = Client::default().with_url(&url);
+    let rows: Vec<Transaction> = client
+        .query("SELECT ?fields FROM transactions")
+        .fetch_all()
+        .await
+        .unwrap();
+
+    assert_eq!(rows.len(), 1);
+    let row = &rows[0];
+    assert_eq!(row.signature, signature);
+    assert_eq!(row.instruction_index, 0);
+    assert_eq!(row.slot, 377107390);
+    assert_eq!(&*row.program_id, common::PUMP_FUN_PROGRAM_ID);
+    assert_eq!(&*row.protocol_name, "pump_fun");
+    assert_eq!(&*row.instruction_type, "Sell");
+    assert_eq!(&*row.parser, "pump_fun");
+    assert_eq!(row.success, 1);
+    assert_eq!(row.fee, 5000);
+    assert_eq!(row.compute_units, 42_000);
+    assert_eq!(row.accounts_count, 14);
+    assert_eq!(row.data_len, 24);
+    assert_eq!(row.writable_accounts_count, 14);
+    assert_eq!(row.signer_accounts_count, 1);
+    assert_eq!(row.epoch, 872);
+    assert_eq!(row.leader, "");
+    assert_eq!(row.protocol_id, 4);
+    assert_eq!(row.instruction_type_id, solixdb_decoder::registry::instruction_type_id("Sell"));
+
+    let registered: String = client
+        .query("SELECT name FROM protocol_registry FINAL WHERE kind = 'instruction_type' AND protocol_id = 4")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(registered, "Sell");
+
+    let failed: u64 = client
+        .query("SELECT count() FROM failed_transactions")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(failed, 0);
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_parse_failure_writes_classified_failed_row() {
+    let (_container, url) = start_clickhouse().await;
+    let storage = Arc::new(ClickHouseStorage::new_with_clear(&url, StorageOptions::default()).await.unwrap());
+    let ctx = common::processing_context(storage.clone());
+
+    let tx = common::pump_fun_unknown_tx(377107391, 2);
+    let signature = tx.signature.to_string();
+    process_transaction(tx, &ctx).await.unwrap();
+    storage.flush_all().await.unwrap();
+
+    let client = Client::default().with_url(&url);
+    let rows: Vec<(String, u64, String, String, String)> = client
+        .query("SELECT signature, slot, protocol_name, raw_data, error_class FROM failed_transactions")
+        .fetch_all()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        rows,
+        vec![(
+            signature,
+            377107391,
+            "pump_fun".to_string(),
+            "deadbeef00000000".to_string(),
+            "DiscriminatorMismatch".to_string(),
+        )]
+    );
+
+    let transactions: u64 = client
+        .query("SELECT count() FROM transactions")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(transactions, 0);
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_write_groups_flush_without_orphans() {
+    let (_container, url) = start_clickhouse().await;
+    let options = StorageOptions {
+        write_groups: true,
+        ..StorageOptions::default()
+    };
+    let storage = Arc::new(ClickHouseStorage::new_with_clear(&url, options).await.unwrap());
+    let ctx = common::processing_context(storage.clone());
+    storage.watermark().begin_range(377107390, 1);
+
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+    process_transaction(common::pump_fun_unknown_tx(377107391, 2), &ctx)
+        .await
+        .unwrap();
+    storage.watermark().thread_at(0, 377107392);
+    // Both slots are handed off but still buffered
+    assert_eq!(storage.watermark().slot(), 377107390);
+
+    let client = Client::default().with_url(&url);
+    let buffered: u64 = client
+        .query("SELECT count() FROM transactions")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(buffered, 0);
+
+    storage.flush_all().await.unwrap();
+
+    let (transactions, failed): (u64, u64) = client
+        .query("SELECT (SELECT count() FROM transactions), (SELECT count() FROM failed_transactions)")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!((transactions, failed), (1, 1));
+    assert!(storage.find_orphaned_rows(377107390, 377107392).await.unwrap().is_empty());
+    assert!(storage.validate_run(377107390, 377107392).await.unwrap().is_empty());
+    assert_eq!(storage.watermark().slot(), 377107392);
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_aged_buffers_are_flushed() {
+    let (_container, url) = start_clickhouse().await;
+    let storage = Arc::new(ClickHouseStorage::new_with_clear(&url, StorageOptions::default()).await.unwrap());
+    let ctx = common::processing_context(storage.clone());
+
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+    let buffered = storage.watermark().buffered();
+    assert!(buffered.iter().any(|table| table.table == "transactions" && table.rows == 1));
+
+    let watch = tokio::spawn(buffer_age::run(storage.clone(), Duration::from_secs(1)));
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    watch.abort();
+
+    assert!(storage.watermark().buffered().iter().all(|table| table.rows == 0));
+    let transactions: u64 = Client::default()
+        .with_url(&url)
+        .query("SELECT count() FROM transactions")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(transactions, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_commit_watermarks_follow_flushes() {
+    let (_container, url) = start_clickhouse().await;
+    let options = StorageOptions {
+        commit_watermarks: true,
+        flush_buckets: 2,
+        ..StorageOptions::default()
+    };
+    let storage = Arc::new(ClickHouseStorage::new_with_clear(&url, options).await.unwrap());
+    let ctx = common::processing_context(storage.clone());
+    storage.watermark().begin_range(377107390, 1);
+
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+    process_transaction(common::pump_fun_sell_tx(377107391, 2, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+    storage.watermark().thread_at(0, 377107392);
+    assert_eq!(storage.watermark().table_slot("transactions"), 377107390);
+
+    storage.flush_all().await.unwrap();
+    assert_eq!(storage.watermark().table_slot("transactions"), 377107392);
+
+    let client = Client::default().with_url(&url);
+    let committed: u64 = client
+        .query("SELECT argMax(slot, committed_at) FROM commit_watermark WHERE table_name = 'transactions'")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(committed, 377107392);
+    let transactions: u64 = client
+        .query("SELECT count() FROM transactions")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(transactions, 2);
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_vacuum_keeps_checkpoints_of_unfinished_runs() {
+    let (_container, url) = start_clickhouse().await;
+    let storage = ClickHouseStorage::new_with_clear(&url, StorageOptions::default()).await.unwrap();
+
+    for (run_id, status, slot_start) in [("done", "complete", 0), ("crashed", "failed", 1000)] {
+        let range = IndexedRange {
+            run_id: run_id.to_string(),
+            slot_start,
+            slot_end: slot_start + 1000,
+            parser_set: "pump_fun".to_string(),
+            status: status.to_string(),
+            started_at: 1,
+            updated_at: 2,
+        };
+        storage.record_range(&range).await.unwrap();
+        let checkpoint = RangeCheckpoint {
+            parser_set: "pump_fun".to_string(),
+            slot_start,
+            slot_end: slot_start + 500,
+            run_id: run_id.to_string(),
+            completed_at: 0,
+        };
+        storage.record_checkpoint(checkpoint).await.unwrap();
+    }
+
+    // Both checkpoints are past any cutoff; only the complete run's goes
+    storage.vacuum_checkpoints(u64::MAX).await.unwrap();
+    let remaining = storage.load_checkpoints(0, 2000, "pump_fun").await.unwrap();
+    assert_eq!(remaining, vec![(1000, 1500)]);
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_unreachable_primary_fails_over() {
+    let (_container, url) = start_clickhouse().await;
+    let options = StorageOptions {
+        failover_urls: vec![url.clone()],
+        ..StorageOptions::default()
+    };
+    // Nothing listens on the primary
+    let storage = Arc::new(ClickHouseStorage::new_with_clear("http://127.0.0.1:9", options).await.unwrap());
+    assert_eq!(storage.endpoints().active(), 1);
+    assert_eq!(storage.endpoints().failovers(), 1);
+
+    let ctx = common::processing_context(storage.clone());
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+    storage.flush_all().await.unwrap();
+
+    let transactions: u64 = Client::default()
+        .with_url(&url)
+        .query("SELECT count() FROM transactions")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(transactions, 1);
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_wallet_pnl_is_fifo() {
+    let (_container, url) = start_clickhouse().await;
+    let storage = ClickHouseStorage::new_with_clear(&url, StorageOptions::default()).await.unwrap();
+    let trade = |slot: u64, side: &str, token_amount: u64, sol_amount: u64| Trade {
+        signature: format!("sig{}", slot),
+        slot,
+        block_time: 1_700_000_000 + slot,
+        protocol_name: "pump_fun".to_string(),
+        launchpad: "pump_fun".to_string(),
+        venue: "bonding_curve".to_string(),
+        side: side.to_string(),
+        mint: "mint".to_string(),
+        bonding_curve: "curve".to_string(),
+        user: "wallet".to_string(),
+        token_amount,
+        sol_amount,
+        token_amount_ui: None,
+        sol_amount_ui: None,
+        exact_tokens: 1,
+    };
+    // 100 tokens at 1 SOL each, 100 at 3, then 150 sold at 4: basis 100 + 150
+    for row in [
+        trade(10, "buy", 100, 100),
+        trade(11, "buy", 100, 300),
+        trade(12, "sell", 150, 600),
+    ] {
+        storage.insert_trade(row).await.unwrap();
+    }
+    storage.flush_all().await.unwrap();
+
+    assert_eq!(storage.build_wallet_pnl(0, 100).await.unwrap(), 1);
+    let (realized, open_tokens, open_cost): (i64, u64, u64) = Client::default()
+        .with_url(&url)
+        .query("SELECT realized_pnl, open_tokens, open_cost FROM wallet_pnl FINAL WHERE wallet = 'wallet'")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!((realized, open_tokens, open_cost), (350, 50, 150));
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_schema_check_rejects_mismatched_tables() {
+    let (_container, url) = start_clickhouse().await;
+    ClickHouseStorage::new_with_clear(&url, StorageOptions::default()).await.unwrap();
+    ClickHouseStorage::new(&url, StorageOptions::default()).await.unwrap();
+
+    // A column the swaps migration adds back is fine, and is added back
+    let client = Client::default().with_url(&url);
+    client.query("ALTER TABLE swaps DROP COLUMN fee_rate").execute().await.unwrap();
+    ClickHouseStorage::new(&url, StorageOptions::default()).await.unwrap();
+    let fee_rate: u64 = client
+        .query("SELECT count() FROM system.columns WHERE database = currentDatabase() AND table = 'swaps' AND name = 'fee_rate'")
+        .fetch_one()
+        .await
+        .unwrap();
+    assert_eq!(fee_rate, 1);
+
+    client.query("ALTER TABLE swaps DROP COLUMN exact_in").execute().await.unwrap();
+    let error = ClickHouseStorage::new(&url, StorageOptions::default()).await.err().unwrap().to_string();
+    assert!(error.contains("swaps.exact_in: missing (expected UInt8)"), "{}", error);
+
+    let options = StorageOptions {
+        strict_schema: false,
+        ..StorageOptions::default()
+    };
+    ClickHouseStorage::new(&url, options).await.unwrap();
+}
+
+#[tokio::test]
+#[ignore = "requires Docker"]
+async fn test_dumped_schema_passes_schema_check() {
+    let (_container, url) = start_clickhouse().await;
+    let statements = ClickHouseStorage::dump_schema(StorageOptions::default()).await.unwrap();
+    assert!(statements.iter().any(|s| s.starts_with("CREATE TABLE IF NOT EXISTS transactions")));
+
+    // Tables pre-created from the dump are the ones the indexer expects
+    let client = Client::default().with_url(&url);
+    for statement in &statements {
+        client.query(statement).execute().await.unwrap();
+    }
+    ClickHouseStorage::new(&url, StorageOptions::default()).await.unwrap();
+}
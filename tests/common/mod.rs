@@ -0,0 +1,161 @@
+//! Shared fixtures for integration tests
+
+use jetstreamer_firehose::firehose::TransactionData;
+use solana_address::Address;
+use solana_hash::Hash;
+use solana_message::compiled_instruction::CompiledInstruction;
+use solana_message::{legacy, MessageHeader, VersionedMessage};
+use solana_signature::Signature;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction_status_client_types::TransactionStatusMeta;
+use solixdb_indexer::alt::AltCache;
+use solixdb_indexer::creators::FirstBuyers;
+use solixdb_indexer::decimals::TokenDecimals;
+use solixdb_indexer::discriminators::UnknownDiscriminators;
+use solixdb_indexer::failed_rows::FailedRowLimiter;
+use solixdb_indexer::filters::AccountFilter;
+use solixdb_indexer::helpers::ProcessingContext;
+use solixdb_indexer::intern::Interner;
+use solixdb_indexer::mints::MintCounter;
+use solixdb_indexer::multi_parser::{build_parser_map, lifecycle_programs};
+use solixdb_indexer::network::Network;
+use solixdb_indexer::payload_limits::PayloadLimits;
+use solixdb_indexer::priority::ProtocolPriority;
+use solixdb_indexer::stage_metrics::StageMetrics;
+use solixdb_indexer::storage::Storage;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+
+pub const PUMP_FUN_PROGRAM_ID: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+/// pump_fun `sell` discriminator from idls/pump_fun.json
+pub const PUMP_FUN_SELL_DISCRIMINATOR: [u8; 8] = [0x33, 0xe6, 0x85, 0xa4, 0x01, 0x7f, 0x83, 0xad];
+
+pub fn address(byte: u8) -> Address {
+    Address::from([byte; 32])
+}
+
+pub fn program_address(program_id: &str) -> Address {
+    solixdb_indexer::alt::parse_address(program_id).expect("valid program id")
+}
+
+/// A successful legacy transaction with a single instruction invoking `program_id`
+/// with `account_count` accounts (fee payer is account 0)
+pub fn single_instruction_tx(
+    slot: u64,
+    signature_byte: u8,
+    program_id: &str,
+    account_count: u8,
+    data: Vec<u8>,
+) -> TransactionData {
+    let accounts = (0..account_count).map(|i| address(i + 1)).collect();
+    instruction_tx(slot, signature_byte, program_id, accounts, data)
+}
+
+/// Like [`single_instruction_tx`], with the instruction's accounts given
+pub fn instruction_tx(
+    slot: u64,
+    signature_byte: u8,
+    program_id: &str,
+    accounts: Vec<Address>,
+    data: Vec<u8>,
+) -> TransactionData {
+    let account_count = accounts.len() as u8;
+    let mut account_keys = accounts;
+    account_keys.push(program_address(program_id));
+    let program_id_index = account_count;
+
+    let message = legacy::Message {
+        header: MessageHeader {
+            num_required_signatures: 1,
+            num_readonly_signed_accounts: 0,
+            num_readonly_unsigned_accounts: 1,
+        },
+        account_keys,
+        recent_blockhash: Hash::default(),
+        instructions: vec![CompiledInstruction {
+            program_id_index,
+            accounts: (0..account_count).collect(),
+            data,
+        }],
+    };
+
+    let signature = Signature::from([signature_byte; 64]);
+    TransactionData {
+        slot,
+        transaction_slot_index: 0,
+        signature,
+        message_hash: Hash::default(),
+        is_vote: false,
+        transaction_status_meta: TransactionStatusMeta {
+            fee: 5000,
+            compute_units_consumed: Some(42_000),
+            ..Default::default()
+        },
+        transaction: VersionedTransaction {
+            signatures: vec![signature],
+            message: VersionedMessage::Legacy(message),
+        },
+    }
+}
+
+/// A pump_fun `sell` that parses successfully
+pub fn pump_fun_sell_tx(slot: u64, signature_byte: u8, amount: u64, min_sol_output: u64) -> TransactionData {
+    let mut data = PUMP_FUN_SELL_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min_sol_output.to_le_bytes());
+    single_instruction_tx(slot, signature_byte, PUMP_FUN_PROGRAM_ID, 14, data)
+}
+
+/// A pump_fun instruction with an unknown discriminator (lands in failed_transactions)
+pub fn pump_fun_unknown_tx(slot: u64, signature_byte: u8) -> TransactionData {
+    single_instruction_tx(slot, signature_byte, PUMP_FUN_PROGRAM_ID, 3, vec![0xde, 0xad, 0xbe, 0xef, 0, 0, 0, 0])
+}
+
+pub fn processing_context(storage: Arc<dyn Storage>) -> ProcessingContext {
+    let parser_map = build_parser_map();
+    let metrics = parser_map
+        .values()
+        .flatten()
+        .map(|name| {
+            (
+                name.to_string(),
+                (Arc::new(AtomicU64::new(0)), Arc::new(AtomicU64::new(0))),
+            )
+        })
+        .collect();
+    ProcessingContext {
+        network: Network::solana(),
+        slot_times: None,
+        parser_map,
+        lifecycle_programs: lifecycle_programs(),
+        transfer_programs: None,
+        metrics,
+        protocols: HashMap::new(),
+        protocol_priority: ProtocolPriority::default(),
+        account_filter: AccountFilter::default(),
+        named_accounts: false,
+        log_invocations: false,
+        raw_data_encoding: Default::default(),
+        interner: Interner::new(),
+        alt_cache: AltCache::new(),
+        alt_fetcher: None,
+        leader_schedule: None,
+        fixture_capture: None,
+        first_buyers: FirstBuyers::new(),
+        new_mints: MintCounter::new(),
+        decimals: TokenDecimals::new(),
+        hourly_summary: None,
+        daily_users: None,
+        cooccurrence: None,
+        first_seen: None,
+        pools: None,
+        unknown_discriminators: UnknownDiscriminators::new(0),
+        failed_rows: FailedRowLimiter::unlimited(),
+        payload_limits: PayloadLimits::unlimited(),
+        parse_pool: None,
+        stage_metrics: StageMetrics::new(),
+        storage,
+    }
+}
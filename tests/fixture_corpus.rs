@@ -0,0 +1,48 @@
+//! Replays captured parser fixtures (`--capture-fixtures`) and checks the
+//! outcome still matches what was recorded at capture time
+
+use solixdb_indexer::fixtures::InstructionFixture;
+use solixdb_indexer::multi_parser::{build_parser_map, try_parse_candidates};
+use std::path::Path;
+
+#[tokio::test]
+async fn test_fixture_corpus() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let fixtures = InstructionFixture::load_dir(&dir).unwrap();
+    // An empty corpus would pass without replaying anything
+    assert!(!fixtures.is_empty(), "no fixtures under {}", dir.display());
+    let parser_map = build_parser_map();
+
+    let mut mismatches = Vec::new();
+    for (path, fixture) in &fixtures {
+        let update = fixture.to_instruction_update().unwrap();
+        let candidates = parser_map
+            .get(update.program.as_slice())
+            .unwrap_or_else(|| panic!("{}: no parser for program {}", path.display(), fixture.program_id));
+        let outcome = try_parse_candidates(&update, candidates).await.map(|(_, parsed)| parsed);
+        match (&fixture.instruction_type, outcome) {
+            (Some(expected), Ok(parsed)) if parsed.instruction_type() == *expected => {}
+            (None, Err(_)) => {}
+            (expected, Ok(parsed)) => mismatches.push(format!(
+                "{}: expected {:?}, parsed as {}",
+                path.display(),
+                expected.as_deref().unwrap_or("<error>"),
+                parsed.instruction_type()
+            )),
+            (expected, Err(e)) => mismatches.push(format!(
+                "{}: expected {:?}, failed with {:?}",
+                path.display(),
+                expected,
+                e
+            )),
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "{} of {} fixtures changed outcome:\n{}",
+        mismatches.len(),
+        fixtures.len(),
+        mismatches.join("\n")
+    );
+}
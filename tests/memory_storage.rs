@@ -0,0 +1,453 @@
+//! Fixture transactions -> process_transaction -> MemoryStorage rows
+//!
+//! Runs without Docker; the ClickHouse path is covered by clickhouse_integration.
+
+mod common;
+
+use solixdb_indexer::helpers::process_transaction;
+use solixdb_indexer::memory::MemoryStorage;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_memory_storage_records_rows() {
+    let storage = Arc::new(MemoryStorage::new());
+    let ctx = common::processing_context(storage.clone());
+
+    let sell = common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000);
+    let sell_signature = sell.signature.to_string();
+    process_transaction(sell, &ctx).await.unwrap();
+    process_transaction(common::pump_fun_unknown_tx(377107391, 2), &ctx)
+        .await
+        .unwrap();
+
+    let rows = storage.transactions_for(&sell_signature);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(&*rows[0].protocol_name, "pump_fun");
+    assert_eq!(&*rows[0].instruction_type, "Sell");
+    assert_eq!(&*rows[0].resolution_source, "meta");
+    let fee_payer = solixdb_indexer::alt::address_to_string(&common::address(1));
+    assert_eq!(rows[0].fee_payer, fee_payer);
+    assert_eq!(rows[0].signers, vec![fee_payer]);
+    assert_eq!(storage.row_count("transactions"), 1);
+
+    let failed = storage.failed();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].slot, 377107391);
+    assert_eq!(failed[0].error_class, "DiscriminatorMismatch");
+
+    assert_eq!(
+        storage.instruction_types(),
+        vec![(4, solixdb_decoder::registry::instruction_type_id("Sell"), "Sell".to_string())]
+    );
+}
+
+#[tokio::test]
+async fn test_failed_raw_data_encoding() {
+    use solixdb_indexer::raw_data::RawDataEncoding;
+
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    ctx.raw_data_encoding = RawDataEncoding::Zstd;
+
+    process_transaction(common::pump_fun_unknown_tx(377107391, 2), &ctx)
+        .await
+        .unwrap();
+
+    let failed = storage.failed();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(&*failed[0].raw_data_encoding, "zstd");
+    let encoding = RawDataEncoding::parse(&failed[0].raw_data_encoding).unwrap();
+    assert_eq!(
+        encoding.decode(&failed[0].raw_data),
+        Some(vec![0xde, 0xad, 0xbe, 0xef, 0, 0, 0, 0])
+    );
+    assert_eq!(RawDataEncoding::parse(""), Some(RawDataEncoding::Hex));
+}
+
+#[tokio::test]
+async fn test_network_program_id_override() {
+    use solixdb_indexer::config::NetworkConfig;
+    use solixdb_indexer::multi_parser::build_parser_map;
+    use solixdb_indexer::network::Network;
+
+    let program_id = solixdb_indexer::alt::address_to_string(&common::address(0x42));
+    let network = Network::from_config(&NetworkConfig {
+        name: "eclipse".to_string(),
+        genesis_timestamp: Some(1_700_000_000),
+        slots_per_epoch: 400,
+        program_ids: [("pump_fun".to_string(), program_id.clone())].into(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    ctx.parser_map = network.parser_map(build_parser_map()).unwrap();
+    ctx.network = network;
+
+    let mut data = common::PUMP_FUN_SELL_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&1_000_000u64.to_le_bytes());
+    data.extend_from_slice(&5_000u64.to_le_bytes());
+    let moved = common::single_instruction_tx(1_000, 1, &program_id, 14, data.clone());
+    let moved_signature = moved.signature.to_string();
+    let mainnet = common::single_instruction_tx(1_000, 2, common::PUMP_FUN_PROGRAM_ID, 14, data);
+    let mainnet_signature = mainnet.signature.to_string();
+    process_transaction(moved, &ctx).await.unwrap();
+    process_transaction(mainnet, &ctx).await.unwrap();
+
+    let rows = storage.transactions_for(&moved_signature);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(&*rows[0].protocol_name, "pump_fun");
+    assert_eq!(rows[0].block_time, 1_700_000_400);
+    assert_eq!(rows[0].epoch, 2);
+    assert!(storage.transactions_for(&mainnet_signature).is_empty());
+
+    let missing_genesis = NetworkConfig {
+        name: "soon".to_string(),
+        ..Default::default()
+    };
+    assert!(Network::from_config(&missing_genesis).is_err());
+}
+
+#[tokio::test]
+async fn test_log_invocations_for_failed_parses() {
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    ctx.log_invocations = true;
+
+    let mut tx = common::pump_fun_unknown_tx(377107391, 2);
+    tx.transaction_status_meta.log_messages = Some(
+        [
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]",
+            "Program log: Instruction: Unknown",
+            "Program 11111111111111111111111111111111 invoke [2]",
+            "Program 11111111111111111111111111111111 success",
+            "Program data: vdt/007mYe4=",
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P consumed 12000 of 200000 compute units",
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P failed: custom program error: 0x1771",
+        ]
+        .map(String::from)
+        .to_vec(),
+    );
+    process_transaction(tx, &ctx).await.unwrap();
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+
+    let invocations = storage.log_invocations();
+    assert_eq!(invocations.len(), 2);
+    let (outer, inner) = (&invocations[0], &invocations[1]);
+    assert_eq!(outer.program_id, common::PUMP_FUN_PROGRAM_ID);
+    assert_eq!((outer.depth, outer.parent_index, outer.success), (1, -1, 0));
+    assert_eq!(outer.error, "custom program error: 0x1771");
+    assert_eq!((outer.compute_units, outer.compute_budget), (12_000, 200_000));
+    assert_eq!(outer.logs, vec!["Instruction: Unknown"]);
+    assert_eq!(outer.data, vec!["vdt/007mYe4="]);
+    assert_eq!((inner.depth, inner.parent_index, inner.success), (2, 0, 1));
+    assert_eq!(inner.instruction_index, 0);
+}
+
+#[tokio::test]
+async fn test_payload_limits_truncate_failed_rows() {
+    use solixdb_indexer::payload_limits::{PayloadLimits, TRUNCATION_MARKER};
+    use solixdb_indexer::raw_data::RawDataEncoding;
+
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    ctx.log_invocations = true;
+    ctx.payload_limits = PayloadLimits::new(200, 4);
+
+    let mut tx = common::pump_fun_unknown_tx(377107391, 2);
+    let long_log = format!("Program log: {}", "x".repeat(500));
+    tx.transaction_status_meta.log_messages = Some(vec![
+        "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]".to_string(),
+        long_log,
+        "Program data: vdt/007mYe4=".to_string(),
+        "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P failed: custom program error: 0x1771".to_string(),
+    ]);
+    process_transaction(tx, &ctx).await.unwrap();
+
+    let failed = storage.failed();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].truncated, 1);
+    assert_eq!(failed[0].log_messages.len(), 200);
+    assert!(failed[0].log_messages.ends_with(TRUNCATION_MARKER));
+    assert!(failed[0].log_messages.starts_with("Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]\n"));
+    let encoding = RawDataEncoding::parse(&failed[0].raw_data_encoding).unwrap();
+    assert_eq!(encoding.decode(&failed[0].raw_data), Some(vec![0xde, 0xad, 0xbe, 0xef]));
+
+    let invocations = storage.log_invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].truncated, 1);
+    assert_eq!(invocations[0].logs.len(), 1);
+    assert_eq!(invocations[0].logs[0].len(), 200);
+    assert_eq!(invocations[0].data, vec!["vdt/"]);
+
+    // Nothing is cut (or flagged) within the limits
+    let storage = Arc::new(MemoryStorage::new());
+    let ctx = common::processing_context(storage.clone());
+    process_transaction(common::pump_fun_unknown_tx(377107391, 2), &ctx)
+        .await
+        .unwrap();
+    assert_eq!(storage.failed()[0].truncated, 0);
+}
+
+#[tokio::test]
+async fn test_failed_rows_cap() {
+    use solixdb_indexer::failed_rows::FailedRowLimiter;
+
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    ctx.failed_rows = FailedRowLimiter::new(0, 2, 1.0);
+
+    for signature_byte in 1..=5 {
+        process_transaction(common::pump_fun_unknown_tx(377107391, signature_byte), &ctx)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(storage.failed().len(), 2);
+    assert_eq!(ctx.metrics["pump_fun"].1.load(std::sync::atomic::Ordering::Relaxed), 5);
+    assert_eq!(ctx.failed_rows.suppressed(), vec![("pump_fun".to_string(), 3, 0)]);
+}
+
+#[tokio::test]
+async fn test_failed_row_sampling_is_independent_of_protocol_sampling() {
+    use solixdb_indexer::config::ProtocolConfig;
+    use solixdb_indexer::failed_rows::FailedRowLimiter;
+    use solixdb_indexer::fixtures::{sample_by_signature, SampleWindow};
+
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    let protocol = ProtocolConfig {
+        sample_rate: 0.5,
+        ..Default::default()
+    };
+    ctx.protocols.insert("pump_fun".to_string(), protocol);
+    ctx.failed_rows = FailedRowLimiter::new(0, 0, 0.5);
+
+    // Signatures with unrelated bytes throughout, as real ones have
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut sampled = 0u64;
+    for i in 0..400u64 {
+        let mut bytes = [0u8; 64];
+        for chunk in bytes.chunks_mut(8) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            chunk.copy_from_slice(&state.to_le_bytes());
+        }
+        if sample_by_signature(&bytes, SampleWindow::Protocol, 0.5) {
+            sampled += 1;
+        }
+        let mut tx = common::pump_fun_unknown_tx(377107391 + i, 1);
+        tx.signature = solana_signature::Signature::from(bytes);
+        tx.transaction.signatures = vec![tx.signature];
+        process_transaction(tx, &ctx).await.unwrap();
+    }
+
+    // About half of the ~200 sampled transactions; were the two samples the same,
+    // every sampled failure would be stored
+    let stored = storage.failed().len() as u64;
+    assert!((150..=250).contains(&sampled), "sampled {}", sampled);
+    assert!(stored > sampled / 4 && stored < sampled * 3 / 4, "stored {} of {}", stored, sampled);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parse_pool_matches_inline() {
+    use solixdb_indexer::parse_pool::ParsePool;
+
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    ctx.parse_pool = Some(ParsePool::new(2, 1).unwrap());
+
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+    process_transaction(common::pump_fun_unknown_tx(377107391, 2), &ctx)
+        .await
+        .unwrap();
+
+    let transactions = storage.transactions();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(&*transactions[0].instruction_type, "Sell");
+    assert_eq!(storage.failed().len(), 1);
+}
+
+#[tokio::test]
+async fn test_slot_time_oracle_block_times() {
+    use solixdb_indexer::network::Network;
+    use solixdb_indexer::slot_time::SlotTimeOracle;
+
+    let oracle = Arc::new(SlotTimeOracle::new(Network::solana()));
+    oracle.record(1_000, 1_700_000_000);
+    oracle.record(1_100, 1_700_000_050);
+
+    let storage = Arc::new(MemoryStorage::new());
+    let mut ctx = common::processing_context(storage.clone());
+    ctx.slot_times = Some(Arc::clone(&oracle));
+
+    // Exact, interpolated between anchors, and extrapolated past the last one at 400 ms per slot
+    for (slot, signature_byte) in [(1_000, 1), (1_040, 2), (1_200, 3)] {
+        process_transaction(common::pump_fun_sell_tx(slot, signature_byte, 1_000_000, 5_000), &ctx)
+            .await
+            .unwrap();
+    }
+
+    let mut block_times: Vec<_> = storage.transactions().iter().map(|tx| (tx.slot, tx.block_time)).collect();
+    block_times.sort();
+    assert_eq!(
+        block_times,
+        vec![(1_000, 1_700_000_000), (1_040, 1_700_000_020), (1_200, 1_700_000_090)]
+    );
+    // Without anchors it's the genesis estimate
+    assert_eq!(
+        SlotTimeOracle::new(Network::solana()).block_time(1_000),
+        Network::solana().block_time(1_000)
+    );
+}
+
+#[tokio::test]
+async fn test_launchpad_trades() {
+    let storage = Arc::new(MemoryStorage::new());
+    let ctx = common::processing_context(storage.clone());
+
+    // Boop.fun buy_token: exactly 2 SOL in, at least 1,000 tokens out
+    let mut data = vec![0x8a, 0x7f, 0x0e, 0x5b, 0x26, 0x57, 0x73, 0x69];
+    data.extend_from_slice(&2_000_000_000u64.to_le_bytes());
+    data.extend_from_slice(&1_000u64.to_le_bytes());
+    let boop = common::single_instruction_tx(377107390, 1, "boop8hVGQGqehUK2iVEMEnMrL5RbjywRzHKBmBE7ry4", 13, data);
+    process_transaction(boop, &ctx).await.unwrap();
+
+    // Moonshot sell with fixed_side 0: exactly 5,000 tokens in for about 0.1 SOL
+    let mut data = vec![0x33, 0xe6, 0x85, 0xa4, 0x01, 0x7f, 0x83, 0xad];
+    data.extend_from_slice(&5_000u64.to_le_bytes());
+    data.extend_from_slice(&100_000_000u64.to_le_bytes());
+    data.push(0);
+    data.extend_from_slice(&50u64.to_le_bytes());
+    let moonshot = common::single_instruction_tx(377107391, 2, "MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG", 11, data);
+    process_transaction(moonshot, &ctx).await.unwrap();
+
+    let mut trades = storage.trades();
+    trades.sort_by_key(|t| t.slot);
+    let address = |byte| solixdb_indexer::alt::address_to_string(&common::address(byte));
+    let summary: Vec<_> = trades
+        .iter()
+        .map(|t| (t.protocol_name.as_str(), t.side.as_str(), t.token_amount, t.sol_amount, t.exact_tokens))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![("boop", "buy", 1_000, 2_000_000_000, 0), ("moonshot", "sell", 5_000, 100_000_000, 1)]
+    );
+    // Boop: mint is account 0, the buyer account 6; Moonshot: sender 0, curve 2, mint 6
+    assert_eq!((trades[0].mint.clone(), trades[0].user.clone()), (address(1), address(7)));
+    assert_eq!(
+        (trades[1].user.clone(), trades[1].bonding_curve.clone(), trades[1].mint.clone()),
+        (address(1), address(3), address(7))
+    );
+    assert!(storage.failed().is_empty());
+}
+
+#[tokio::test]
+async fn test_meteora_dbc_trade_side_and_launchpad() {
+    let storage = Arc::new(MemoryStorage::new());
+    let ctx = common::processing_context(storage.clone());
+
+    // swap: exactly 1 SOL from the payer's quote account, at least 250,000 tokens
+    // into its base account. Account i is address(i + 1) apart from those two:
+    // config 1, pool 2, base mint 7, quote mint 8 (wrapped SOL), payer 9, token programs 10/11
+    let wsol = common::program_address("So11111111111111111111111111111111111111112");
+    let pubkey = |address: solana_address::Address| solixdb_decoder::Pubkey::from(address.to_bytes());
+    let ata = |mint: solana_address::Address, token_program: u8| {
+        let owner = pubkey(common::address(10));
+        let ata = solixdb_decoder::events::associated_token_address(
+            &owner,
+            &pubkey(mint),
+            &pubkey(common::address(token_program)),
+        );
+        solana_address::Address::from(ata.0)
+    };
+    let swap_tx = |slot, signature_byte, quote_mint| {
+        let mut accounts: Vec<_> = (0..15u8).map(|i| common::address(i + 1)).collect();
+        accounts[8] = quote_mint;
+        (accounts[3], accounts[4]) = (ata(quote_mint, 12), ata(common::address(8), 11));
+        let mut data = vec![0xf8, 0xc6, 0x9e, 0x91, 0xe1, 0x75, 0x87, 0xc8];
+        data.extend_from_slice(&1_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&250_000u64.to_le_bytes());
+        common::instruction_tx(slot, signature_byte, "dbcij3LWUppWqq96dh6gJWwBifmcGfLSB5D4DuSMaqN", accounts, data)
+    };
+    process_transaction(swap_tx(377107392, 3, wsol), &ctx).await.unwrap();
+    // Curves quoted in another token aren't trades against SOL
+    process_transaction(swap_tx(377107393, 4, common::address(9)), &ctx).await.unwrap();
+
+    let trades = storage.trades();
+    assert_eq!(trades.len(), 1);
+    let address = |byte| solixdb_indexer::alt::address_to_string(&common::address(byte));
+    let t = &trades[0];
+    assert_eq!((t.side.as_str(), t.token_amount, t.sol_amount, t.exact_tokens), ("buy", 250_000, 1_000_000_000, 0));
+    assert_eq!(t.sol_amount_ui, Some(1.0));
+    assert_eq!((t.mint.clone(), t.bonding_curve.clone(), t.user.clone()), (address(8), address(3), address(10)));
+    // Shared launchpad: the partner config tells the frontends apart
+    assert_eq!((t.protocol_name.as_str(), t.launchpad.clone()), ("meteora_dbc", address(2)));
+    assert!(storage.failed().is_empty());
+}
+
+#[tokio::test]
+async fn test_pump_amm_trades_continue_the_curve_series() {
+    let storage = Arc::new(MemoryStorage::new());
+    let ctx = common::processing_context(storage.clone());
+
+    // Bonding curve sell of the mint at account 2 (address 3)
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 2_000_000, 10_000_000), &ctx)
+        .await
+        .unwrap();
+    // After migration, PumpSwap sell: exactly 1,000,000 of it for at least 0.004 SOL. Pool is
+    // account 0, user 1, base mint 3 and quote mint 4 (WSOL)
+    let mut accounts: Vec<_> = (0..21u8).map(|i| common::address(i + 1)).collect();
+    accounts[3] = common::address(3);
+    accounts[4] = common::program_address("So11111111111111111111111111111111111111112");
+    let mut data = vec![0x33, 0xe6, 0x85, 0xa4, 0x01, 0x7f, 0x83, 0xad];
+    data.extend_from_slice(&1_000_000u64.to_le_bytes());
+    data.extend_from_slice(&4_000_000u64.to_le_bytes());
+    let amm = common::instruction_tx(377107391, 2, "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA", accounts, data);
+    process_transaction(amm, &ctx).await.unwrap();
+
+    let mint = solixdb_indexer::alt::address_to_string(&common::address(3));
+    let mut trades = storage.trades();
+    trades.sort_by_key(|t| t.slot);
+    let series: Vec<_> = trades
+        .iter()
+        .filter(|t| t.mint == mint)
+        .map(|t| (t.protocol_name.as_str(), t.venue.as_str(), t.side.as_str(), t.token_amount, t.sol_amount))
+        .collect();
+    assert_eq!(
+        series,
+        vec![
+            ("pump_fun", "bonding_curve", "sell", 2_000_000, 10_000_000),
+            ("pump_amm", "amm", "sell", 1_000_000, 4_000_000),
+        ]
+    );
+    let pool = solixdb_indexer::alt::address_to_string(&common::address(1));
+    assert_eq!((trades[1].bonding_curve.clone(), trades[1].exact_tokens), (pool, 1));
+    // Still in swaps as well
+    assert_eq!(storage.swaps().len(), 1);
+    assert!(storage.failed().is_empty());
+}
+
+#[tokio::test]
+async fn test_ui_amounts_from_known_decimals() {
+    let storage = Arc::new(MemoryStorage::new());
+    let ctx = common::processing_context(storage.clone());
+    // As if an earlier transaction's token balances reported the mint (account 2 of a sell)
+    let mint = solixdb_indexer::alt::address_to_string(&common::address(3));
+    ctx.decimals.observe([(mint.as_str(), 6)]);
+
+    process_transaction(common::pump_fun_sell_tx(377107393, 1, 2_500_000, 500_000_000), &ctx)
+        .await
+        .unwrap();
+
+    let trades = storage.trades();
+    assert_eq!(trades.len(), 1);
+    assert_eq!((trades[0].token_amount_ui, trades[0].sol_amount_ui), (Some(2.5), Some(0.5)));
+    assert_eq!(ctx.decimals.ui_amount("unseen", 1), None);
+}
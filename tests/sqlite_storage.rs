@@ -0,0 +1,29 @@
+//! Fixture transactions -> process_transaction -> SqliteStorage rows
+//!
+//! Needs the `sqlite` feature: `cargo test --features sqlite --test sqlite_storage`
+
+mod common;
+
+use solixdb_indexer::helpers::process_transaction;
+use solixdb_indexer::sqlite::SqliteStorage;
+use solixdb_indexer::storage::Storage;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_sqlite_storage_writes_core_tables() {
+    let storage = Arc::new(SqliteStorage::open(":memory:").unwrap());
+    let ctx = common::processing_context(storage.clone());
+
+    process_transaction(common::pump_fun_sell_tx(377107390, 1, 1_000_000, 5_000), &ctx)
+        .await
+        .unwrap();
+    process_transaction(common::pump_fun_unknown_tx(377107391, 2), &ctx)
+        .await
+        .unwrap();
+    storage.flush_all().await.unwrap();
+
+    // The sell is stored and its trade lands in protocol_events; the unknown
+    // instruction only has a failed_transactions row, which isn't kept
+    assert_eq!(storage.count("transactions").unwrap(), 1);
+    assert_eq!(storage.count("protocol_events").unwrap(), 1);
+}